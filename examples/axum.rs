@@ -0,0 +1,79 @@
+//! Serve a Router over HTTP with `axum`, using the helpers of `jrpc::http`.
+//!
+//! Run with `cargo run --example axum --features http`, which sends a few requests through the
+//! app and prints the responses. Add `-- serve` to listen on `127.0.0.1:3000` instead:
+//!
+//! ```text
+//! curl -H 'Content-Type: application/json' \
+//!     -d '{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}' \
+//!     http://127.0.0.1:3000/rpc
+//! ```
+extern crate axum;
+extern crate jrpc;
+extern crate tokio;
+extern crate tower;
+
+use std::future::{self, IntoFuture, Ready};
+use std::sync::Arc;
+
+use axum::body::{self, Body, Bytes};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Request};
+use axum::response::Response;
+use axum::routing::post;
+use jrpc::router::Router;
+use tower::ServiceExt;
+
+type Rpc = Arc<Router<()>>;
+
+/// The handler of `POST /rpc`. Handling is synchronous, so the future is already resolved.
+fn rpc(State(router): State<Rpc>, headers: HeaderMap, body: Bytes) -> Ready<Response> {
+    future::ready(jrpc::http::handle(&router, &(), &headers, &body).map(Body::from))
+}
+
+fn app() -> axum::Router {
+    let mut router = Router::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+    router.register("log", |_: &(), (message,): (String,)| {
+        println!("log: {}", message);
+        Ok(())
+    });
+    axum::Router::new().route("/rpc", post(rpc)).with_state(Arc::new(router))
+}
+
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    if std::env::args().any(|arg| arg == "serve") {
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:3000"))
+            .unwrap();
+        println!("listening on http://127.0.0.1:3000/rpc");
+        runtime.block_on(axum::serve(listener, app()).into_future()).unwrap();
+        return;
+    }
+
+    let bodies = [
+        ("application/json", r#"{"jsonrpc": "2.0", "method": "subtract", "params": [4, 2],
+            "id": 1}"#),
+        ("application/json", r#"{"jsonrpc": "2.0", "method": "log", "params": ["hello"]}"#),
+        ("application/json", r#"[
+            {"jsonrpc": "2.0", "method": "subtract", "params": [1, 2], "id": 2},
+            {"jsonrpc": "2.0", "method": "divide", "params": [1, 2], "id": 3},
+            {"jsonrpc": "2.0", "method": "log", "params": ["batched"]}
+        ]"#),
+        ("application/json", r#"{"jsonrpc": "2.0", "method"#),
+        ("text/plain", r#"{"jsonrpc": "2.0", "method": "subtract", "params": [1, 2], "id": 4}"#),
+    ];
+    for &(content_type, json) in bodies.iter() {
+        let request = Request::post("/rpc")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(json))
+            .unwrap();
+        let response = runtime.block_on(app().oneshot(request)).unwrap();
+        let status = response.status();
+        let body = runtime
+            .block_on(body::to_bytes(response.into_body(), usize::MAX))
+            .unwrap();
+        println!("--> {}\n<-- {} {}\n", json, status, String::from_utf8_lossy(&body));
+    }
+}
@@ -0,0 +1,68 @@
+//! A Client and a Server sharing the methods defined with `rpc_methods!`.
+//!
+//! Run with `cargo run --example calculator`.
+#[macro_use]
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::{ErrorCode, Id, Response, Value};
+
+rpc_methods! {
+    /// The methods of the calculator.
+    pub enum Calc {
+        /// Subtract `subtrahend` from `minuend`.
+        Subtract = "subtract" => fn subtract(SubtractParams {
+            minuend: i64,
+            subtrahend: i64,
+        }) -> i64;
+        /// Sum all of the `values`.
+        Sum = "sum" => fn sum(SumParams { values: Vec<i64> }) -> i64;
+        /// Log a message on the Server.
+        Log = "log" => notify log(LogParams { message: String });
+    }
+}
+
+/// Handle a single request, returning the reply (if any).
+fn serve(json: &str) -> Option<String> {
+    let request = match jrpc::parse_request::<Calc>(json) {
+        Ok(r) => r,
+        Err(err) => return Some(err.to_string()),
+    };
+    let params = request.params.unwrap_or(Value::Null);
+    let result = match request.method {
+        Calc::Subtract => serde_json::from_value::<SubtractParams>(params)
+            .map(|p| Value::from(p.minuend - p.subtrahend)),
+        Calc::Sum => serde_json::from_value::<SumParams>(params)
+            .map(|p| Value::from(p.values.iter().sum::<i64>())),
+        Calc::Log => {
+            if let Ok(p) = serde_json::from_value::<LogParams>(params) {
+                println!("server log: {}", p.message);
+            }
+            return None;
+        }
+    };
+
+    let id = request.id.into_id()?;
+    let response = match result {
+        Ok(result) => Response::success(id, result),
+        Err(err) => Response::error(id, ErrorCode::InvalidParams, err.to_string(), None),
+    };
+    Some(response.to_string())
+}
+
+fn main() {
+    let request = Calc::subtract(Id::from(1), 42, 23);
+    let reply = serve(&serde_json::to_string(&request).unwrap()).unwrap();
+    let difference = jrpc::decode_response::<SubtractParams>(&reply).unwrap();
+    println!("42 - 23 = {}", difference);
+
+    let request = Calc::sum(Id::from(2), vec![1, 2, 4]);
+    let reply = serve(&serde_json::to_string(&request).unwrap()).unwrap();
+    let sum = jrpc::decode_response::<SumParams>(&reply).unwrap();
+    println!("1 + 2 + 4 = {}", sum);
+
+    let notification = Calc::log("hello".to_string());
+    assert!(serve(&serde_json::to_string(&notification).unwrap()).is_none());
+}
@@ -0,0 +1,26 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate jrpc;
+
+use jrpc::router::{self, Router};
+use jrpc::{Response, Value};
+
+fuzz_target!(|data: &[u8]| {
+    let json = match ::std::str::from_utf8(data) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    let echo = |value: Value| match jrpc::parse_request_value::<String>(value) {
+        Ok(request) => {
+            let id = request.id.into_id()?;
+            Some(Response::success(id, request.params.unwrap_or(Value::Null)))
+        }
+        Err(error) => Some(Response::Err(error)),
+    };
+    let _ = router::handle_batch(echo, json);
+
+    let mut router = Router::new();
+    router.register_fn("subtract", |(a, b): (i64, i64)| Ok(a.wrapping_sub(b)));
+    let _ = router.handle_batch(&(), json);
+});
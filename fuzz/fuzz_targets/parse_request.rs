@@ -0,0 +1,25 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate jrpc;
+
+use jrpc::{ParseOptions, Value};
+
+fuzz_target!(|data: &[u8]| {
+    let json = match ::std::str::from_utf8(data) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    let _ = jrpc::parse_request::<String>(json);
+    let _ = jrpc::parse_request::<Value>(json);
+    let _ = jrpc::parse_request_exact::<String>(json);
+    let options = ParseOptions::strict()
+        .max_depth(8)
+        .max_method_len(16)
+        .max_id_len(16)
+        .max_message_len(Some(16))
+        .full_message_in_data(true);
+    if let Ok(request) = jrpc::parse_request_with::<String>(json, &options) {
+        let _ = request.to_string();
+    }
+});
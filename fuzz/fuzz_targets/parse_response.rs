@@ -0,0 +1,23 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate jrpc;
+
+use jrpc::client::ClientState;
+use jrpc::{Id, Value};
+
+fuzz_target!(|data: &[u8]| {
+    let json = match ::std::str::from_utf8(data) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    let _ = jrpc::parse_response_exact::<Value>(json);
+    let _ = jrpc::parse_response_lenient::<Value>(json);
+    if let Ok(response) = jrpc::parse_response_exact::<Value>(json) {
+        let _ = response.to_string();
+    }
+
+    let mut client: ClientState<()> = ClientState::new();
+    let _ = client.pending_mut().register(Id::from(1), ());
+    let _ = client.on_message(json);
+});
@@ -0,0 +1,498 @@
+//! Builders checking the requirements of the spec when building the messages.
+
+use std::error;
+use std::fmt;
+
+use super::*;
+
+/// Build a `Request<String, Value>`, checking the requirements of the spec in
+/// [`build`](#method.build). See [`Request::builder`](struct.Request.html#method.builder).
+///
+/// The builder must be given a method and either an id or
+/// [`notification`](#method.notification). The params are optional, but must be Structured (an
+/// Array or an Object) when given.
+#[derive(Debug, Clone, Default)]
+pub struct RequestBuilder {
+    method: Option<String>,
+    params: Option<Result<Value, String>>,
+    id: Option<Id>,
+    notification: bool,
+    allow_reserved: bool,
+}
+
+impl Request<String, Value> {
+    /// Build a Request, validating it when it is built.
+    ///
+    /// This is the same as the constructors ([`new`](#method.new) and
+    /// [`with_params`](#method.with_params)), with the checks of [`RequestBuilder::build`].
+    ///
+    /// [`RequestBuilder::build`]: struct.RequestBuilder.html#method.build
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{BuildError, Request};
+    ///
+    /// # fn main() {
+    /// let request = Request::builder()
+    ///     .method("subtract")
+    ///     .positional_params([42, 23])
+    ///     .id(4)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     request.to_string(),
+    ///     r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":4}"#,
+    /// );
+    ///
+    /// let error = Request::builder().method("subtract").params(42).notification().build();
+    /// assert_eq!(error.unwrap_err(), BuildError::ParamsNotStructured("a number"));
+    /// # }
+    /// ```
+    pub fn builder() -> RequestBuilder {
+        RequestBuilder::default()
+    }
+}
+
+impl RequestBuilder {
+    /// The name of the method to call.
+    pub fn method<S: Into<String>>(mut self, method: S) -> RequestBuilder {
+        self.method = Some(method.into());
+        self
+    }
+
+    /// The params, which must serialize to an Array or an Object.
+    pub fn params<P: Serialize>(mut self, params: P) -> RequestBuilder {
+        self.params = Some(serde_json::to_value(params).map_err(|err| err.to_string()));
+        self
+    }
+
+    /// The params by-position, as an Array.
+    pub fn positional_params<I>(mut self, params: I) -> RequestBuilder
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        let params: Result<Vec<Value>, _> = params.into_iter().map(serde_json::to_value).collect();
+        self.params = Some(params.map(Value::Array).map_err(|err| err.to_string()));
+        self
+    }
+
+    /// The params by-name, as an Object.
+    pub fn named_params<I, K, V>(mut self, params: I) -> RequestBuilder
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Serialize,
+    {
+        let params: Result<serde_json::Map<String, Value>, _> = params
+            .into_iter()
+            .map(|(key, value)| serde_json::to_value(value).map(|value| (key.into(), value)))
+            .collect();
+        self.params = Some(params.map(Value::Object).map_err(|err| err.to_string()));
+        self
+    }
+
+    /// The id of the Request, the Server will reply to it.
+    pub fn id<I: Into<Id>>(mut self, id: I) -> RequestBuilder {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Make the Request a Notification, the Server won't reply to it.
+    pub fn notification(mut self) -> RequestBuilder {
+        self.notification = true;
+        self
+    }
+
+    /// Allow the methods reserved for system extensions (starting with `rpc.`, see
+    /// [`is_reserved_method`](fn.is_reserved_method.html)), i.e. to call `rpc.discover`.
+    pub fn allow_reserved(mut self) -> RequestBuilder {
+        self.allow_reserved = true;
+        self
+    }
+
+    /// Build the Request, failing if:
+    ///
+    /// - There is no method, or it is reserved for system extensions (unless
+    ///   [`allow_reserved`](#method.allow_reserved)).
+    /// - There is neither an id nor `notification`, or there are both.
+    /// - The params failed to serialize, or are not an Array or an Object.
+    pub fn build(self) -> Result<Request<String, Value>, BuildError> {
+        let method = match self.method {
+            Some(method) => method,
+            None => return Err(BuildError::MissingMethod),
+        };
+        if !self.allow_reserved && is_reserved_method(&method) {
+            return Err(BuildError::ReservedMethod(method));
+        }
+        let id = match (self.id, self.notification) {
+            (Some(id), false) => IdReq::from(id),
+            (None, true) => IdReq::Notification,
+            (None, false) => return Err(BuildError::MissingId),
+            (Some(_), true) => return Err(BuildError::IdAndNotification),
+        };
+        let params = match self.params {
+            None => None,
+            Some(Err(message)) => return Err(BuildError::Params(message)),
+            Some(Ok(params)) => match params {
+                Value::Array(_) | Value::Object(_) => Some(params),
+                Value::Null => return Err(BuildError::ParamsNotStructured("null")),
+                Value::Bool(_) => return Err(BuildError::ParamsNotStructured("a boolean")),
+                Value::Number(_) => return Err(BuildError::ParamsNotStructured("a number")),
+                Value::String(_) => return Err(BuildError::ParamsNotStructured("a string")),
+            },
+        };
+        Ok(Request {
+            jsonrpc: V2_0,
+            method: method,
+            params: params,
+            id: id,
+        })
+    }
+
+    /// Build the Request without any checks.
+    ///
+    /// A missing method is empty, a missing id is a Notification (an id wins over
+    /// `notification`) and params which failed to serialize are absent.
+    pub fn build_unchecked(self) -> Request<String, Value> {
+        Request {
+            jsonrpc: V2_0,
+            method: self.method.unwrap_or_default(),
+            params: self.params.and_then(Result::ok),
+            id: self.id.map(IdReq::from).unwrap_or(IdReq::Notification),
+        }
+    }
+}
+
+/// Build a `Response<Value>`: either a result or an error, checked in [`build`](#method.build).
+/// See [`Response::builder`](enum.Response.html#method.builder).
+#[derive(Debug, Clone, Default)]
+pub struct ResponseBuilder {
+    id: Option<Id>,
+    result: Option<Result<Value, String>>,
+    error: Option<(ErrorCode, Cow<'static, str>)>,
+    data: Option<Result<Value, String>>,
+}
+
+impl Response<Value> {
+    /// Build a Response, checking that it is either a result or an error when it is built.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{BuildError, ErrorCode, Id, Response};
+    ///
+    /// # fn main() {
+    /// let response = Response::builder().id(4).result(19).build().unwrap();
+    /// assert_eq!(response.to_string(), r#"{"jsonrpc":"2.0","result":19,"id":4}"#);
+    ///
+    /// let response = Response::builder()
+    ///     .id(Id::Null)
+    ///     .error(ErrorCode::ServerError(-32000), "BadIndexes")
+    ///     .data([1, 2, 3])
+    ///     .build()
+    ///     .unwrap();
+    /// jrpc::testing::assert_json_eq(
+    ///     &response.to_string(),
+    ///     r#"{"jsonrpc": "2.0", "error": {"code": -32000, "message": "BadIndexes",
+    ///         "data": [1, 2, 3]}, "id": null}"#,
+    /// );
+    ///
+    /// let error = Response::builder().id(4).result(19).error(ErrorCode::InternalError, "oops");
+    /// assert_eq!(error.build().unwrap_err(), BuildError::ResultAndError);
+    /// # }
+    /// ```
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::default()
+    }
+}
+
+impl ResponseBuilder {
+    /// The id of the Request, `Id::Null` if it couldn't be detected.
+    pub fn id<I: Into<Id>>(mut self, id: I) -> ResponseBuilder {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Reply with the `result`.
+    pub fn result<R: Serialize>(mut self, result: R) -> ResponseBuilder {
+        self.result = Some(serde_json::to_value(result).map_err(|err| err.to_string()));
+        self
+    }
+
+    /// Reply with an error.
+    pub fn error<C, S>(mut self, code: C, message: S) -> ResponseBuilder
+    where
+        C: Into<ErrorCode>,
+        S: Into<Cow<'static, str>>,
+    {
+        self.error = Some((code.into(), message.into()));
+        self
+    }
+
+    /// The `data` of the error.
+    pub fn data<D: Serialize>(mut self, data: D) -> ResponseBuilder {
+        self.data = Some(serde_json::to_value(data).map_err(|err| err.to_string()));
+        self
+    }
+
+    /// Build the Response, failing if:
+    ///
+    /// - There is no id.
+    /// - There is both a result and an error, or neither of them.
+    /// - There is `data` without an error.
+    /// - The error code is in the range reserved by the spec but not defined by it (see
+    ///   [`ErrorCode::try_from_code`](enum.ErrorCode.html#method.try_from_code)).
+    /// - The result or data failed to serialize.
+    pub fn build(self) -> Result<Response<Value>, BuildError> {
+        let id = match self.id {
+            Some(id) => id,
+            None => return Err(BuildError::MissingId),
+        };
+        match (self.result, self.error) {
+            (Some(_), Some(_)) => Err(BuildError::ResultAndError),
+            (None, None) => Err(BuildError::NoResultOrError),
+            (Some(result), None) => {
+                if self.data.is_some() {
+                    return Err(BuildError::DataWithoutError);
+                }
+                let result = result.map_err(BuildError::Result)?;
+                Ok(Response::Ok(Success::new(id, result)))
+            }
+            (None, Some((code, message))) => {
+                if let Err(UnknownReservedCode(code)) = ErrorCode::try_from_code(code.code()) {
+                    return Err(BuildError::UndefinedReservedCode(code));
+                }
+                let data = self.data.transpose().map_err(BuildError::Data)?;
+                Ok(Response::Err(Error::new(id, code, message, data)))
+            }
+        }
+    }
+}
+
+/// Build an `ErrorObject<Value>`, checking the error code in [`build`](#method.build). See
+/// [`ErrorObject::builder`](struct.ErrorObject.html#method.builder).
+#[derive(Debug, Clone)]
+pub struct ErrorObjectBuilder {
+    code: ErrorCode,
+    message: Option<Cow<'static, str>>,
+    data: Option<Result<Value, BuildError>>,
+    application_code: bool,
+}
+
+impl ErrorObject<Value> {
+    /// Build an error object with the `code`. The message is the name of the error in the spec
+    /// (i.e. `Invalid params`) unless one is given.
+    ///
+    /// The data is either given whole with [`data`](struct.ErrorObjectBuilder.html#method.data)
+    /// or member by member with [`detail`](struct.ErrorObjectBuilder.html#method.detail).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// extern crate serde_json;
+    /// use jrpc::{BuildError, ErrorCode, ErrorObject};
+    ///
+    /// # fn main() {
+    /// let error = ErrorObject::builder(ErrorCode::InvalidParams)
+    ///     .detail("field", "minuend")
+    ///     .detail("expected", "a number")
+    ///     .build()
+    ///     .unwrap();
+    /// jrpc::testing::assert_json_eq(
+    ///     &serde_json::to_string(&error).unwrap(),
+    ///     r#"{"code": -32602, "message": "Invalid params",
+    ///         "data": {"field": "minuend", "expected": "a number"}}"#,
+    /// );
+    ///
+    /// // the codes outside of the server errors must be allowed
+    /// let error = ErrorObject::builder(ErrorCode::from(42)).message("out of stock").build();
+    /// assert_eq!(error.unwrap_err(), BuildError::ApplicationCode(42));
+    /// let error = ErrorObject::builder(ErrorCode::from(42)).application_code().build().unwrap();
+    /// assert_eq!(error.message, "Server error");
+    /// # }
+    /// ```
+    pub fn builder<C: Into<ErrorCode>>(code: C) -> ErrorObjectBuilder {
+        ErrorObjectBuilder {
+            code: code.into(),
+            message: None,
+            data: None,
+            application_code: false,
+        }
+    }
+}
+
+impl ErrorObjectBuilder {
+    /// The message, instead of the name of the error.
+    pub fn message<S: Into<Cow<'static, str>>>(mut self, message: S) -> ErrorObjectBuilder {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// The `data`, replacing the previous data and details.
+    pub fn data<D: Serialize>(mut self, data: D) -> ErrorObjectBuilder {
+        let data = serde_json::to_value(data).map_err(|err| BuildError::Data(err.to_string()));
+        self.data = Some(data);
+        self
+    }
+
+    /// Set the member `key` of the data to the `value`, replacing a previous detail with the same
+    /// key. The data must be an Object.
+    ///
+    /// The members of the data are sorted by key, unless the `preserve_order` feature is enabled:
+    /// they are then in the order they were first set, a replaced detail keeping its place.
+    pub fn detail<K, V>(mut self, key: K, value: V) -> ErrorObjectBuilder
+    where
+        K: Into<String>,
+        V: Serialize,
+    {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(err) => {
+                self.data = Some(Err(BuildError::Data(err.to_string())));
+                return self;
+            }
+        };
+        let data = match self.data {
+            None => Ok(Value::Object(serde_json::Map::new())),
+            Some(data) => data,
+        };
+        self.data = Some(match data {
+            Ok(Value::Object(mut data)) => {
+                data.insert(key.into(), value);
+                Ok(Value::Object(data))
+            }
+            Ok(_) => Err(BuildError::DetailWithoutObject),
+            Err(error) => Err(error),
+        });
+        self
+    }
+
+    /// Record the `cause` and its sources (see `std::error::Error::source`) as the `causes`
+    /// detail: the messages of the chain, outermost first.
+    pub fn cause<E: error::Error + ?Sized>(self, cause: &E) -> ErrorObjectBuilder {
+        let mut causes = vec![cause.to_string()];
+        let mut source = cause.source();
+        while let Some(err) = source {
+            causes.push(err.to_string());
+            source = err.source();
+        }
+        self.detail("causes", causes)
+    }
+
+    /// Allow a code outside of the server errors (`-32099` to `-32000`) and of the errors
+    /// defined by the spec, i.e. a code of the application.
+    pub fn application_code(mut self) -> ErrorObjectBuilder {
+        self.application_code = true;
+        self
+    }
+
+    /// Build the error object, failing if:
+    ///
+    /// - The code is in the range reserved by the spec but not defined by it, or it is outside of
+    ///   the reserved range without [`application_code`](#method.application_code).
+    /// - The data (or a detail) failed to serialize.
+    /// - A detail was added to data which is not an Object.
+    pub fn build(self) -> Result<ErrorObject<Value>, BuildError> {
+        let code = self.code.code();
+        if let Err(UnknownReservedCode(code)) = ErrorCode::try_from_code(code) {
+            return Err(BuildError::UndefinedReservedCode(code));
+        }
+        let reserved = (ErrorCode::RESERVED_MIN..=ErrorCode::RESERVED_MAX).contains(&code);
+        if !reserved && !self.application_code {
+            return Err(BuildError::ApplicationCode(code));
+        }
+        Ok(ErrorObject {
+            message: self.message.unwrap_or(Cow::Borrowed(self.code.standard_message())),
+            code: self.code,
+            data: self.data.transpose()?,
+        })
+    }
+}
+
+/// Why a builder refused to build, see [`RequestBuilder::build`], [`ResponseBuilder::build`] and
+/// [`ErrorObjectBuilder::build`].
+///
+/// [`RequestBuilder::build`]: struct.RequestBuilder.html#method.build
+/// [`ResponseBuilder::build`]: struct.ResponseBuilder.html#method.build
+/// [`ErrorObjectBuilder::build`]: struct.ErrorObjectBuilder.html#method.build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// No method was given.
+    MissingMethod,
+    /// The method is reserved for system extensions.
+    ReservedMethod(String),
+    /// No id was given, nor `notification` for a Request.
+    MissingId,
+    /// Both an id and `notification` were given.
+    IdAndNotification,
+    /// The params are not Structured, with what they are instead (i.e. `"a number"`).
+    ParamsNotStructured(&'static str),
+    /// The params failed to serialize, with the message of the serialization error.
+    Params(String),
+    /// A Response was given both a result and an error.
+    ResultAndError,
+    /// A Response was given neither a result nor an error.
+    NoResultOrError,
+    /// A Response was given error `data` but no error.
+    DataWithoutError,
+    /// The error code is reserved by the spec but not defined by it.
+    UndefinedReservedCode(i64),
+    /// The result failed to serialize, with the message of the serialization error.
+    Result(String),
+    /// The error data failed to serialize, with the message of the serialization error.
+    Data(String),
+    /// The error code is outside of the reserved range but was not allowed as an application
+    /// code.
+    ApplicationCode(i64),
+    /// A detail was added to error data which is not an Object.
+    DetailWithoutObject,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::MissingMethod => write!(f, "the Request has no method"),
+            BuildError::ReservedMethod(ref method) => write!(
+                f,
+                "the method {:?} is reserved for system extensions, use allow_reserved to call it",
+                method
+            ),
+            BuildError::MissingId => {
+                write!(f, "an id (or notification() for a Request) is required")
+            }
+            BuildError::IdAndNotification => {
+                write!(f, "a Notification can't have an id, give only one of them")
+            }
+            BuildError::ParamsNotStructured(got) => {
+                write!(f, "params must be an array or an object, got {}", got)
+            }
+            BuildError::Params(ref message) => write!(f, "params failed to serialize: {}", message),
+            BuildError::ResultAndError => {
+                write!(f, "a Response has either a result or an error, not both")
+            }
+            BuildError::NoResultOrError => write!(f, "a Response needs a result or an error"),
+            BuildError::DataWithoutError => write!(f, "error data was given without an error"),
+            BuildError::UndefinedReservedCode(code) => {
+                write!(f, "the error code {} is reserved but not defined", code)
+            }
+            BuildError::Result(ref message) => write!(f, "result failed to serialize: {}", message),
+            BuildError::Data(ref message) => write!(f, "data failed to serialize: {}", message),
+            BuildError::ApplicationCode(code) => write!(
+                f,
+                "the error code {} is not a server error (-32099 to -32000), \
+                 use application_code to allow it",
+                code
+            ),
+            BuildError::DetailWithoutObject => {
+                write!(f, "a detail can only be added to data which is an object")
+            }
+        }
+    }
+}
+
+impl error::Error for BuildError {}
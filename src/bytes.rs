@@ -0,0 +1,98 @@
+//! Serializing into a `bytes::BytesMut` and parsing from `bytes::Bytes`, for network stacks built
+//! on `bytes`.
+//!
+//! Only available with the `bytes` feature.
+
+use bytes_crate::{BufMut, Bytes, BytesMut};
+
+use super::*;
+
+/// Append the json of `value` to the `buf`, without going through a String.
+///
+/// On failure the `buf` is left as it was.
+fn write_json<S: Serialize>(value: &S, buf: &mut BytesMut) -> serde_json::Result<()> {
+    let len = buf.len();
+    let result = serde_json::to_writer(buf.writer(), value);
+    if result.is_err() {
+        buf.truncate(len);
+    }
+    result
+}
+
+impl<M: Serialize, T: Serialize> Request<M, T> {
+    /// Append the json of the Request to the `buf`, the same as
+    /// [`to_string`](#method.to_string).
+    ///
+    /// On failure the `buf` is left as it was.
+    ///
+    /// ```rust
+    /// # extern crate bytes;
+    /// # extern crate jrpc;
+    /// use bytes::BytesMut;
+    /// use jrpc::{Id, Request};
+    ///
+    /// # fn main() {
+    /// let request = Request::with_params(Id::from(1), "subtract".to_string(), vec![42, 23]);
+    /// let mut buf = BytesMut::new();
+    /// request.to_bytes(&mut buf).unwrap();
+    /// assert_eq!(&buf[..], request.to_string().as_bytes());
+    /// # }
+    /// ```
+    pub fn to_bytes(&self, buf: &mut BytesMut) -> serde_json::Result<()> {
+        write_json(self, buf)
+    }
+}
+
+impl<T: Serialize> Response<T> {
+    /// Append the json of the Response to the `buf`, see
+    /// [`Request::to_bytes`](struct.Request.html#method.to_bytes).
+    pub fn to_bytes(&self, buf: &mut BytesMut) -> serde_json::Result<()> {
+        write_json(self, buf)
+    }
+}
+
+impl<T: Serialize> Success<T> {
+    /// Append the json of the Success to the `buf`, see
+    /// [`Request::to_bytes`](struct.Request.html#method.to_bytes).
+    pub fn to_bytes(&self, buf: &mut BytesMut) -> serde_json::Result<()> {
+        write_json(self, buf)
+    }
+}
+
+impl<T: Serialize> Error<T> {
+    /// Append the json of the Error to the `buf`, see
+    /// [`Request::to_bytes`](struct.Request.html#method.to_bytes).
+    pub fn to_bytes(&self, buf: &mut BytesMut) -> serde_json::Result<()> {
+        write_json(self, buf)
+    }
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) but from the bytes received, which are
+/// a `ParseError` if they are not UTF-8.
+///
+/// ```rust
+/// # extern crate bytes;
+/// # extern crate jrpc;
+/// use bytes::Bytes;
+/// use jrpc::ErrorCode;
+///
+/// # fn main() {
+/// let json = Bytes::from_static(br#"{"jsonrpc": "2.0", "method": "sum", "id": 1}"#);
+/// let request = jrpc::parse_request_bytes::<String>(&json).unwrap();
+/// assert_eq!(request.method, "sum");
+///
+/// let error = jrpc::parse_request_bytes::<String>(&Bytes::from_static(b"\xff")).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::ParseError);
+/// # }
+/// ```
+pub fn parse_request_bytes<M>(json: &Bytes) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    let json = ::std::str::from_utf8(json).map_err(|err| {
+        let message = format!("request is not UTF-8: {}", err);
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+        Error::new(Id::Null, ErrorCode::ParseError, message, None)
+    })?;
+    parse_request(json)
+}
@@ -0,0 +1,160 @@
+//! Keys for memoizing the results of idempotent Requests and deduplicating them.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use super::*;
+
+/// The 64 bit FNV-1a hash: simple and, unlike `DefaultHasher`, the same across runs and Rust
+/// versions.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write the json of the `value` with the keys of the Objects sorted, whatever the order they
+/// were inserted in.
+fn write_canonical<W: Write>(w: &mut W, value: &Value) -> io::Result<()> {
+    match *value {
+        Value::Array(ref values) => {
+            w.write_all(b"[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                write_canonical(w, value)?;
+            }
+            w.write_all(b"]")
+        }
+        Value::Object(ref object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            w.write_all(b"{")?;
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *w, key)?;
+                w.write_all(b":")?;
+                write_canonical(w, value)?;
+            }
+            w.write_all(b"}")
+        }
+        ref value => serde_json::to_writer(w, value).map_err(io::Error::from),
+    }
+}
+
+impl<M: Serialize, T: Serialize> Request<M, T> {
+    /// A key for caching the result of the Request: the hash of the method and the params,
+    /// without the `id` (and `jsonrpc`).
+    ///
+    /// The params are hashed as json with the keys of the Objects sorted, so the order of the
+    /// members doesn't matter. The key is the same across runs and platforms, so it can be
+    /// stored. It only compares the json: by-position and by-name params which mean the same to
+    /// the method have different keys.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// extern crate serde_json;
+    /// use jrpc::{Id, Request, Value};
+    ///
+    /// # fn main() {
+    /// let a: Request<String, Value> = serde_json::from_str(
+    ///     r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 2}, "id": 1}"#,
+    /// ).unwrap();
+    /// let b: Request<String, Value> = serde_json::from_str(
+    ///     r#"{"jsonrpc": "2.0", "method": "get", "params": {"b": 2, "a": 1}, "id": 2}"#,
+    /// ).unwrap();
+    /// assert_eq!(a.cache_key(), b.cache_key());
+    ///
+    /// let c = Request::with_params(Id::from(1), "get".to_string(), [1, 2]);
+    /// assert_ne!(a.cache_key(), c.cache_key());
+    /// # }
+    /// ```
+    pub fn cache_key(&self) -> u64 {
+        hash_content(&self.content())
+    }
+
+    /// The hash of the method and the params, i.e. to find the same logical Request sent twice
+    /// with different ids. It is the [`cache_key`](#method.cache_key).
+    ///
+    /// See [`dedup_by_content`](fn.dedup_by_content.html) to remove the duplicates.
+    pub fn content_hash(&self) -> u64 {
+        self.cache_key()
+    }
+
+    fn content(&self) -> (Value, Option<Value>) {
+        let method = serde_json::to_value(&self.method).unwrap_or(Value::Null);
+        let params = self
+            .params
+            .as_ref()
+            .map(|params| serde_json::to_value(params).unwrap_or(Value::Null));
+        (method, params)
+    }
+}
+
+fn hash_content(content: &(Value, Option<Value>)) -> u64 {
+    let mut hash = Fnv1a::new();
+    // writing to the hash never fails, neither does serializing a `Value`
+    let _ = write_content(&mut hash, content);
+    hash.0
+}
+
+fn write_content<W: Write>(w: &mut W, content: &(Value, Option<Value>)) -> io::Result<()> {
+    write_canonical(w, &content.0)?;
+    if let Some(ref params) = content.1 {
+        w.write_all(b",")?;
+        write_canonical(w, params)?;
+    }
+    Ok(())
+}
+
+/// Remove the Requests with the same method and params (whatever the order of the members of
+/// their Objects) as an earlier one, keeping the first of them. The ids are ignored.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{Id, Request};
+///
+/// # fn main() {
+/// let mut requests = vec![
+///     Request::with_params(Id::from(1), "sum".to_string(), vec![1, 2]),
+///     Request::with_params(Id::from(2), "sum".to_string(), vec![3]),
+///     Request::with_params(Id::from(3), "sum".to_string(), vec![1, 2]),
+/// ];
+/// jrpc::dedup_by_content(&mut requests);
+/// assert_eq!(requests.len(), 2);
+/// assert_eq!(requests[1].params, Some(vec![3]));
+/// # }
+/// ```
+pub fn dedup_by_content<M: Serialize, T: Serialize>(requests: &mut Vec<Request<M, T>>) {
+    // the contents are compared too, in case two hashes collide
+    let mut seen: HashMap<u64, Vec<(Value, Option<Value>)>> = HashMap::new();
+    requests.retain(|request| {
+        let content = request.content();
+        let same_hash = seen.entry(hash_content(&content)).or_default();
+        if same_hash.contains(&content) {
+            false
+        } else {
+            same_hash.push(content);
+            true
+        }
+    });
+}
@@ -0,0 +1,123 @@
+//! Cancellation of pending Requests (i.e. LSP's `$/cancelRequest`).
+//!
+//! The Client sends a Notification with the id of the Request to cancel. The Server records it in
+//! a [`CancellationSet`](struct.CancellationSet.html), which the handlers check before (or while)
+//! doing their work.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate serde_json;
+//! use jrpc::cancel::{self, CancellationSet};
+//! use jrpc::Id;
+//!
+//! # fn main() {
+//! // Client
+//! let json = serde_json::to_string(&cancel::notification(Id::from(4))).unwrap();
+//! jrpc::testing::assert_json_eq(
+//!     &json,
+//!     r#"{"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": 4}}"#,
+//! );
+//!
+//! // Server
+//! let mut cancelled = CancellationSet::new();
+//! let request = jrpc::parse_request::<String>(&json).unwrap();
+//! if let Some(Ok(id)) = cancel::decode(&request, cancel::METHOD) {
+//!     cancelled.cancel(id);
+//! }
+//! assert!(cancelled.take(&Id::from(4)));
+//! assert!(!cancelled.take(&Id::from(4)));
+//! # }
+//! ```
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use super::*;
+
+/// The method of the cancellation Notifications of LSP.
+pub const METHOD: &str = "$/cancelRequest";
+
+/// The params of a cancellation Notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancelParams {
+    /// The id of the Request to cancel.
+    pub id: Id,
+}
+
+/// Create the cancellation Notification of [`METHOD`](constant.METHOD.html) for the pending
+/// Request with the `id`.
+pub fn notification(id: Id) -> Request<&'static str, CancelParams> {
+    Request {
+        jsonrpc: V2_0,
+        method: METHOD,
+        params: Some(CancelParams { id: id }),
+        id: IdReq::Notification,
+    }
+}
+
+/// Decode the id of the Request to cancel if the `request` is a cancellation Notification of
+/// `method`.
+///
+/// Returns `None` if the `request` is of another method or has an `id`. Otherwise returns the id,
+/// or the error if the params are not a `{"id"}` Object.
+pub fn decode(
+    request: &Request<String, Value>,
+    method: &str,
+) -> Option<Result<Id, serde_json::Error>> {
+    if request.method != method || request.id != IdReq::Notification {
+        return None;
+    }
+    let params = request.params.as_ref().unwrap_or(&Value::Null);
+    Some(CancelParams::deserialize(params).map(|p| p.id))
+}
+
+/// The ids of the Requests which were cancelled and not yet checked by their handler.
+///
+/// Share it between the handlers behind a `Mutex`. A handler calls [`take`](#method.take) with
+/// the id of its Request: if it returns `true` the Request was cancelled and the handler should
+/// stop (LSP replies with the error code `-32800`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CancellationSet {
+    cancelled: HashSet<Id>,
+}
+
+impl CancellationSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        CancellationSet::default()
+    }
+
+    /// Mark the Request with the `id` as cancelled. Returns `false` if it already was.
+    pub fn cancel(&mut self, id: Id) -> bool {
+        self.cancelled.insert(id)
+    }
+
+    /// Return whether the Request with the `id` is cancelled, without removing it.
+    pub fn is_cancelled(&self, id: &Id) -> bool {
+        self.cancelled.contains(id)
+    }
+
+    /// Return whether the Request with the `id` is cancelled and remove it from the set.
+    pub fn take(&mut self, id: &Id) -> bool {
+        self.cancelled.remove(id)
+    }
+
+    /// The number of cancelled Requests.
+    pub fn len(&self) -> usize {
+        self.cancelled.len()
+    }
+
+    /// Return whether no Request is cancelled.
+    pub fn is_empty(&self) -> bool {
+        self.cancelled.is_empty()
+    }
+
+    /// Forget all the cancelled Requests, i.e. once the Requests which were cancelled before
+    /// their handler ran were replied to.
+    pub fn clear(&mut self) {
+        self.cancelled.clear()
+    }
+}
@@ -0,0 +1,194 @@
+//! Methods whose wire name follows a case convention.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::result;
+use serde::de::{self, IntoDeserializer};
+use serde::ser;
+
+use super::*;
+
+/// A case convention of the method names on the wire, converting from and to the names of the
+/// variants of a Rust enum (`PascalCase`).
+pub trait CaseConvention {
+    /// The name of the convention, for the error messages (i.e. `snake_case`).
+    const NAME: &'static str;
+
+    /// Convert the name of a variant (i.e. `CreateFooBar`) to the wire name.
+    fn from_variant(variant: &str) -> String;
+
+    /// Convert a wire name back to the name of the variant.
+    fn to_variant(wire: &str) -> String;
+}
+
+/// `create_foo_bar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct SnakeCase;
+
+impl CaseConvention for SnakeCase {
+    const NAME: &'static str = "snake_case";
+
+    fn from_variant(variant: &str) -> String {
+        let mut wire = String::with_capacity(variant.len() + 4);
+        for (i, c) in variant.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                wire.push('_');
+            }
+            wire.extend(c.to_lowercase());
+        }
+        wire
+    }
+
+    fn to_variant(wire: &str) -> String {
+        wire.split('_').map(capitalize).collect()
+    }
+}
+
+/// `createFooBar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct CamelCase;
+
+impl CaseConvention for CamelCase {
+    const NAME: &'static str = "camelCase";
+
+    fn from_variant(variant: &str) -> String {
+        let mut chars = variant.chars();
+        match chars.next() {
+            Some(first) => first.to_lowercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    fn to_variant(wire: &str) -> String {
+        capitalize(wire)
+    }
+}
+
+/// Uppercase the first character.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// A method enum whose wire names are the names of its variants in the convention `C`, so that
+/// the enum doesn't need a `#[serde(rename_all)]`.
+///
+/// The method `M` must serialize to the name of its variant and deserialize from it, which is
+/// what `#[derive(Serialize, Deserialize)]` does for the unit variants of an enum.
+///
+/// A wire name which is not in the convention (i.e. `CreateFooBar` or `createFooBar` for
+/// `SnakeCase`) fails to deserialize, which makes [`parse_request`](fn.parse_request.html)
+/// return `MethodNotFound`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// use jrpc::{Cased, ErrorCode, Id, Request, SnakeCase};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Method {
+///     CreateFooBar,
+///     Delete,
+/// }
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "create_foo_bar", "id": 1}"#;
+/// let request = jrpc::parse_request::<Cased<Method, SnakeCase>>(json).unwrap();
+/// assert_eq!(*request.method, Method::CreateFooBar);
+///
+/// let request = Request::new(Id::from(2), Cased::<_, SnakeCase>::new(Method::Delete));
+/// assert_eq!(request.to_string(), r#"{"jsonrpc":"2.0","method":"delete","id":2}"#);
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "CreateFooBar", "id": 1}"#;
+/// let error = jrpc::parse_request::<Cased<Method, SnakeCase>>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cased<M, C = SnakeCase> {
+    method: M,
+    convention: PhantomData<C>,
+}
+
+impl<M, C> Cased<M, C> {
+    /// Wrap the `method`.
+    pub fn new(method: M) -> Self {
+        Cased {
+            method: method,
+            convention: PhantomData,
+        }
+    }
+
+    /// The wrapped method.
+    pub fn into_inner(self) -> M {
+        self.method
+    }
+}
+
+impl<M, C> From<M> for Cased<M, C> {
+    fn from(method: M) -> Self {
+        Cased::new(method)
+    }
+}
+
+impl<M, C> ::std::ops::Deref for Cased<M, C> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.method
+    }
+}
+
+impl<M: Serialize, C: CaseConvention> ser::Serialize for Cased<M, C> {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match serde_json::to_value(&self.method) {
+            Ok(Value::String(variant)) => serializer.serialize_str(&C::from_variant(&variant)),
+            Ok(_) => Err(ser::Error::custom("a Cased method must serialize to a string")),
+            Err(err) => Err(ser::Error::custom(err)),
+        }
+    }
+}
+
+struct CasedVisitor<M, C>(PhantomData<(M, C)>);
+
+impl<'de, M: DeserializeOwned, C: CaseConvention> de::Visitor<'de> for CasedVisitor<M, C> {
+    type Value = Cased<M, C>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a {} method", C::NAME)
+    }
+
+    fn visit_str<E>(self, value: &str) -> result::Result<Cased<M, C>, E>
+    where
+        E: de::Error,
+    {
+        let variant = C::to_variant(value);
+        // reject the names which are not in the convention, they convert to the same variant
+        if C::from_variant(&variant) != value {
+            return Err(E::custom(format!("method `{}` is not {}", value, C::NAME)));
+        }
+        let deserializer: de::value::StringDeserializer<de::value::Error> =
+            variant.into_deserializer();
+        match M::deserialize(deserializer) {
+            Ok(method) => Ok(Cased::new(method)),
+            Err(_) => Err(E::custom(format!("unknown method `{}`", value))),
+        }
+    }
+}
+
+impl<'de, M: DeserializeOwned, C: CaseConvention> de::Deserialize<'de> for Cased<M, C> {
+    fn deserialize<D>(deserializer: D) -> result::Result<Cased<M, C>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CasedVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,895 @@
+//! State for the Client side: correlating Responses with the Requests which were sent.
+//!
+//! Nothing here does any IO or is async. The callers bring their own transport and their own way
+//! of waking up the caller of a Request (i.e. a oneshot channel), which are stored as the `Meta`.
+//!
+//! [`ClientState`](struct.ClientState.html) is the whole state machine: it allocates the ids,
+//! builds the Requests to send and classifies the messages which are received.
+//! [`BatchBuilder`](struct.BatchBuilder.html) allocates the ids of a batch and
+//! [`NotificationBatch`](struct.NotificationBatch.html) is a batch which gets no Response.
+
+use std::collections::hash_map::{self, HashMap};
+use std::collections::HashSet;
+use std::error;
+use std::fmt;
+
+use super::*;
+
+/// The table of the Requests which were sent and are waiting for their Response.
+///
+/// Each Request is registered with its `id` and some `Meta` (i.e. the sender of a oneshot channel
+/// or the method which was called), which is returned once its Response arrives.
+///
+/// Ids are compared exactly: the String `"1"` and the integer `1` are different ids.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::client::PendingRequests;
+/// use jrpc::{Id, Response, Value};
+///
+/// # fn main() {
+/// let mut pending = PendingRequests::new();
+/// pending.register(Id::from(1), "subtract").unwrap();
+/// pending.register(Id::from(2), "sum").unwrap();
+///
+/// let response: Response<Value> = Response::success(Id::from(2), Value::from(7));
+/// assert_eq!(pending.complete(response.id()), Some("sum"));
+/// assert_eq!(pending.len(), 1);
+///
+/// // The connection was closed: fail the remaining callers.
+/// let outstanding: Vec<_> = pending.drain().collect();
+/// assert_eq!(outstanding, vec![(Id::from(1), "subtract")]);
+/// assert!(pending.is_empty());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PendingRequests<Meta> {
+    pending: HashMap<Id, Meta>,
+    loose_ids: bool,
+}
+
+impl<Meta> Default for PendingRequests<Meta> {
+    fn default() -> Self {
+        PendingRequests {
+            pending: HashMap::new(),
+            loose_ids: false,
+        }
+    }
+}
+
+impl<Meta> PendingRequests<Meta> {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        PendingRequests::default()
+    }
+
+    /// Register the Request with the `id`, which is waiting for its Response.
+    ///
+    /// If a Request with the same `id` is already pending, nothing is changed and the `meta` is
+    /// given back as the error.
+    pub fn register(&mut self, id: Id, meta: Meta) -> Result<(), Meta> {
+        match self.pending.entry(id) {
+            hash_map::Entry::Occupied(_) => Err(meta),
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(meta);
+                Ok(())
+            }
+        }
+    }
+
+    /// Complete the Request with the id of a Response, returning its `Meta`.
+    ///
+    /// Returns `None` if no Request with the id is pending, i.e. it was already completed or the
+    /// Server replied with an unknown id.
+    pub fn complete(&mut self, response_id: &Id) -> Option<Meta> {
+        match self.pending.remove(response_id) {
+            None if self.loose_ids => response_id
+                .loose_alternative()
+                .and_then(|id| self.pending.remove(&id)),
+            meta => meta,
+        }
+    }
+
+    /// Also complete the Requests with ids which are only
+    /// [`eq_loose`](../enum.Id.html#method.eq_loose) to the id of the Response, i.e. when the
+    /// Server replies to `7` with `"7"`. Ids are compared exactly by default.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::client::PendingRequests;
+    /// use jrpc::Id;
+    ///
+    /// # fn main() {
+    /// let mut pending = PendingRequests::new();
+    /// pending.register(Id::from(7), "subtract").unwrap();
+    /// assert_eq!(pending.complete(&Id::from("7")), None);
+    ///
+    /// pending.set_loose_ids(true);
+    /// assert_eq!(pending.complete(&Id::from("7")), Some("subtract"));
+    /// # }
+    /// ```
+    pub fn set_loose_ids(&mut self, loose: bool) {
+        self.loose_ids = loose;
+    }
+
+    /// Return whether a Request with the `id` is pending.
+    pub fn contains(&self, id: &Id) -> bool {
+        self.pending.contains_key(id)
+    }
+
+    /// The number of pending Requests.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Return whether no Request is pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Iterate over the pending Requests, in no particular order.
+    pub fn iter(&self) -> hash_map::Iter<'_, Id, Meta> {
+        self.pending.iter()
+    }
+
+    /// Remove all the pending Requests, i.e. to fail them when the connection is closed.
+    pub fn drain(&mut self) -> hash_map::Drain<'_, Id, Meta> {
+        self.pending.drain()
+    }
+}
+
+impl<'a, Meta> IntoIterator for &'a PendingRequests<Meta> {
+    type Item = (&'a Id, &'a Meta);
+    type IntoIter = hash_map::Iter<'a, Id, Meta>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Allocate the ids of the Requests sent by a Client: the integers `1`, `2`, `3`...
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::client::IdGenerator;
+/// use jrpc::Id;
+///
+/// # fn main() {
+/// let mut ids = IdGenerator::new();
+/// assert_eq!(ids.next_id(), Id::from(1));
+/// assert_eq!(ids.next_id(), Id::from(2));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdGenerator {
+    next: i64,
+}
+
+impl Default for IdGenerator {
+    fn default() -> Self {
+        IdGenerator::starting_at(1)
+    }
+}
+
+impl IdGenerator {
+    /// Create a generator starting at `1`.
+    pub fn new() -> Self {
+        IdGenerator::default()
+    }
+
+    /// Create a generator starting at `first`.
+    pub fn starting_at(first: i64) -> Self {
+        IdGenerator { next: first }
+    }
+
+    /// Allocate the next id. Wraps around at `i64::MAX`.
+    pub fn next_id(&mut self) -> Id {
+        let id = self.next;
+        self.next = self.next.wrapping_add(1);
+        Id::Int(id)
+    }
+}
+
+/// Encode a call of `method`, with the next id of the `ids`: the id to wait for and the json
+/// Request to send.
+///
+/// The `params` member is omitted when they are `None`. Use
+/// [`ClientState::start_call`](struct.ClientState.html#method.start_call) to also keep track of the
+/// pending Requests.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::client::{self, IdGenerator};
+/// use jrpc::Id;
+///
+/// # fn main() {
+/// let mut ids = IdGenerator::new();
+/// let (id, json) = client::encode_call(&mut ids, "subtract", Some([42, 23])).unwrap();
+/// assert_eq!(id, Id::from(1));
+/// assert_eq!(json, r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#);
+///
+/// let json = client::encode_notification("heartbeat", None::<()>).unwrap();
+/// assert_eq!(json, r#"{"jsonrpc":"2.0","method":"heartbeat"}"#);
+/// # }
+/// ```
+pub fn encode_call<M, P>(
+    ids: &mut IdGenerator,
+    method: M,
+    params: Option<P>,
+) -> serde_json::Result<(Id, String)>
+where
+    M: Serialize,
+    P: Serialize,
+{
+    let id = ids.next_id();
+    let json = encode(IdReq::from(id.clone()), method, params)?;
+    Ok((id, json))
+}
+
+/// Encode a Notification of `method`, see [`encode_call`](fn.encode_call.html).
+pub fn encode_notification<M, P>(method: M, params: Option<P>) -> serde_json::Result<String>
+where
+    M: Serialize,
+    P: Serialize,
+{
+    encode(IdReq::Notification, method, params)
+}
+
+fn encode<M: Serialize, P: Serialize>(
+    id: IdReq,
+    method: M,
+    params: Option<P>,
+) -> serde_json::Result<String> {
+    serde_json::to_string(&Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: params,
+        id: id,
+    })
+}
+
+/// A message received by a [`ClientState`](struct.ClientState.html).
+#[derive(Debug)]
+pub enum InboundEvent<Meta> {
+    /// The Response to a pending Request, with the `Meta` it was registered with.
+    Response {
+        /// The `Meta` given to [`start_call`](struct.ClientState.html#method.start_call).
+        meta: Meta,
+        /// The Response.
+        response: Response<Value>,
+    },
+    /// A Response whose id matches no pending Request, i.e. an `Error` with a `null` id when the
+    /// Server could not read the Request.
+    Unmatched(Response<Value>),
+    /// A Request or Notification initiated by the Server.
+    Request(Request<String, Value>),
+    /// The message is neither a Response nor a Request, with the reason.
+    Invalid(String),
+}
+
+/// A Request completed by [`complete_typed`](struct.ClientState.html#method.complete_typed): its
+/// `Meta` with either the decoded `result` or the `error` of the Response.
+pub type Completed<Meta, R> = (Meta, Result<R, Error<Value>>);
+
+/// The Response could not be completed by
+/// [`complete_typed`](struct.ClientState.html#method.complete_typed).
+#[derive(Debug)]
+pub enum ClientError<Meta> {
+    /// The message is not a Response, with the reason.
+    InvalidResponse(String),
+    /// No Request with the id of the Response is pending.
+    UnknownId(Id),
+    /// The `result` of the Response could not be decoded into the expected type.
+    ///
+    /// The Request is no longer pending: its `meta` is returned here.
+    ResultTypeMismatch {
+        /// The `Meta` of the Request.
+        meta: Meta,
+        /// The `result` as received.
+        result: Value,
+        /// Why it could not be decoded.
+        reason: String,
+    },
+}
+
+impl<Meta> fmt::Display for ClientError<Meta> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ClientError::InvalidResponse(ref reason) => write!(f, "invalid response: {}", reason),
+            ClientError::UnknownId(ref id) => {
+                let id = serde_json::to_string(id).map_err(|_| fmt::Error)?;
+                write!(f, "no pending request has the id {}", id)
+            }
+            ClientError::ResultTypeMismatch { ref reason, .. } => {
+                write!(f, "the result has the wrong type: {}", reason)
+            }
+        }
+    }
+}
+
+impl<Meta: fmt::Debug> error::Error for ClientError<Meta> {}
+
+/// The state of a Client: the id generator and the pending Requests.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::client::{ClientState, InboundEvent};
+///
+/// # fn main() {
+/// let mut client = ClientState::new();
+/// let (id, json) = client.start_call("subtract", Some([42, 23]), "caller 1").unwrap();
+/// jrpc::testing::assert_json_eq(
+///     &json,
+///     r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+/// );
+///
+/// // ... send `json` and receive the reply ...
+/// match client.on_message(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#) {
+///     InboundEvent::Response { meta, response } => {
+///         assert_eq!(meta, "caller 1");
+///         assert_eq!(response.id(), &id);
+///     }
+///     event => panic!("unexpected {:?}", event),
+/// }
+/// assert!(client.pending().is_empty());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientState<Meta> {
+    ids: IdGenerator,
+    pending: PendingRequests<Meta>,
+}
+
+impl<Meta> Default for ClientState<Meta> {
+    fn default() -> Self {
+        ClientState::with_ids(IdGenerator::new())
+    }
+}
+
+impl<Meta> ClientState<Meta> {
+    /// Create a state without any pending Request.
+    pub fn new() -> Self {
+        ClientState::default()
+    }
+
+    /// Create a state allocating the ids with `ids`.
+    pub fn with_ids(ids: IdGenerator) -> Self {
+        ClientState {
+            ids: ids,
+            pending: PendingRequests::new(),
+        }
+    }
+
+    /// The pending Requests.
+    pub fn pending(&self) -> &PendingRequests<Meta> {
+        &self.pending
+    }
+
+    /// The pending Requests, i.e. to [`drain`](struct.PendingRequests.html#method.drain) them
+    /// when the connection is closed.
+    pub fn pending_mut(&mut self) -> &mut PendingRequests<Meta> {
+        &mut self.pending
+    }
+
+    /// Start calling `method`: allocate an id, register the `meta` as pending and return the id
+    /// with the json Request to send.
+    ///
+    /// Ids which are still pending (after the generator wrapped around) are skipped.
+    ///
+    /// Fails if the `method` or `params` fail to serialize, in which case nothing is registered.
+    pub fn start_call<M, T>(
+        &mut self,
+        method: M,
+        params: Option<T>,
+        meta: Meta,
+    ) -> serde_json::Result<(Id, String)>
+    where
+        M: Serialize,
+        T: Serialize,
+    {
+        let mut id = self.ids.next_id();
+        while self.pending.contains(&id) {
+            id = self.ids.next_id();
+        }
+        let json = encode(IdReq::from(id.clone()), method, params)?;
+        if self.pending.register(id.clone(), meta).is_err() {
+            unreachable!("the id is not pending");
+        }
+        Ok((id, json))
+    }
+
+    /// Classify a json message received from the Server.
+    ///
+    /// A Response to a pending Request completes it. Batches are not supported, since
+    /// `start_call` never sends them: an Array is `Invalid`.
+    pub fn on_message(&mut self, json: &str) -> InboundEvent<Meta> {
+        let value: Value = match serde_json::from_str(json) {
+            Ok(value) => value,
+            Err(err) => return InboundEvent::Invalid(format!("invalid json: {}", err)),
+        };
+        if value.get("method").is_some() {
+            return match parse_request_value::<String>(value) {
+                Ok(request) => InboundEvent::Request(request),
+                Err(error) => InboundEvent::Invalid(error.error.message.into_owned()),
+            };
+        }
+        let response: Response<Value> = match serde_json::from_value(value) {
+            Ok(response) => response,
+            Err(err) => return InboundEvent::Invalid(format!("invalid response: {}", err)),
+        };
+        match self.pending.complete(response.id()) {
+            Some(meta) => InboundEvent::Response {
+                meta: meta,
+                response: response,
+            },
+            None => InboundEvent::Unmatched(response),
+        }
+    }
+
+    /// Complete the pending Request with the json Response, decoding its `result` into `R`.
+    ///
+    /// Returns the `Meta` of the Request with either the decoded `result` or the `error` of the
+    /// Response.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::client::{ClientError, ClientState};
+    ///
+    /// # fn main() {
+    /// let mut client = ClientState::new();
+    /// client.start_call("subtract", Some([42, 23]), "caller 1");
+    /// client.start_call("subtract", Some([42, 23]), "caller 2");
+    ///
+    /// let reply = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+    /// let (meta, result) = client.complete_typed::<i64>(reply).unwrap();
+    /// assert_eq!(meta, "caller 1");
+    /// assert_eq!(result.unwrap(), 19);
+    ///
+    /// let reply = r#"{"jsonrpc": "2.0", "result": "19", "id": 2}"#;
+    /// match client.complete_typed::<i64>(reply).unwrap_err() {
+    ///     ClientError::ResultTypeMismatch { meta, result, .. } => {
+    ///         assert_eq!(meta, "caller 2");
+    ///         assert_eq!(result, "19");
+    ///     }
+    ///     err => panic!("unexpected {}", err),
+    /// }
+    /// # }
+    /// ```
+    pub fn complete_typed<R>(
+        &mut self,
+        json: &str,
+    ) -> Result<Completed<Meta, R>, ClientError<Meta>>
+    where
+        R: DeserializeOwned,
+    {
+        let response: Response<Value> = serde_json::from_str(json)
+            .map_err(|err| ClientError::InvalidResponse(err.to_string()))?;
+        let meta = match self.pending.complete(response.id()) {
+            Some(meta) => meta,
+            None => return Err(ClientError::UnknownId(response.id().clone())),
+        };
+        match response.into_result() {
+            Ok(result) => match R::deserialize(&result) {
+                Ok(decoded) => Ok((meta, Ok(decoded))),
+                Err(err) => Err(ClientError::ResultTypeMismatch {
+                    meta: meta,
+                    result: result,
+                    reason: err.to_string(),
+                }),
+            },
+            Err(error) => Ok((meta, Err(error))),
+        }
+    }
+}
+
+/// The Requests of a batch, in the order they are sent.
+pub type BatchRequest<M, T> = Vec<Request<M, T>>;
+
+/// The batch could not be built by [`BatchBuilder`](struct.BatchBuilder.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchError {
+    /// No Request was added: the Server would reply to an empty Array with `InvalidRequest`.
+    Empty,
+    /// More Requests were added than the maximum.
+    TooLong {
+        /// The number of Requests.
+        len: usize,
+        /// The maximum given to [`max_len`](struct.BatchBuilder.html#method.max_len).
+        max: usize,
+    },
+    /// The params of a Request (or the methods, for `build_string`) failed to serialize.
+    Serialize {
+        /// The index of the Request in the batch, `None` if unknown.
+        index: Option<usize>,
+        /// The error of `serde_json`.
+        reason: String,
+    },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BatchError::Empty => write!(f, "the batch is empty"),
+            BatchError::TooLong { len, max } => write!(
+                f,
+                "the batch has {} requests, more than the maximum of {}",
+                len, max
+            ),
+            BatchError::Serialize {
+                index: Some(index),
+                ref reason,
+            } => write!(f, "request {} of the batch failed to serialize: {}", index, reason),
+            BatchError::Serialize {
+                index: None,
+                ref reason,
+            } => write!(f, "the batch failed to serialize: {}", reason),
+        }
+    }
+}
+
+impl error::Error for BatchError {}
+
+/// Build a batch, allocating the ids of its Requests.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::client::BatchBuilder;
+/// use jrpc::Id;
+///
+/// # fn main() {
+/// let mut batch = BatchBuilder::new();
+/// assert_eq!(batch.call("sum", Some([1, 2, 4])), Id::from(1));
+/// batch.notify("notify_hello", Some([7]));
+/// assert_eq!(batch.call("get_data", None::<()>), Id::from(2));
+///
+/// jrpc::testing::assert_json_eq(
+///     &batch.build_string().unwrap(),
+///     r#"[
+///         {"jsonrpc": "2.0", "method": "sum", "params": [1, 2, 4], "id": 1},
+///         {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]},
+///         {"jsonrpc": "2.0", "method": "get_data", "id": 2}
+///     ]"#,
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BatchBuilder<M> {
+    ids: IdGenerator,
+    requests: BatchRequest<M, Value>,
+    max_len: Option<usize>,
+    /// The first params which failed to serialize.
+    failed: Option<BatchError>,
+}
+
+impl<M> Default for BatchBuilder<M> {
+    fn default() -> Self {
+        BatchBuilder::with_ids(IdGenerator::new())
+    }
+}
+
+impl<M> BatchBuilder<M> {
+    /// Create an empty batch whose ids start at `1`.
+    pub fn new() -> Self {
+        BatchBuilder::default()
+    }
+
+    /// Create an empty batch allocating the ids with `ids`.
+    pub fn with_ids(ids: IdGenerator) -> Self {
+        BatchBuilder {
+            ids: ids,
+            requests: Vec::new(),
+            max_len: None,
+            failed: None,
+        }
+    }
+
+    /// Refuse to build a batch of more than `max` Requests, i.e. the limit of the Server.
+    pub fn max_len(mut self, max: usize) -> Self {
+        self.max_len = Some(max);
+        self
+    }
+
+    /// Add a Request calling `method`, returning its id.
+    ///
+    /// If the `params` fail to serialize, [`build`](#method.build) fails.
+    pub fn call<T: Serialize>(&mut self, method: M, params: Option<T>) -> Id {
+        let id = self.ids.next_id();
+        self.push(IdReq::from(id.clone()), method, params);
+        id
+    }
+
+    /// Add a Notification of `method`.
+    ///
+    /// If the `params` fail to serialize, [`build`](#method.build) fails.
+    pub fn notify<T: Serialize>(&mut self, method: M, params: Option<T>) {
+        self.push(IdReq::Notification, method, params);
+    }
+
+    fn push<T: Serialize>(&mut self, id: IdReq, method: M, params: Option<T>) {
+        let params = match params.map(serde_json::to_value) {
+            Some(Ok(params)) => Some(params),
+            Some(Err(err)) => {
+                if self.failed.is_none() {
+                    self.failed = Some(BatchError::Serialize {
+                        index: Some(self.requests.len()),
+                        reason: err.to_string(),
+                    });
+                }
+                Some(Value::Null)
+            }
+            None => None,
+        };
+        self.requests.push(Request {
+            jsonrpc: V2_0,
+            method: method,
+            params: params,
+            id: id,
+        });
+    }
+
+    /// The number of Requests added.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Return whether no Request was added.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// The Requests, in the order they were added.
+    pub fn build(self) -> Result<BatchRequest<M, Value>, BatchError> {
+        if let Some(failed) = self.failed {
+            return Err(failed);
+        }
+        match self.max_len {
+            _ if self.requests.is_empty() => Err(BatchError::Empty),
+            Some(max) if self.requests.len() > max => Err(BatchError::TooLong {
+                len: self.requests.len(),
+                max: max,
+            }),
+            _ => Ok(self.requests),
+        }
+    }
+}
+
+impl<M: Serialize> BatchBuilder<M> {
+    /// The json Array of the Requests, in the order they were added.
+    pub fn build_string(self) -> Result<String, BatchError> {
+        let requests = self.build()?;
+        serde_json::to_string(&requests).map_err(|err| BatchError::Serialize {
+            index: None,
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// A batch of Notifications only, to which the Server sends no Response at all (not even an
+/// empty Array), so there is nothing to wait for.
+///
+/// It can't contain a Request with an id: [`push`](#method.push) and `TryFrom` give it back. It
+/// serializes to the json Array of the Notifications, in the order they were added.
+///
+/// > An empty Array is replied with an `InvalidRequest`: don't send an empty batch.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::client::NotificationBatch;
+/// use jrpc::{Id, Request};
+///
+/// # fn main() {
+/// let mut batch = NotificationBatch::new();
+/// batch.notify("notify_sum".to_string(), Some(vec![1, 2, 4]));
+/// batch.notify("notify_hello".to_string(), Some(vec![7]));
+///
+/// let request = Request::with_params(Id::from(1), "sum".to_string(), vec![1, 2]);
+/// assert_eq!(batch.push(request).unwrap_err().method, "sum");
+/// assert_eq!(batch.len(), 2);
+///
+/// jrpc::testing::assert_json_eq(
+///     &serde_json::to_string(&batch).unwrap(),
+///     r#"[
+///         {"jsonrpc": "2.0", "method": "notify_sum", "params": [1, 2, 4]},
+///         {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]}
+///     ]"#,
+/// );
+/// # }
+/// ```
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct NotificationBatch<M, T> {
+    notifications: Vec<Request<M, T>>,
+}
+
+impl<M, T> Default for NotificationBatch<M, T> {
+    fn default() -> Self {
+        NotificationBatch {
+            notifications: Vec::new(),
+        }
+    }
+}
+
+impl<M, T> NotificationBatch<M, T> {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        NotificationBatch::default()
+    }
+
+    /// Add a Notification of `method`.
+    pub fn notify(&mut self, method: M, params: Option<T>) {
+        self.notifications.push(Request {
+            jsonrpc: V2_0,
+            method: method,
+            params: params,
+            id: IdReq::Notification,
+        });
+    }
+
+    /// Add the `request`, returning it back if it is not a Notification.
+    pub fn push(&mut self, request: Request<M, T>) -> Result<(), Request<M, T>> {
+        if !request.id.is_notification() {
+            return Err(request);
+        }
+        self.notifications.push(request);
+        Ok(())
+    }
+
+    /// The number of Notifications added.
+    pub fn len(&self) -> usize {
+        self.notifications.len()
+    }
+
+    /// Return whether no Notification was added.
+    pub fn is_empty(&self) -> bool {
+        self.notifications.is_empty()
+    }
+
+    /// The Notifications, in the order they were added.
+    pub fn into_vec(self) -> Vec<Request<M, T>> {
+        self.notifications
+    }
+}
+
+/// Fails with the `requests`, unchanged, if any of them has an id.
+impl<M, T> ::std::convert::TryFrom<Vec<Request<M, T>>> for NotificationBatch<M, T> {
+    type Error = Vec<Request<M, T>>;
+
+    fn try_from(requests: Vec<Request<M, T>>) -> Result<Self, Vec<Request<M, T>>> {
+        if !requests.iter().all(|request| request.id.is_notification()) {
+            return Err(requests);
+        }
+        Ok(NotificationBatch {
+            notifications: requests,
+        })
+    }
+}
+
+/// The Responses of a batch, indexed by their id.
+///
+/// The Server may reply to a batch in any order: this finds the Response to each Request without
+/// scanning them all. The Responses with a `null` id, i.e. the `ParseError` of a Request the
+/// Server couldn't read, match no Request and are kept aside as the
+/// [`orphans`](struct.ResponseSet.html#method.orphans).
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::client::ResponseSet;
+/// use jrpc::{Id, Response, Value};
+///
+/// # fn main() {
+/// let responses: Vec<Response<Value>> = serde_json::from_str(
+///     r#"[
+///         {"jsonrpc": "2.0", "result": 7, "id": "1"},
+///         {"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null},
+///         {"jsonrpc": "2.0", "result": 19, "id": "2"}
+///     ]"#,
+/// ).unwrap();
+/// let mut responses = ResponseSet::new(responses).unwrap();
+/// assert_eq!(responses.len(), 2);
+/// assert_eq!(responses.orphans().len(), 1);
+///
+/// match responses.remove(&Id::from("2")) {
+///     Some(Response::Ok(success)) => assert_eq!(success.result, 19),
+///     response => panic!("unexpected {:?}", response),
+/// }
+/// assert!(responses.get(&Id::from("2")).is_none());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ResponseSet<T> {
+    by_id: HashMap<Id, Response<T>>,
+    orphans: Vec<Response<T>>,
+}
+
+/// Several Responses of a batch have the same id, see
+/// [`ResponseSet::new`](struct.ResponseSet.html#method.new).
+#[derive(Debug)]
+pub struct DuplicateIds<T> {
+    /// The ids of more than one Response, in the order they were first repeated.
+    pub ids: Vec<Id>,
+    /// The Responses, unchanged.
+    pub responses: Vec<Response<T>>,
+}
+
+impl<T> fmt::Display for DuplicateIds<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ids: Vec<_> = self.ids.iter().map(|id| id.to_string()).collect();
+        write!(f, "several responses have the id {}", ids.join(", "))
+    }
+}
+
+impl<T: fmt::Debug> error::Error for DuplicateIds<T> {}
+
+impl<T> ResponseSet<T> {
+    /// Index the `responses` by their id.
+    ///
+    /// Two Responses with the same (non `null`) id are a Server bug: neither can be trusted to be
+    /// the Response of the Request, so the `responses` are given back in the error.
+    pub fn new(responses: Vec<Response<T>>) -> Result<ResponseSet<T>, DuplicateIds<T>> {
+        let mut duplicates = Vec::new();
+        {
+            let mut seen = HashSet::new();
+            for id in responses.iter().map(Response::id) {
+                if *id != Id::Null && !seen.insert(id) && !duplicates.contains(id) {
+                    duplicates.push(id.clone());
+                }
+            }
+        }
+        if !duplicates.is_empty() {
+            return Err(DuplicateIds {
+                ids: duplicates,
+                responses: responses,
+            });
+        }
+        let mut set = ResponseSet {
+            by_id: HashMap::with_capacity(responses.len()),
+            orphans: Vec::new(),
+        };
+        for response in responses {
+            match *response.id() {
+                Id::Null => set.orphans.push(response),
+                ref id => {
+                    set.by_id.insert(id.clone(), response);
+                }
+            }
+        }
+        Ok(set)
+    }
+
+    /// The Response with the `id`.
+    pub fn get(&self, id: &Id) -> Option<&Response<T>> {
+        self.by_id.get(id)
+    }
+
+    /// Remove the Response with the `id`, i.e. to hand it to the caller of the Request.
+    pub fn remove(&mut self, id: &Id) -> Option<Response<T>> {
+        self.by_id.remove(id)
+    }
+
+    /// The Responses with a `null` id, which match no Request.
+    pub fn orphans(&self) -> &[Response<T>] {
+        &self.orphans
+    }
+
+    /// The number of Responses with an id, i.e. without the orphans.
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    /// Return whether no Response with an id is left.
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
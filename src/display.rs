@@ -0,0 +1,163 @@
+//! Concise one-line summaries of the messages, for access logs.
+
+use std::fmt;
+
+use super::*;
+
+/// Write the length of the json of `value`, i.e. `23B`.
+fn write_len<S: Serialize>(f: &mut fmt::Formatter, value: &S) -> fmt::Result {
+    write!(f, "{}B", serialize::json_len(value).map_err(|_| fmt::Error)?)
+}
+
+/// The id as json: `4`, `"abc"` or `null`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::Id;
+///
+/// # fn main() {
+/// assert_eq!(Id::from(4).to_string(), "4");
+/// assert_eq!(Id::from("abc").to_string(), r#""abc""#);
+/// assert_eq!(Id::Null.to_string(), "null");
+/// # }
+/// ```
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Id::String(ref s) => write!(f, "{}", Value::from(s.as_str())),
+            Id::Int(v) => write!(f, "{}", v),
+            Id::Null => f.write_str("null"),
+        }
+    }
+}
+
+/// A one-line summary: `--> subtract id=4 params=23B`.
+///
+/// The id of a Notification is `notif` and `params` is omitted when absent. The method is printed
+/// as-is if it serializes to a String, otherwise as json. Only the length of the params is
+/// printed, so the summary never contains their content.
+///
+/// Note that `to_string()` is still the json of the Request: use `format!("{}")` for the
+/// summary.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{Id, Request};
+///
+/// # fn main() {
+/// let request = Request::with_params(Id::from(4), "subtract".to_string(), vec![42, 23]);
+/// assert_eq!(format!("{}", request), "--> subtract id=4 params=7B");
+/// # }
+/// ```
+impl<M: Serialize, T: Serialize> fmt::Display for Request<M, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("--> ")?;
+        match serde_json::to_value(&self.method).map_err(|_| fmt::Error)? {
+            Value::String(ref method) => f.write_str(method)?,
+            ref method => write!(f, "{}", method)?,
+        }
+        match self.id.as_id() {
+            Some(id) => write!(f, " id={}", id)?,
+            None => f.write_str(" id=notif")?,
+        }
+        if let Some(ref params) = self.params {
+            f.write_str(" params=")?;
+            write_len(f, params)?;
+        }
+        Ok(())
+    }
+}
+
+/// A one-line summary: `<-- id=4 ok result=2B` or `<-- id=4 err code=-32601`.
+///
+/// Only the length of the result is printed, so the summary never contains its content. Note
+/// that `to_string()` is still the json of the Response.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, Id, Response, Value};
+///
+/// # fn main() {
+/// let response = Response::success(Id::from(4), 19);
+/// assert_eq!(format!("{}", response), "<-- id=4 ok result=2B");
+///
+/// let response: Response<Value> =
+///     Response::error(Id::from(4), ErrorCode::MethodNotFound, "not found", None);
+/// assert_eq!(format!("{}", response), "<-- id=4 err code=-32601");
+/// # }
+/// ```
+impl<T: Serialize> fmt::Display for Response<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Response::Ok(ref success) => {
+                write!(f, "<-- id={} ok result=", success.id)?;
+                write_len(f, &success.result)
+            }
+            Response::Err(ref error) => {
+                write!(f, "<-- id={} err code={}", error.id, error.error.code.code())
+            }
+        }
+    }
+}
+
+/// The maximum length of the json of the `data` in the summary of an `ErrorObject`.
+const DATA_LIMIT: usize = 100;
+
+/// A one-line summary: `Invalid params (-32602): minuend must be a number`.
+///
+/// The json of the `data` is appended when present, truncated to 100 bytes (followed by `...`).
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::{ErrorCode, ErrorObject};
+///
+/// # fn main() {
+/// let error = ErrorObject {
+///     code: ErrorCode::InvalidParams,
+///     message: "minuend must be a number".into(),
+///     data: Some(json!({"field": "minuend"})),
+/// };
+/// assert_eq!(
+///     error.to_string(),
+///     r#"Invalid params (-32602): minuend must be a number; data: {"field":"minuend"}"#,
+/// );
+/// # }
+/// ```
+impl<T: Serialize> fmt::Display for ErrorObject<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.code.standard_message(), self.code.code(), self.message)?;
+        if let Some(ref data) = self.data {
+            let data = serde_json::to_string(data).map_err(|_| fmt::Error)?;
+            if data.len() <= DATA_LIMIT {
+                write!(f, "; data: {}", data)?;
+            } else {
+                let mut end = DATA_LIMIT;
+                while !data.is_char_boundary(end) {
+                    end -= 1;
+                }
+                write!(f, "; data: {}...", &data[..end])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The summary of the error object prefixed with the id: `id=4 Method not found (-32601): ...`.
+///
+/// Note that `to_string()` is still the json of the Error: use `format!("{}")` for the summary.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{Error, ErrorCode, Id, Value};
+///
+/// # fn main() {
+/// let error: Error<Value> = Error::new(Id::from(4), ErrorCode::MethodNotFound, "no foo", None);
+/// assert_eq!(format!("{}", error), "id=4 Method not found (-32601): no foo");
+/// # }
+/// ```
+impl<T: Serialize> fmt::Display for Error<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "id={} {}", self.id, self.error)
+    }
+}
@@ -0,0 +1,318 @@
+//! The examples from section 7 of the [specification][spec], for conformance tests.
+//!
+//! Each [`Fixture`](struct.Fixture.html) contains the raw request sent by the Client and the
+//! response the Server replies with (if any), exactly as listed in the specification. They assume
+//! a Server implementing the methods of [`SpecMethod`](enum.SpecMethod.html).
+//!
+//! > Note: the error `message`s in the specification's responses are the standard messages
+//! > ("Parse error", "Method not found", etc). This crate puts a more useful reason in the message,
+//! > so compare the error `code` and `id` rather than the message.
+//!
+//! [spec]: http://www.jsonrpc.org/specification#examples
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! use jrpc::fixtures::{self, Expected, SpecMethod};
+//!
+//! # fn main() {
+//! let fixture = fixtures::METHOD_NOT_FOUND;
+//! assert_eq!(fixture.expected, Expected::Error(jrpc::ErrorCode::MethodNotFound));
+//!
+//! let error = jrpc::parse_request::<SpecMethod>(fixture.request).unwrap_err();
+//! assert_eq!(error.error.code, jrpc::ErrorCode::MethodNotFound);
+//! assert_eq!(error.id, jrpc::Id::from("1"));
+//! # }
+//! ```
+
+use super::*;
+
+/// The methods of the Server in the specification's examples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecMethod {
+    /// `subtract`: subtract the positional or named (`minuend`, `subtrahend`) params.
+    Subtract,
+    /// `sum`: sum the positional params.
+    Sum,
+    /// `update`: notification.
+    Update,
+    /// `notify_hello`: notification.
+    NotifyHello,
+    /// `notify_sum`: notification.
+    NotifySum,
+    /// `get_data`: return `["hello", 5]`.
+    GetData,
+}
+
+impl MethodList for SpecMethod {
+    fn method_names() -> &'static [&'static str] {
+        &[
+            "subtract",
+            "sum",
+            "update",
+            "notify_hello",
+            "notify_sum",
+            "get_data",
+        ]
+    }
+}
+
+/// What parsing the `request` of a [`Fixture`](struct.Fixture.html) should produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// `parse_request::<SpecMethod>` succeeds.
+    Request,
+    /// `parse_request::<SpecMethod>` fails with the error code.
+    Error(ErrorCode),
+    /// The request is a batch (a JSON Array).
+    Batch,
+}
+
+/// A single example of the specification.
+#[derive(Debug, Clone, Copy)]
+pub struct Fixture {
+    /// A unique name for the example.
+    pub name: &'static str,
+    /// The raw JSON sent by the Client.
+    pub request: &'static str,
+    /// The raw JSON replied by the Server. `None` if the Server MUST NOT reply.
+    pub response: Option<&'static str>,
+    /// What parsing the request should produce.
+    pub expected: Expected,
+    /// The parsed request, for `Expected::Request`.
+    pub parsed: Option<fn() -> Request<SpecMethod, Value>>,
+}
+
+fn request(id: IdReq, method: SpecMethod, params: Option<Value>) -> Request<SpecMethod, Value> {
+    Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: params,
+        id: id,
+    }
+}
+
+/// rpc call with positional parameters.
+pub const POSITIONAL_1: Fixture = Fixture {
+    name: "positional_1",
+    request: r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+    response: Some(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#),
+    expected: Expected::Request,
+    parsed: Some(positional_1),
+};
+
+fn positional_1() -> Request<SpecMethod, Value> {
+    request(IdReq::Int(1), SpecMethod::Subtract, Some(serde_json::json!([42, 23])))
+}
+
+/// rpc call with positional parameters (swapped).
+pub const POSITIONAL_2: Fixture = Fixture {
+    name: "positional_2",
+    request: r#"{"jsonrpc": "2.0", "method": "subtract", "params": [23, 42], "id": 2}"#,
+    response: Some(r#"{"jsonrpc": "2.0", "result": -19, "id": 2}"#),
+    expected: Expected::Request,
+    parsed: Some(positional_2),
+};
+
+fn positional_2() -> Request<SpecMethod, Value> {
+    request(IdReq::Int(2), SpecMethod::Subtract, Some(serde_json::json!([23, 42])))
+}
+
+/// rpc call with named parameters.
+pub const NAMED_1: Fixture = Fixture {
+    name: "named_1",
+    request: r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"subtrahend": 23, "minuend": 42}, "id": 3}"#,
+    response: Some(r#"{"jsonrpc": "2.0", "result": 19, "id": 3}"#),
+    expected: Expected::Request,
+    parsed: Some(named_1),
+};
+
+fn named_1() -> Request<SpecMethod, Value> {
+    request(
+        IdReq::Int(3),
+        SpecMethod::Subtract,
+        Some(serde_json::json!({"subtrahend": 23, "minuend": 42})),
+    )
+}
+
+/// rpc call with named parameters (reordered).
+pub const NAMED_2: Fixture = Fixture {
+    name: "named_2",
+    request: r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"minuend": 42, "subtrahend": 23}, "id": 4}"#,
+    response: Some(r#"{"jsonrpc": "2.0", "result": 19, "id": 4}"#),
+    expected: Expected::Request,
+    parsed: Some(named_2),
+};
+
+fn named_2() -> Request<SpecMethod, Value> {
+    request(
+        IdReq::Int(4),
+        SpecMethod::Subtract,
+        Some(serde_json::json!({"minuend": 42, "subtrahend": 23})),
+    )
+}
+
+/// a Notification.
+pub const NOTIFICATION_UPDATE: Fixture = Fixture {
+    name: "notification_update",
+    request: r#"{"jsonrpc": "2.0", "method": "update", "params": [1,2,3,4,5]}"#,
+    response: None,
+    expected: Expected::Request,
+    parsed: Some(notification_update),
+};
+
+fn notification_update() -> Request<SpecMethod, Value> {
+    request(IdReq::Notification, SpecMethod::Update, Some(serde_json::json!([1, 2, 3, 4, 5])))
+}
+
+/// a Notification of a non-existent method: the Server still MUST NOT reply.
+pub const NOTIFICATION_FOOBAR: Fixture = Fixture {
+    name: "notification_foobar",
+    request: r#"{"jsonrpc": "2.0", "method": "foobar"}"#,
+    response: None,
+    expected: Expected::Error(ErrorCode::MethodNotFound),
+    parsed: None,
+};
+
+/// rpc call of non-existent method.
+pub const METHOD_NOT_FOUND: Fixture = Fixture {
+    name: "method_not_found",
+    request: r#"{"jsonrpc": "2.0", "method": "foobar", "id": "1"}"#,
+    response: Some(
+        r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "1"}"#,
+    ),
+    expected: Expected::Error(ErrorCode::MethodNotFound),
+    parsed: None,
+};
+
+/// rpc call with invalid JSON.
+pub const INVALID_JSON: Fixture = Fixture {
+    name: "invalid_json",
+    request: r#"{"jsonrpc": "2.0", "method": "foobar, "params": "bar", "baz]"#,
+    response: Some(
+        r#"{"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null}"#,
+    ),
+    expected: Expected::Error(ErrorCode::ParseError),
+    parsed: None,
+};
+
+/// rpc call with invalid Request object.
+pub const INVALID_REQUEST: Fixture = Fixture {
+    name: "invalid_request",
+    request: r#"{"jsonrpc": "2.0", "method": 1, "params": "bar"}"#,
+    response: Some(
+        r#"{"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}"#,
+    ),
+    expected: Expected::Error(ErrorCode::InvalidRequest),
+    parsed: None,
+};
+
+/// rpc call Batch, invalid JSON.
+pub const BATCH_INVALID_JSON: Fixture = Fixture {
+    name: "batch_invalid_json",
+    request: r#"[
+  {"jsonrpc": "2.0", "method": "sum", "params": [1,2,4], "id": "1"},
+  {"jsonrpc": "2.0", "method"
+]"#,
+    response: Some(
+        r#"{"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null}"#,
+    ),
+    expected: Expected::Error(ErrorCode::ParseError),
+    parsed: None,
+};
+
+/// rpc call with an empty Array.
+pub const BATCH_EMPTY: Fixture = Fixture {
+    name: "batch_empty",
+    request: r#"[]"#,
+    response: Some(
+        r#"{"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}"#,
+    ),
+    expected: Expected::Error(ErrorCode::InvalidRequest),
+    parsed: None,
+};
+
+/// rpc call with an invalid Batch (but not empty).
+pub const BATCH_INVALID_ONE: Fixture = Fixture {
+    name: "batch_invalid_one",
+    request: r#"[1]"#,
+    response: Some(
+        r#"[
+  {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}
+]"#,
+    ),
+    expected: Expected::Batch,
+    parsed: None,
+};
+
+/// rpc call with invalid Batch.
+pub const BATCH_INVALID_MANY: Fixture = Fixture {
+    name: "batch_invalid_many",
+    request: r#"[1,2,3]"#,
+    response: Some(
+        r#"[
+  {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+  {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+  {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}
+]"#,
+    ),
+    expected: Expected::Batch,
+    parsed: None,
+};
+
+/// rpc call Batch.
+pub const BATCH: Fixture = Fixture {
+    name: "batch",
+    request: r#"[
+    {"jsonrpc": "2.0", "method": "sum", "params": [1,2,4], "id": "1"},
+    {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]},
+    {"jsonrpc": "2.0", "method": "subtract", "params": [42,23], "id": "2"},
+    {"foo": "boo"},
+    {"jsonrpc": "2.0", "method": "foo.get", "params": {"name": "myself"}, "id": "5"},
+    {"jsonrpc": "2.0", "method": "get_data", "id": "9"}
+]"#,
+    response: Some(
+        r#"[
+    {"jsonrpc": "2.0", "result": 7, "id": "1"},
+    {"jsonrpc": "2.0", "result": 19, "id": "2"},
+    {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+    {"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "5"},
+    {"jsonrpc": "2.0", "result": ["hello", 5], "id": "9"}
+]"#,
+    ),
+    expected: Expected::Batch,
+    parsed: None,
+};
+
+/// rpc call Batch (all notifications): nothing is returned.
+pub const BATCH_NOTIFICATIONS: Fixture = Fixture {
+    name: "batch_notifications",
+    request: r#"[
+    {"jsonrpc": "2.0", "method": "notify_sum", "params": [1,2,4]},
+    {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]}
+]"#,
+    response: None,
+    expected: Expected::Batch,
+    parsed: None,
+};
+
+/// All of the fixtures, in the order of the specification.
+pub const ALL: &[Fixture] = &[
+    POSITIONAL_1,
+    POSITIONAL_2,
+    NAMED_1,
+    NAMED_2,
+    NOTIFICATION_UPDATE,
+    NOTIFICATION_FOOBAR,
+    METHOD_NOT_FOUND,
+    INVALID_JSON,
+    INVALID_REQUEST,
+    BATCH_INVALID_JSON,
+    BATCH_EMPTY,
+    BATCH_INVALID_ONE,
+    BATCH_INVALID_MANY,
+    BATCH,
+    BATCH_NOTIFICATIONS,
+];
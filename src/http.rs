@@ -0,0 +1,161 @@
+//! Helpers to serve jsonrpc over HTTP, independent of the server.
+//!
+//! Serving jsonrpc over HTTP is always the same handler:
+//!
+//! - Check that the body is json: a `Content-Type` other than `application/json` is a
+//!   `415 Unsupported Media Type`. See [`body_str`](fn.body_str.html).
+//! - Parse and handle the Request (or batch of Requests).
+//! - Reply with a `200 OK` containing the json Response(s), or an empty `204 No Content` when
+//!   there is nothing to reply (a Notification, or a batch of only Notifications). See
+//!   [`into_response`](fn.into_response.html).
+//!
+//! A Request which fails, including a body which can't be parsed, is still a `200 OK` with the
+//! jsonrpc Error: the failure is part of the jsonrpc exchange, not of the HTTP one.
+//!
+//! The helpers only depend on the types of the `http` crate, which most servers (`hyper`,
+//! `axum`, `actix-web` with a conversion...) build upon. See `examples/axum.rs` for a server.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate http;
+//! use http::{header, HeaderMap, HeaderValue, StatusCode};
+//! use jrpc::router::Router;
+//!
+//! # fn main() {
+//! let mut router = Router::new();
+//! router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+//!
+//! let body = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+//! let response = jrpc::http::handle(&router, &(), &headers, body);
+//! assert_eq!(response.status(), StatusCode::OK);
+//! assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+//! assert_eq!(response.body(), br#"{"jsonrpc":"2.0","result":19,"id":1}"#);
+//!
+//! let body = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23]}"#;
+//! let response = jrpc::http::handle(&router, &(), &headers, body);
+//! assert_eq!(response.status(), StatusCode::NO_CONTENT);
+//! assert!(response.body().is_empty());
+//! # }
+//! ```
+
+use http_crate::header::{self, HeaderMap, HeaderValue};
+use http_crate::{Response as HttpResponse, StatusCode};
+
+use router::Router;
+
+use super::*;
+
+/// The media type of jsonrpc bodies.
+pub const CONTENT_TYPE: &str = "application/json";
+
+/// Return whether the `Content-Type` of the `headers` is `application/json`.
+///
+/// Parameters (i.e. `; charset=utf-8`) are accepted and the comparison ignores the case. A
+/// missing `Content-Type` is not json.
+pub fn is_json(headers: &HeaderMap) -> bool {
+    let value = match headers.get(header::CONTENT_TYPE).map(HeaderValue::to_str) {
+        Some(Ok(value)) => value,
+        _ => return false,
+    };
+    let essence = value.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case(CONTENT_TYPE)
+}
+
+/// Check the `Content-Type` of the `headers` and decode the `body` as UTF-8.
+///
+/// Fails with the HTTP response to reply:
+///
+/// - `415 Unsupported Media Type` if the body is not json (see [`is_json`](fn.is_json.html)).
+/// - `200 OK` with a `ParseError` if the body is not UTF-8.
+#[allow(clippy::result_large_err)] // the error is returned as the reply
+pub fn body_str<'a>(
+    headers: &HeaderMap,
+    body: &'a [u8],
+) -> Result<&'a str, HttpResponse<Vec<u8>>> {
+    if !is_json(headers) {
+        let message = format!("expected a Content-Type of {}", CONTENT_TYPE);
+        return Err(status_response(StatusCode::UNSUPPORTED_MEDIA_TYPE, message));
+    }
+    ::std::str::from_utf8(body).map_err(|err| {
+        let message = format!("request body is not UTF-8: {}", err);
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+        let error = Error::<Value>::new(Id::Null, ErrorCode::ParseError, message, None);
+        into_response(Some(error.to_string()))
+    })
+}
+
+/// Parse the `body` of a single Request, checking the `headers` with
+/// [`body_str`](fn.body_str.html).
+///
+/// Fails with the HTTP response to reply, containing the jsonrpc Error if the Request is invalid.
+/// Use [`handle_batch`](fn.handle_batch.html) to also accept batches.
+#[allow(clippy::result_large_err)]
+pub fn parse_request_body<M>(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<Request<M, Value>, HttpResponse<Vec<u8>>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    parse_request::<M>(body_str(headers, body)?)
+        .map_err(|error| into_response(Some(error.to_string())))
+}
+
+/// Check the `headers`, then handle the json Request or batch of Requests of the `body` with
+/// `handler`, as in [`router::handle_batch`](../router/fn.handle_batch.html).
+pub fn handle_batch<F>(handler: F, headers: &HeaderMap, body: &[u8]) -> HttpResponse<Vec<u8>>
+where
+    F: FnMut(Value) -> Option<Response<Value>>,
+{
+    match body_str(headers, body) {
+        Ok(json) => into_response(router::handle_batch(handler, json)),
+        Err(response) => response,
+    }
+}
+
+/// Identical to [`handle_batch`](fn.handle_batch.html) with the handlers of the `router`.
+pub fn handle<Ctx>(
+    router: &Router<Ctx>,
+    ctx: &Ctx,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> HttpResponse<Vec<u8>> {
+    match body_str(headers, body) {
+        Ok(json) => into_response(router.handle_batch(ctx, json)),
+        Err(response) => response,
+    }
+}
+
+/// Convert the reply of a handler into the HTTP response.
+///
+/// `Some` json is a `200 OK` with a `Content-Type` of `application/json`. `None` (a Notification,
+/// or a batch of only Notifications) is an empty `204 No Content`.
+pub fn into_response(reply: Option<String>) -> HttpResponse<Vec<u8>> {
+    let mut response = match reply {
+        Some(json) => HttpResponse::new(json.into_bytes()),
+        None => {
+            let mut response = HttpResponse::new(Vec::new());
+            *response.status_mut() = StatusCode::NO_CONTENT;
+            return response;
+        }
+    };
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+    response
+}
+
+/// A plain text response with the `status`, for the failures outside of jsonrpc.
+fn status_response(status: StatusCode, message: String) -> HttpResponse<Vec<u8>> {
+    let mut response = HttpResponse::new(message.into_bytes());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"));
+    response
+}
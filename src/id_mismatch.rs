@@ -0,0 +1,85 @@
+//! Check that a Response belongs to the Request it was received for.
+
+use std::error;
+use std::fmt;
+
+use super::*;
+
+/// The Response doesn't have the id of the Request, see
+/// [`Response::validate_against`](enum.Response.html#method.validate_against).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdMismatch {
+    /// The id of the Request, `Notification` if the Server replied to a Notification.
+    pub request: IdReq,
+    /// The id of the Response.
+    pub response: Id,
+}
+
+impl fmt::Display for IdMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.request.as_id() {
+            Some(id) => write!(
+                f,
+                "the Response id {} doesn't match the Request id {}",
+                self.response, id
+            ),
+            None => write!(
+                f,
+                "the Response with the id {} was received for a Notification",
+                self.response
+            ),
+        }
+    }
+}
+
+impl error::Error for IdMismatch {}
+
+impl<T> Response<T> {
+    /// Check that this is the Response to the `request`, i.e. to catch a Server copying the wrong
+    /// id.
+    ///
+    /// A Response to a Notification is a protocol violation. A `Null` id is only accepted for a
+    /// `ParseError` or `InvalidRequest`: the spec requires it when the Server couldn't detect the
+    /// id of the Request.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, Request, Response, Value};
+    ///
+    /// # fn main() {
+    /// let request = Request::new(Id::from(4), "subtract".to_string());
+    /// assert!(Response::success(Id::from(4), 19).validate_against(&request).is_ok());
+    ///
+    /// let mismatch = Response::success(Id::from(5), 19).validate_against(&request).unwrap_err();
+    /// assert_eq!(mismatch.response, Id::from(5));
+    /// assert_eq!(mismatch.to_string(), "the Response id 5 doesn't match the Request id 4");
+    ///
+    /// let response: Response<Value> =
+    ///     Response::error(Id::Null, ErrorCode::ParseError, "invalid json", None);
+    /// assert!(response.validate_against(&request).is_ok());
+    /// # }
+    /// ```
+    pub fn validate_against<M, P>(&self, request: &Request<M, P>) -> Result<(), IdMismatch> {
+        let id = self.id();
+        // the Server couldn't know the id
+        let undetected = match *self {
+            Response::Err(ref error) => matches!(
+                error.error.code,
+                ErrorCode::ParseError | ErrorCode::InvalidRequest
+            ),
+            Response::Ok(_) => false,
+        } && *id == Id::Null;
+        let matches = match request.id {
+            IdReq::Notification => false,
+            ref expected => *expected == IdReq::from(id.clone()) || undetected,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(IdMismatch {
+                request: request.id.clone(),
+                response: id.clone(),
+            })
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Shared method names, so that servers don't allocate the same names for every Request.
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::*;
+
+/// A method name backed by an `Arc<str>`: cloning it never allocates.
+///
+/// Names from the same [`MethodInterner`](struct.MethodInterner.html) share their allocation.
+/// Equality and hashing are by content, so it can be compared with names from anywhere, but two
+/// names sharing their allocation are equal without comparing the content.
+///
+/// It serializes as a String. Deserializing it allocates a new name: use
+/// [`parse_request_interned`](fn.parse_request_interned.html) to reuse the known names.
+#[derive(Clone, PartialOrd, Ord)]
+pub struct MethodName(Arc<str>);
+
+impl MethodName {
+    /// The name as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return whether both names share their allocation, i.e. were interned by the same
+    /// `MethodInterner`.
+    pub fn ptr_eq(a: &MethodName, b: &MethodName) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl PartialEq for MethodName {
+    fn eq(&self, other: &MethodName) -> bool {
+        MethodName::ptr_eq(self, other) || self.0 == other.0
+    }
+}
+
+impl Eq for MethodName {}
+
+impl Hash for MethodName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the same as `str`, for `Borrow<str>`
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq<str> for MethodName {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for MethodName {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Deref for MethodName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for MethodName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for MethodName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> From<&'a str> for MethodName {
+    fn from(name: &'a str) -> Self {
+        MethodName(Arc::from(name))
+    }
+}
+
+impl From<String> for MethodName {
+    fn from(name: String) -> Self {
+        MethodName(Arc::from(name))
+    }
+}
+
+impl fmt::Debug for MethodName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for MethodName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for MethodName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MethodName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(MethodName::from)
+    }
+}
+
+/// The set of the known method names.
+///
+/// It is shared (i.e. in an `Arc`) between the threads parsing the Requests: looking up a name
+/// only takes a read lock. The names are added with
+/// [`get_or_intern`](struct.MethodInterner.html#method.get_or_intern), e.g. when registering the
+/// handlers.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{MethodInterner, MethodName};
+///
+/// # fn main() {
+/// let interner = MethodInterner::new();
+/// let subtract = interner.get_or_intern("subtract");
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+/// let request = jrpc::parse_request_interned(json, &interner).unwrap();
+/// assert_eq!(request.method, "subtract");
+/// assert!(MethodName::ptr_eq(&request.method, &subtract));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "sum", "params": [42, 23], "id": 2}"#;
+/// let request = jrpc::parse_request_interned(json, &interner).unwrap();
+/// assert_eq!(request.method, "sum");
+/// assert!(interner.get("sum").is_none());
+/// assert_eq!(interner.len(), 1);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MethodInterner {
+    names: RwLock<HashSet<MethodName>>,
+}
+
+impl MethodInterner {
+    /// Create an empty interner.
+    pub fn new() -> Self {
+        MethodInterner::default()
+    }
+
+    /// Return the known name, or add it. Only allocates the first time a name is seen.
+    pub fn get_or_intern(&self, name: &str) -> MethodName {
+        if let Some(known) = self.names.read().unwrap_or_else(PoisonError::into_inner).get(name) {
+            return known.clone();
+        }
+        let mut names = self.names.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(known) = names.get(name) {
+            return known.clone();
+        }
+        let interned = MethodName::from(name);
+        names.insert(interned.clone());
+        interned
+    }
+
+    /// Return the known name, without adding it.
+    pub fn get(&self, name: &str) -> Option<MethodName> {
+        self.names
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned()
+    }
+
+    /// The number of known names.
+    pub fn len(&self) -> usize {
+        self.names.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Return whether no name is known.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that a known method is the
+/// name of the `interner`.
+///
+/// A method the `interner` doesn't know is a new `MethodName`, which is not added to it: the
+/// names of the incoming Requests can't grow the interner.
+pub fn parse_request_interned(
+    json: &str,
+    interner: &MethodInterner,
+) -> Result<Request<MethodName, Value>, Error<Value>> {
+    let envelope = parse_envelope(json, &ParseOptions::default())?;
+    let method = match interner.get(&envelope.method) {
+        Some(known) => known,
+        None => MethodName::from(envelope.method),
+    };
+    Ok(Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: envelope.params,
+        id: envelope.id,
+    })
+}
@@ -0,0 +1,272 @@
+//! Conversions to and from [`jsonrpc-core`](https://docs.rs/jsonrpc-core) types.
+//!
+//! | jrpc                      | jsonrpc-core      |
+//! |---------------------------|-------------------|
+//! | `Id`                      | `Id`              |
+//! | `ErrorCode`               | `ErrorCode`       |
+//! | `ErrorObject<Value>`      | `Error`           |
+//! | `Success<Value>`          | `Success`         |
+//! | `Error<Value>`            | `Failure`         |
+//! | `Response<Value>`         | `Output`          |
+//! | `Request<String, Value>`  | `Call`            |
+//!
+//! # Lossy conversions
+//!
+//! - jsonrpc-core's numeric ids are `u64`, ours are `i64`. Negative ids can't be converted to
+//!   jsonrpc-core and ids above `i64::MAX` can't be converted from it.
+//! - jsonrpc-core's `Params` can only be an Array, an Object or absent. A Request whose `params`
+//!   is any other `Value` can't be converted; `params: Some(Value::Null)` becomes `Params::None`.
+//! - jsonrpc-core represents a notification as its own `Call::Notification` variant, which maps
+//!   to `IdReq::Notification`. `Call::Invalid` has no equivalent and can't be converted.
+//! - jsonrpc-core accepts messages without a `jsonrpc` member (version 1.0). They are converted to
+//!   our 2.0-only types and gain `"jsonrpc": "2.0"` when serialized again.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate jsonrpc_core;
+//! use std::convert::TryFrom;
+//! use jrpc::{Id, Request, Value};
+//!
+//! # fn main() {
+//! let request = Request::with_params(Id::from(1), "add".to_string(), Value::from(vec![1, 2]));
+//! let call = jsonrpc_core::Call::try_from(request).unwrap();
+//! match call {
+//!     jsonrpc_core::Call::MethodCall(ref call) => assert_eq!(call.method, "add"),
+//!     _ => panic!("expected a method call"),
+//! }
+//!
+//! let request = Request::<String, Value>::try_from(call).unwrap();
+//! assert_eq!(request.id, Id::from(1).into());
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use jsonrpc_core as rpc;
+
+use super::ConversionError;
+use {Error, ErrorCode, ErrorObject, Id, IdReq, Request, Response, Success, Value, V2_0};
+
+// ##################################################
+// # ID
+
+impl TryFrom<Id> for rpc::Id {
+    type Error = ConversionError;
+
+    fn try_from(id: Id) -> Result<rpc::Id, ConversionError> {
+        match id {
+            Id::String(s) => Ok(rpc::Id::Str(s)),
+            Id::Int(i) if i >= 0 => Ok(rpc::Id::Num(i as u64)),
+            Id::Int(i) => Err(ConversionError::new(format!(
+                "jsonrpc-core ids cannot be negative, got {}",
+                i
+            ))),
+            Id::Null => Ok(rpc::Id::Null),
+        }
+    }
+}
+
+impl TryFrom<rpc::Id> for Id {
+    type Error = ConversionError;
+
+    fn try_from(id: rpc::Id) -> Result<Id, ConversionError> {
+        match id {
+            rpc::Id::Str(s) => Ok(Id::String(s)),
+            rpc::Id::Num(n) if n <= i64::MAX as u64 => Ok(Id::Int(n as i64)),
+            rpc::Id::Num(n) => Err(ConversionError::new(format!(
+                "id {} does not fit in an i64",
+                n
+            ))),
+            rpc::Id::Null => Ok(Id::Null),
+        }
+    }
+}
+
+// ##################################################
+// # ERRORS
+
+impl From<ErrorCode> for rpc::ErrorCode {
+    fn from(code: ErrorCode) -> rpc::ErrorCode {
+        match code {
+            ErrorCode::ParseError => rpc::ErrorCode::ParseError,
+            ErrorCode::InvalidRequest => rpc::ErrorCode::InvalidRequest,
+            ErrorCode::MethodNotFound => rpc::ErrorCode::MethodNotFound,
+            ErrorCode::InvalidParams => rpc::ErrorCode::InvalidParams,
+            ErrorCode::InternalError => rpc::ErrorCode::InternalError,
+            ErrorCode::ServerError(code) => rpc::ErrorCode::ServerError(code),
+        }
+    }
+}
+
+impl From<rpc::ErrorCode> for ErrorCode {
+    fn from(code: rpc::ErrorCode) -> ErrorCode {
+        ErrorCode::from(code.code())
+    }
+}
+
+impl From<ErrorObject<Value>> for rpc::Error {
+    fn from(error: ErrorObject<Value>) -> rpc::Error {
+        rpc::Error {
+            code: error.code.into(),
+            message: error.message.into_owned(),
+            data: error.data,
+        }
+    }
+}
+
+impl From<rpc::Error> for ErrorObject<Value> {
+    fn from(error: rpc::Error) -> ErrorObject<Value> {
+        ErrorObject {
+            code: error.code.into(),
+            message: error.message.into(),
+            data: error.data,
+        }
+    }
+}
+
+// ##################################################
+// # RESPONSES
+
+impl TryFrom<Success<Value>> for rpc::Success {
+    type Error = ConversionError;
+
+    fn try_from(success: Success<Value>) -> Result<rpc::Success, ConversionError> {
+        Ok(rpc::Success {
+            jsonrpc: Some(rpc::Version::V2),
+            result: success.result,
+            id: rpc::Id::try_from(success.id)?,
+        })
+    }
+}
+
+impl TryFrom<rpc::Success> for Success<Value> {
+    type Error = ConversionError;
+
+    fn try_from(success: rpc::Success) -> Result<Success<Value>, ConversionError> {
+        Ok(Success {
+            jsonrpc: V2_0,
+            result: success.result,
+            id: Id::try_from(success.id)?,
+        })
+    }
+}
+
+impl TryFrom<Error<Value>> for rpc::Failure {
+    type Error = ConversionError;
+
+    fn try_from(error: Error<Value>) -> Result<rpc::Failure, ConversionError> {
+        Ok(rpc::Failure {
+            jsonrpc: Some(rpc::Version::V2),
+            error: error.error.into(),
+            id: rpc::Id::try_from(error.id)?,
+        })
+    }
+}
+
+impl TryFrom<rpc::Failure> for Error<Value> {
+    type Error = ConversionError;
+
+    fn try_from(failure: rpc::Failure) -> Result<Error<Value>, ConversionError> {
+        Ok(Error {
+            jsonrpc: V2_0,
+            error: failure.error.into(),
+            id: Id::try_from(failure.id)?,
+        })
+    }
+}
+
+impl TryFrom<Response<Value>> for rpc::Output {
+    type Error = ConversionError;
+
+    fn try_from(response: Response<Value>) -> Result<rpc::Output, ConversionError> {
+        match response {
+            Response::Ok(success) => rpc::Success::try_from(success).map(rpc::Output::Success),
+            Response::Err(error) => rpc::Failure::try_from(error).map(rpc::Output::Failure),
+        }
+    }
+}
+
+impl TryFrom<rpc::Output> for Response<Value> {
+    type Error = ConversionError;
+
+    fn try_from(output: rpc::Output) -> Result<Response<Value>, ConversionError> {
+        match output {
+            rpc::Output::Success(success) => Success::try_from(success).map(Response::Ok),
+            rpc::Output::Failure(failure) => Error::try_from(failure).map(Response::Err),
+        }
+    }
+}
+
+// ##################################################
+// # REQUESTS
+
+/// Convert `Request.params` into jsonrpc-core's `Params`.
+///
+/// Fails if the params are not Structured (an Array or an Object) or `Null`.
+pub fn to_params(params: Option<Value>) -> Result<rpc::Params, ConversionError> {
+    match params {
+        None | Some(Value::Null) => Ok(rpc::Params::None),
+        Some(Value::Array(values)) => Ok(rpc::Params::Array(values)),
+        Some(Value::Object(map)) => Ok(rpc::Params::Map(map)),
+        Some(other) => Err(ConversionError::new(format!(
+            "jsonrpc-core params must be an Array or an Object, got {}",
+            other
+        ))),
+    }
+}
+
+/// Convert jsonrpc-core's `Params` into `Request.params`. This never fails.
+pub fn from_params(params: rpc::Params) -> Option<Value> {
+    match params {
+        rpc::Params::None => None,
+        rpc::Params::Array(values) => Some(Value::Array(values)),
+        rpc::Params::Map(map) => Some(Value::Object(map)),
+    }
+}
+
+impl TryFrom<Request<String, Value>> for rpc::Call {
+    type Error = ConversionError;
+
+    fn try_from(request: Request<String, Value>) -> Result<rpc::Call, ConversionError> {
+        let params = to_params(request.params)?;
+        let call = match request.id.into_id() {
+            Some(id) => rpc::Call::MethodCall(rpc::MethodCall {
+                jsonrpc: Some(rpc::Version::V2),
+                method: request.method,
+                params: params,
+                id: rpc::Id::try_from(id)?,
+            }),
+            None => rpc::Call::Notification(rpc::Notification {
+                jsonrpc: Some(rpc::Version::V2),
+                method: request.method,
+                params: params,
+            }),
+        };
+        Ok(call)
+    }
+}
+
+impl TryFrom<rpc::Call> for Request<String, Value> {
+    type Error = ConversionError;
+
+    fn try_from(call: rpc::Call) -> Result<Request<String, Value>, ConversionError> {
+        match call {
+            rpc::Call::MethodCall(call) => Ok(Request {
+                jsonrpc: V2_0,
+                method: call.method,
+                params: from_params(call.params),
+                id: Id::try_from(call.id)?.into(),
+            }),
+            rpc::Call::Notification(notification) => Ok(Request {
+                jsonrpc: V2_0,
+                method: notification.method,
+                params: from_params(notification.params),
+                id: IdReq::Notification,
+            }),
+            rpc::Call::Invalid { .. } => Err(ConversionError::new(
+                "an invalid jsonrpc-core call has no Request equivalent",
+            )),
+        }
+    }
+}
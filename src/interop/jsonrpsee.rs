@@ -0,0 +1,241 @@
+//! Conversions to and from [`jsonrpsee-types`](https://docs.rs/jsonrpsee-types) types, the
+//! types of `jsonrpsee`.
+//!
+//! | jrpc                      | jsonrpsee-types                                  |
+//! |---------------------------|--------------------------------------------------|
+//! | `Id`                      | `Id<'a>`                                         |
+//! | `ErrorObject<Value>`      | `ErrorObject<'a>`                                |
+//! | `Request<String, Value>`  | `Request<'a>`, `Notification<'a, Option<Value>>` |
+//! | `Response<Value>`         | `Response<'a, Value>`                            |
+//!
+//! The conversions into jsonrpsee-types create the owned (`'static`) types. The conversions
+//! from them accept any lifetime, copying out of the borrowed `Cow`s.
+//!
+//! # Lossy conversions
+//!
+//! - jsonrpsee's numeric ids are `u64`, ours are `i64`. Negative ids can't be converted to
+//!   jsonrpsee and ids above `i64::MAX` can't be converted from it.
+//! - jsonrpsee's error codes are `i32`, ours are `i64`. Codes outside of the `i32` range can't be
+//!   converted to jsonrpsee.
+//! - jsonrpsee keeps the `params` and the error `data` as unparsed json (`RawValue`). They are
+//!   parsed into a `Value` when converting from jsonrpsee, which fails if the json is nested too
+//!   deep for `serde_json`.
+//! - A jsonrpsee `Request` always has an id and a `Notification` never does: a Request is
+//!   converted to one or the other depending on its `id`, the wrong one fails.
+//! - A `Notification<Option<Value>>` serializes absent params as `"params": null`, which is
+//!   read back as absent.
+//! - The `extensions` of a jsonrpsee `Request` (the http extensions of the transport) are
+//!   dropped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate jsonrpsee_types;
+//! use std::convert::TryFrom;
+//! use jrpc::{Id, Request, Value};
+//!
+//! # fn main() {
+//! let request = Request::with_params(Id::from(1), "add".to_string(), Value::from(vec![1, 2]));
+//! let call = jsonrpsee_types::Request::try_from(request).unwrap();
+//! assert_eq!(call.method_name(), "add");
+//! assert_eq!(call.id, jsonrpsee_types::Id::Number(1));
+//!
+//! let request = Request::<String, Value>::try_from(call).unwrap();
+//! assert_eq!(request.id, Id::from(1).into());
+//! assert_eq!(request.params, Some(Value::from(vec![1, 2])));
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use jsonrpsee_types as rpc;
+use serde_json::value::{to_raw_value, RawValue};
+
+use super::ConversionError;
+use {Error, ErrorCode, ErrorObject, Id, IdReq, Request, Response, Success, Value, V2_0};
+
+// ##################################################
+// # ID
+
+impl TryFrom<Id> for rpc::Id<'static> {
+    type Error = ConversionError;
+
+    fn try_from(id: Id) -> Result<rpc::Id<'static>, ConversionError> {
+        match id {
+            Id::String(s) => Ok(rpc::Id::Str(Cow::Owned(s))),
+            Id::Int(i) if i >= 0 => Ok(rpc::Id::Number(i as u64)),
+            Id::Int(i) => Err(ConversionError::new(format!(
+                "jsonrpsee ids cannot be negative, got {}",
+                i
+            ))),
+            Id::Null => Ok(rpc::Id::Null),
+        }
+    }
+}
+
+impl<'a> TryFrom<rpc::Id<'a>> for Id {
+    type Error = ConversionError;
+
+    fn try_from(id: rpc::Id<'a>) -> Result<Id, ConversionError> {
+        match id {
+            rpc::Id::Str(s) => Ok(Id::String(s.into_owned())),
+            rpc::Id::Number(n) if n <= i64::MAX as u64 => Ok(Id::Int(n as i64)),
+            rpc::Id::Number(n) => Err(ConversionError::new(format!(
+                "id {} does not fit in an i64",
+                n
+            ))),
+            rpc::Id::Null => Ok(Id::Null),
+        }
+    }
+}
+
+// ##################################################
+// # ERRORS
+
+/// Parse the unparsed json of jsonrpsee.
+fn from_raw(raw: &RawValue) -> Result<Value, ConversionError> {
+    serde_json::from_str(raw.get()).map_err(|err| ConversionError::new(err.to_string()))
+}
+
+/// Write the `value` as the unparsed json of jsonrpsee.
+fn into_raw(value: &Value) -> Result<Box<RawValue>, ConversionError> {
+    to_raw_value(value).map_err(|err| ConversionError::new(err.to_string()))
+}
+
+impl TryFrom<ErrorObject<Value>> for rpc::ErrorObjectOwned {
+    type Error = ConversionError;
+
+    fn try_from(error: ErrorObject<Value>) -> Result<rpc::ErrorObjectOwned, ConversionError> {
+        let code = error.code.code();
+        if code < i64::from(i32::MIN) || code > i64::from(i32::MAX) {
+            return Err(ConversionError::new(format!(
+                "jsonrpsee error codes are i32, got {}",
+                code
+            )));
+        }
+        Ok(rpc::ErrorObject::owned(code as i32, error.message, error.data))
+    }
+}
+
+impl<'a> TryFrom<rpc::ErrorObject<'a>> for ErrorObject<Value> {
+    type Error = ConversionError;
+
+    fn try_from(error: rpc::ErrorObject<'a>) -> Result<ErrorObject<Value>, ConversionError> {
+        Ok(ErrorObject {
+            code: ErrorCode::from(i64::from(error.code())),
+            message: error.message().to_string().into(),
+            // a `"data": null` is absent, like when deserializing an ErrorObject
+            data: error
+                .data()
+                .filter(|data| data.get() != "null")
+                .map(from_raw)
+                .transpose()?,
+        })
+    }
+}
+
+// ##################################################
+// # RESPONSES
+
+impl TryFrom<Response<Value>> for rpc::Response<'static, Value> {
+    type Error = ConversionError;
+
+    fn try_from(
+        response: Response<Value>,
+    ) -> Result<rpc::Response<'static, Value>, ConversionError> {
+        match response {
+            Response::Ok(success) => Ok(rpc::Response::new(
+                rpc::ResponsePayload::success(success.result),
+                rpc::Id::try_from(success.id)?,
+            )),
+            Response::Err(error) => Ok(rpc::Response::new(
+                rpc::ResponsePayload::error(rpc::ErrorObjectOwned::try_from(error.error)?),
+                rpc::Id::try_from(error.id)?,
+            )),
+        }
+    }
+}
+
+impl<'a> TryFrom<rpc::Response<'a, Value>> for Response<Value> {
+    type Error = ConversionError;
+
+    fn try_from(response: rpc::Response<'a, Value>) -> Result<Response<Value>, ConversionError> {
+        let id = Id::try_from(response.id)?;
+        match response.payload {
+            rpc::ResponsePayload::Success(result) => Ok(Response::Ok(Success {
+                jsonrpc: V2_0,
+                result: result.into_owned(),
+                id: id,
+            })),
+            rpc::ResponsePayload::Error(error) => Ok(Response::Err(Error {
+                jsonrpc: V2_0,
+                error: ErrorObject::try_from(error)?,
+                id: id,
+            })),
+        }
+    }
+}
+
+// ##################################################
+// # REQUESTS
+
+impl TryFrom<Request<String, Value>> for rpc::Request<'static> {
+    type Error = ConversionError;
+
+    /// Fails if the Request is a Notification.
+    fn try_from(
+        request: Request<String, Value>,
+    ) -> Result<rpc::Request<'static>, ConversionError> {
+        let id = match request.id.into_id() {
+            Some(id) => rpc::Id::try_from(id)?,
+            None => {
+                return Err(ConversionError::new(
+                    "a notification has no jsonrpsee Request equivalent",
+                ))
+            }
+        };
+        let params = request.params.as_ref().map(into_raw).transpose()?;
+        Ok(rpc::Request::owned(request.method, params, id))
+    }
+}
+
+impl<'a> TryFrom<rpc::Request<'a>> for Request<String, Value> {
+    type Error = ConversionError;
+
+    fn try_from(call: rpc::Request<'a>) -> Result<Request<String, Value>, ConversionError> {
+        Ok(Request {
+            jsonrpc: V2_0,
+            params: call.params.as_ref().map(|params| from_raw(params)).transpose()?,
+            method: call.method.into_owned(),
+            id: Id::try_from(call.id)?.into(),
+        })
+    }
+}
+
+impl TryFrom<Request<String, Value>> for rpc::Notification<'static, Option<Value>> {
+    type Error = ConversionError;
+
+    /// Fails if the Request is not a Notification.
+    fn try_from(
+        request: Request<String, Value>,
+    ) -> Result<rpc::Notification<'static, Option<Value>>, ConversionError> {
+        if request.id != IdReq::Notification {
+            return Err(ConversionError::new(
+                "a Request with an id has no jsonrpsee Notification equivalent",
+            ));
+        }
+        Ok(rpc::Notification::new(Cow::Owned(request.method), request.params))
+    }
+}
+
+impl<'a> From<rpc::Notification<'a, Option<Value>>> for Request<String, Value> {
+    fn from(notification: rpc::Notification<'a, Option<Value>>) -> Request<String, Value> {
+        Request {
+            jsonrpc: V2_0,
+            method: notification.method.into_owned(),
+            params: notification.params,
+            id: IdReq::Notification,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Conversions to and from the types of other jsonrpc crates.
+//!
+//! Each crate is behind its own feature:
+//!
+//! - `interop-jsonrpc-core`: [`jsonrpc-core`](https://docs.rs/jsonrpc-core). See the
+//!   [`jsonrpc_core`](jsonrpc_core/index.html) module for what is lossy.
+//! - `interop-jsonrpsee`: [`jsonrpsee-types`](https://docs.rs/jsonrpsee-types), the types of
+//!   `jsonrpsee`. See the [`jsonrpsee`](jsonrpsee/index.html) module for what is lossy.
+//!
+//! The conversions are all `From`/`TryFrom` implementations. A `TryFrom` is used whenever the
+//! other side cannot represent every value; it fails with a
+//! [`ConversionError`](struct.ConversionError.html).
+
+use std::error;
+use std::fmt;
+
+#[cfg(feature = "interop-jsonrpc-core")]
+pub mod jsonrpc_core;
+#[cfg(feature = "interop-jsonrpsee")]
+pub mod jsonrpsee;
+
+/// A value could not be represented by the target type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    /// Why the conversion failed.
+    pub reason: String,
+}
+
+impl ConversionError {
+    pub(crate) fn new<S: Into<String>>(reason: S) -> ConversionError {
+        ConversionError {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "conversion failed: {}", self.reason)
+    }
+}
+
+impl error::Error for ConversionError {}
@@ -5,6 +5,64 @@
 //! This crate never touches the network, filesystem, etc. It simply uses serde
 //! to easily construct, serialize and deserialize Request and Response data types.
 //!
+//! # Features
+//!
+//! All features are off by default.
+//!
+//! - `schemars`: implement `schemars::JsonSchema` for the types so that JSON Schema documents can
+//!   be generated for the envelope.
+//! - `openrpc`: the [`openrpc`](openrpc/index.html) module, containing the types of an OpenRPC
+//!   service description document (as returned by `rpc.discover`).
+//! - `proptest`: implement `proptest::arbitrary::Arbitrary` for the types. See the
+//!   [`strategy`](strategy/index.html) module.
+//! - `test-fixtures`: the [`fixtures`](fixtures/index.html) module, containing the examples of
+//!   the specification for conformance tests.
+//! - `interop-jsonrpc-core`: `From`/`TryFrom` conversions to and from the types of the
+//!   `jsonrpc-core` crate. See the [`interop`](interop/index.html) module.
+//! - `interop-jsonrpsee`: `From`/`TryFrom` conversions to and from the types of the
+//!   `jsonrpsee-types` crate (used by `jsonrpsee`). See the [`interop`](interop/index.html) module.
+//! - `wasm`: `from_js`/`to_js` on `Request` and `Response`, converting to and from a `JsValue`
+//!   with `serde_wasm_bindgen`. Only has an effect when compiling for `wasm32`.
+//! - `rayon`: handle the elements of a batch in parallel with
+//!   [`router::handle_batch_parallel`](router/fn.handle_batch_parallel.html).
+//! - `tracing`: emit [`tracing`](https://docs.rs/tracing) events when parsing Requests and a span
+//!   around the handlers of the [`Router`](router/struct.Router.html). Without it nothing is
+//!   emitted and `tracing` is not a dependency.
+//! - `json5`: [`parse_request_lenient_json`](fn.parse_request_lenient_json.html), accepting the
+//!   sloppy json of some clients (trailing commas, single quotes, comments).
+//! - `simd`: [`parse_request_simd`](fn.parse_request_simd.html) and
+//!   [`router::handle_batch_simd`](router/fn.handle_batch_simd.html), parsing with `simd-json`
+//!   instead of `serde_json`. The Requests and errors are the same.
+//! - `futures`: [`router::AsyncRouter`](router/struct.AsyncRouter.html), a Router whose handlers
+//!   return futures. It doesn't depend on any runtime.
+//! - `tower`: [`router::RouterService`](router/struct.RouterService.html), a `tower::Service`
+//!   wrapping a Router (and `AsyncRouterService` for the `AsyncRouter`), for middleware stacks
+//!   built with `tower`.
+//! - `http`: the [`http`](http/index.html) module, mapping the bodies of HTTP requests to the
+//!   parsers and the replies to the responses of the `http` crate, whatever the server.
+//! - `bytes`: `to_bytes` on the Requests and Responses, serializing directly into a
+//!   `bytes::BytesMut`, and [`parse_request_bytes`](fn.parse_request_bytes.html).
+//! - `preserve_order`: enable the feature of the same name of `serde_json`, so that the members
+//!   of an Object keep their order when going through a `Value`. This only affects `Value`-typed
+//!   params (i.e. [`parse_request`](fn.parse_request.html) and the proxies): typed params
+//!   serialize in the order of their fields either way.
+//!
+//! # Untrusted input
+//!
+//! The parsers are meant to be fed the bytes received from the network as they are: no input
+//! makes [`parse_request`](fn.parse_request.html) (and its variants),
+//! [`router::handle_batch`](router/fn.handle_batch.html) (and the `Router`),
+//! [`parse_response_exact`](fn.parse_response_exact.html),
+//! [`parse_response_lenient`](fn.parse_response_lenient.html) or
+//! [`ClientState::on_message`](client/struct.ClientState.html#method.on_message) panic, overflow
+//! the stack or loop. Invalid input is an error, i.e. nesting deeper than
+//! [`DEFAULT_MAX_DEPTH`](constant.DEFAULT_MAX_DEPTH.html) is a `ParseError`. The time and memory
+//! are linear in the length of the input.
+//!
+//! This is checked by the fuzz targets in the `fuzz` directory (run with `cargo fuzz`), and the
+//! inputs in `tests/data/fuzz` are run through the same entry points by the tests. Add the
+//! inputs of any crash found by the fuzzers there.
+//!
 //! # Specification
 //!
 //! The below is directly copy/pasted from: [http://www.jsonrpc.org/specification][spec]
@@ -108,23 +166,129 @@
 //! This library does not support checking for extensions. See
 //! [`Request.method`](struct.Request.html#structfield.method) for more details of the spec.
 #![allow(unknown_lints)]
-#![allow(redundant_field_names)]
+#![allow(clippy::redundant_field_names)]
+#![allow(clippy::inherent_to_string, clippy::should_implement_trait)]
+#![allow(clippy::inherent_to_string_shadow_display)]
+// `Error<Value>` is the error of every parser and handler: with the `preserve_order` feature the
+// IndexMap of `Value` makes it larger than the threshold of this lint. Boxing it would change the
+// public signatures for the sake of one feature.
+#![allow(clippy::result_large_err)]
 #![warn(missing_docs)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
 
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
 extern crate std_prelude;
+#[cfg(feature = "schemars")]
+#[macro_use]
+extern crate schemars;
+#[cfg(feature = "proptest")]
+extern crate proptest;
+#[cfg(feature = "interop-jsonrpc-core")]
+extern crate jsonrpc_core;
+#[cfg(feature = "interop-jsonrpsee")]
+extern crate jsonrpsee_types;
+#[cfg(feature = "futures")]
+extern crate futures;
+#[cfg(feature = "json5")]
+extern crate json5;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "simd")]
+extern crate simd_json;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "tower")]
+extern crate tower_service;
+#[cfg(feature = "http")]
+extern crate http as http_crate;
+#[cfg(feature = "bytes")]
+extern crate bytes as bytes_crate;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+extern crate serde_wasm_bindgen;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+extern crate wasm_bindgen;
 
 pub use serde_json::Value;
 
+/// What the exported macros use, not part of the API.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json::{to_value, Map};
+
+    /// The `value` for `error_data!`, or the reason it failed to serialize.
+    pub fn to_data<T: ::serde::Serialize>(value: &T) -> ::serde_json::Value {
+        to_value(value).unwrap_or_else(|err| {
+            ::serde_json::Value::String(format!("failed to serialize: {}", err))
+        })
+    }
+}
+
+#[macro_use]
+mod macros;
 mod serialize;
+mod builder;
+mod cache_key;
+mod cased;
+mod display;
+mod id_mismatch;
+mod interned;
+mod method;
+mod method_set;
+mod namespaced;
+mod one_or_many;
+mod redacted;
+mod string_or_int;
+mod unknown_method;
+mod value_ext;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "openrpc")]
+pub mod openrpc;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
+pub mod testing;
+pub mod router;
+pub mod client;
+pub mod proxy;
+pub mod cancel;
+pub mod responses;
+pub mod prelude;
+pub mod progress;
+pub mod subscription;
+#[cfg(any(feature = "interop-jsonrpc-core", feature = "interop-jsonrpsee"))]
+pub mod interop;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm;
+#[cfg(feature = "bytes")]
+mod bytes;
 
 use std_prelude::*;
 use serde::ser::Serialize;
 use serde::de::DeserializeOwned;
 
+pub use builder::{BuildError, ErrorObjectBuilder, RequestBuilder, ResponseBuilder};
+pub use cache_key::dedup_by_content;
+pub use cased::{CamelCase, CaseConvention, Cased, SnakeCase};
+pub use id_mismatch::IdMismatch;
+pub use interned::{parse_request_interned, MethodInterner, MethodName};
+pub use namespaced::{Dot, NamespacedMethod, Separator, Underscore};
+pub use one_or_many::OneOrMany;
+pub use redacted::Redacted;
+pub use string_or_int::StringOrInt;
+pub use unknown_method::UnknownMethod;
+pub use value_ext::{JrpcValueExt, MessageKind};
+pub use method_set::{MethodSet, DEFAULT_MAX_LISTED};
+pub use method::{call, decode_response, notify, Method, NotificationMethod, RpcMethod};
+#[cfg(feature = "bytes")]
+pub use bytes::parse_request_bytes;
+
 /// The `jsonrpc` version. Will serialize/deserialize to/from `"2.0"`.
 pub struct V2_0;
 
@@ -158,7 +322,12 @@ pub struct V2_0;
 /// );
 /// # }
 /// ```
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "An identifier established by the Client: a String, an integer Number or Null.")
+)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize)]
 #[serde(untagged)]
 pub enum Id {
     /// An String id
@@ -189,6 +358,200 @@ impl From<i64> for Id {
     }
 }
 
+/// The json of the id, i.e. to embed it in the data of an error.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use std::convert::TryFrom;
+/// use jrpc::{Id, Value};
+///
+/// # fn main() {
+/// let id = Id::from(4);
+/// assert_eq!(json!({"cancelled": Value::from(&id)}), json!({"cancelled": 4}));
+/// assert_eq!(Id::try_from(Value::from(id.clone())), Ok(id));
+///
+/// let error = Id::try_from(json!(1.5)).unwrap_err();
+/// assert_eq!(error.to_string(), "id must be a string, integer, or null; got a float");
+/// # }
+/// ```
+impl From<Id> for Value {
+    fn from(id: Id) -> Self {
+        match id {
+            Id::String(s) => Value::String(s),
+            Id::Int(i) => Value::from(i),
+            Id::Null => Value::Null,
+        }
+    }
+}
+
+impl<'a> From<&'a Id> for Value {
+    fn from(id: &'a Id) -> Self {
+        Value::from(id.clone())
+    }
+}
+
+impl ::std::convert::TryFrom<Value> for Id {
+    type Error = InvalidId;
+
+    fn try_from(value: Value) -> Result<Id, InvalidId> {
+        let got = match value {
+            Value::String(s) => return Ok(Id::String(s)),
+            Value::Null => return Ok(Id::Null),
+            Value::Number(ref n) => match n.as_i64() {
+                Some(i) => return Ok(Id::Int(i)),
+                None if n.is_f64() => "a float",
+                None => "an integer out of the range of i64",
+            },
+            Value::Bool(_) => "a boolean",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+        Err(InvalidId { got: got })
+    }
+}
+
+/// A json Value which is not an [`Id`](enum.Id.html), see `TryFrom<Value> for Id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidId {
+    /// What the value is instead, i.e. `"a boolean"`.
+    pub got: &'static str,
+}
+
+impl ::std::fmt::Display for InvalidId {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "id must be a string, integer, or null; got {}", self.got)
+    }
+}
+
+impl ::std::error::Error for InvalidId {}
+
+impl Id {
+    /// The integer of an integer id.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::Id;
+    ///
+    /// # fn main() {
+    /// assert_eq!(Id::from(7).as_int(), Some(7));
+    /// assert_eq!(Id::from("7").as_int(), None);
+    /// # }
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Id::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Borrow the String of a String id.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::Id;
+    ///
+    /// # fn main() {
+    /// assert_eq!(Id::from("a").as_str(), Some("a"));
+    /// assert_eq!(Id::from(1).as_str(), None);
+    /// # }
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Id::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Return whether the id is `null`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::Id;
+    ///
+    /// # fn main() {
+    /// assert!(Id::Null.is_null());
+    /// assert!(!Id::from("null").is_null());
+    /// # }
+    /// ```
+    pub fn is_null(&self) -> bool {
+        matches!(*self, Id::Null)
+    }
+
+    /// Return whether the ids are equal, or one is an integer and the other the String of its
+    /// decimal form (without leading zeros or whitespace), i.e. for Servers replying to `7` with
+    /// `"7"`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::Id;
+    ///
+    /// # fn main() {
+    /// assert!(Id::from(7).eq_loose(&Id::from("7")));
+    /// assert!(!Id::from(7).eq_loose(&Id::from("07")));
+    /// assert!(!Id::Null.eq_loose(&Id::from("null")));
+    /// # }
+    /// ```
+    pub fn eq_loose(&self, other: &Id) -> bool {
+        match (self, other) {
+            (Id::Int(i), Id::String(s)) | (Id::String(s), Id::Int(i)) => *s == i.to_string(),
+            _ => self == other,
+        }
+    }
+
+    // The other id which is `eq_loose` to this one, if any.
+    fn loose_alternative(&self) -> Option<Id> {
+        match *self {
+            Id::Int(i) => Some(Id::String(i.to_string())),
+            Id::String(ref s) => s
+                .parse::<i64>()
+                .ok()
+                .filter(|i| *s == i.to_string())
+                .map(Id::Int),
+            Id::Null => None,
+        }
+    }
+}
+
+/// `Null` is before the integers, which are before the Strings. Integers are ordered by value and
+/// Strings by their bytes.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::Id;
+///
+/// # fn main() {
+/// let mut ids = vec![Id::from("a"), Id::from(10), Id::Null, Id::from(-1), Id::from("B")];
+/// ids.sort();
+/// assert_eq!(
+///     ids,
+///     vec![Id::Null, Id::from(-1), Id::from(10), Id::from("B"), Id::from("a")],
+/// );
+/// # }
+/// ```
+impl Ord for Id {
+    fn cmp(&self, other: &Id) -> ::std::cmp::Ordering {
+        fn rank(id: &Id) -> u8 {
+            match *id {
+                Id::Null => 0,
+                Id::Int(_) => 1,
+                Id::String(_) => 2,
+            }
+        }
+        match (self, other) {
+            (Id::Int(a), Id::Int(b)) => a.cmp(b),
+            (Id::String(a), Id::String(b)) => a.cmp(b),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl PartialOrd for Id {
+    fn partial_cmp(&self, other: &Id) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Identical to [`Id`](enum.Id.html) except has the Notification type. Typically you should use
 /// `Id` since all functions that would accept IdReq accept `Into<IdReq>`.
 ///
@@ -236,7 +599,7 @@ impl From<i64> for Id {
 /// assert_eq!(request.id, IdReq::Notification);
 /// # }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 #[serde(untagged)]
 pub enum IdReq {
     /// An String id
@@ -251,6 +614,27 @@ pub enum IdReq {
     Notification,
 }
 
+/// A borrowed [`Id`](enum.Id.html), see [`IdReq::id_ref`](enum.IdReq.html#method.id_ref).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdRef<'a> {
+    /// An String id
+    String(&'a str),
+    /// An Number id
+    Int(i64),
+    /// A null id
+    Null,
+}
+
+impl<'a> From<IdRef<'a>> for Id {
+    fn from(id: IdRef<'a>) -> Self {
+        match id {
+            IdRef::String(s) => Id::String(s.to_owned()),
+            IdRef::Int(i) => Id::Int(i),
+            IdRef::Null => Id::Null,
+        }
+    }
+}
+
 impl From<Id> for IdReq {
     fn from(id: Id) -> Self {
         match id {
@@ -265,7 +649,30 @@ impl IdReq {
     /// Attempt to convert to an Id.
     ///
     /// Returns `None` if this Id is a `Notification`.
+    #[deprecated(since = "0.5.0", note = "renamed to `into_id`")]
     pub fn to_id(self) -> Option<Id> {
+        self.into_id()
+    }
+
+    /// The id to reply with, moved out of the Request so that a String id is not copied.
+    ///
+    /// Returns `None` for a Notification, which MUST NOT be replied to.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{Id, IdReq, Response};
+    ///
+    /// # fn main() {
+    /// let request = jrpc::parse_request::<String>(
+    ///     r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": "a"}"#,
+    /// ).unwrap();
+    /// let response = Response::success(request.id.into_id().unwrap(), 19);
+    /// assert_eq!(response.id(), &Id::from("a"));
+    ///
+    /// assert_eq!(IdReq::Notification.into_id(), None);
+    /// # }
+    /// ```
+    pub fn into_id(self) -> Option<Id> {
         let out = match self {
             IdReq::String(s) => Id::String(s),
             IdReq::Int(i) => Id::Int(i),
@@ -274,6 +681,90 @@ impl IdReq {
         };
         Some(out)
     }
+
+    /// Identical to [`into_id`](#method.into_id) but without consuming the IdReq: only the
+    /// String of a String id is copied.
+    pub fn as_id(&self) -> Option<Id> {
+        self.id_ref().map(Id::from)
+    }
+
+    /// Borrow the id, or `None` if this is a `Notification`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{IdRef, IdReq};
+    ///
+    /// # fn main() {
+    /// let id = IdReq::String("a".into());
+    /// assert_eq!(id.id_ref(), Some(IdRef::String("a")));
+    /// assert_eq!(IdReq::Int(1).id_ref(), Some(IdRef::Int(1)));
+    /// assert_eq!(IdReq::Notification.id_ref(), None);
+    /// # }
+    /// ```
+    pub fn id_ref(&self) -> Option<IdRef<'_>> {
+        match *self {
+            IdReq::String(ref s) => Some(IdRef::String(s)),
+            IdReq::Int(i) => Some(IdRef::Int(i)),
+            IdReq::Null => Some(IdRef::Null),
+            IdReq::Notification => None,
+        }
+    }
+
+    /// Return whether the id is absent, i.e. the Request is a Notification.
+    pub fn is_notification(&self) -> bool {
+        matches!(*self, IdReq::Notification)
+    }
+
+    /// Return whether the id is `null`. A Notification is not `null`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::IdReq;
+    ///
+    /// # fn main() {
+    /// assert!(IdReq::Null.is_null());
+    /// assert!(!IdReq::Notification.is_null());
+    /// # }
+    /// ```
+    pub fn is_null(&self) -> bool {
+        matches!(*self, IdReq::Null)
+    }
+
+    /// The integer of an integer id, `None` for the other ids and a Notification.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::IdReq;
+    ///
+    /// # fn main() {
+    /// assert_eq!(IdReq::Int(7).as_int(), Some(7));
+    /// assert_eq!(IdReq::Notification.as_int(), None);
+    /// # }
+    /// ```
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            IdReq::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Borrow the String of a String id, `None` for the other ids and a Notification.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::IdReq;
+    ///
+    /// # fn main() {
+    /// assert_eq!(IdReq::String("a".into()).as_str(), Some("a"));
+    /// assert_eq!(IdReq::Notification.as_str(), None);
+    /// # }
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            IdReq::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
 }
 
 /// A rpc call is represented by sending a Request object to a Server.
@@ -302,11 +793,15 @@ impl IdReq {
 ///     "id": 4
 /// }
 /// "#;
-/// let json = json.replace("\n", "").replace(" ", "");
 /// let result = serde_json::to_string(&request).unwrap();
-/// assert_eq!(json, result);
+/// jrpc::testing::assert_json_eq(&result, json);
 /// # }
 /// ```
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "A rpc call is represented by sending a Request object to a Server.")
+)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Request<M, T> {
     /// A String specifying the version of the JSON-RPC protocol. MUST be exactly "2.0".
@@ -324,7 +819,8 @@ pub struct Request<M, T> {
     /// used for anything else. Each system extension is defined in a related specification. All
     /// system extensions are OPTIONAL.
     ///
-    /// This library provides no way of checking for system extensions.
+    /// Use [`is_reserved_method`](fn.is_reserved_method.html), [`validate`](#method.validate) or
+    /// [`parse_request_strict`](fn.parse_request_strict.html) to check for system extensions.
     pub method: M,
 
     /// A Structured value that holds the parameter values to be used during the invocation of the
@@ -344,19 +840,52 @@ pub struct Request<M, T> {
     ///   generated. The names MUST match exactly, including case, to the method's expected
     ///   parameters.
     ///
+    // `null` is not a Structured value, so `None` is serialized without the member. Both an
+    // absent member and `null` are parsed as `None`.
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<T>,
 
     /// The `id`. See [`Id`](enum.Id.html)
     #[serde(default = "notification")]
-    #[serde(skip_serializing_if = "id_req_is_notification")]
+    // Per JSON-RPC-2.0-Section-4.1, we must exclude the `id` field of a `Notification`.
+    #[serde(skip_serializing_if = "IdReq::is_notification")]
     pub id: IdReq,
 }
 
+/// Hashes the `method`, `params` and `id`. See
+/// [`content_hash`](struct.Request.html#method.content_hash) to ignore the `id`.
+impl<M: ::std::hash::Hash, T: ::std::hash::Hash> ::std::hash::Hash for Request<M, T> {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self.method.hash(state);
+        self.params.hash(state);
+        self.id.hash(state);
+    }
+}
+
 impl<M: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned> Request<M, T> {
     /// Helper to serialize the Request as json.
+    ///
+    /// # Panics
+    ///
+    /// If it fails to serialize (i.e. a map with keys which are not strings), see
+    /// [`try_to_string`](#method.try_to_string).
     pub fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap()
+        match self.try_to_string() {
+            Ok(json) => json,
+            Err(err) => panic!("the Request failed to serialize: {}", err),
+        }
+    }
+
+    /// Serialize the Request as json.
+    pub fn try_to_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// The length in bytes of the json of the Request, i.e. `to_string().len()`, without
+    /// allocating it.
+    pub fn serialized_len(&self) -> serde_json::Result<usize> {
+        serialize::json_len(self)
     }
 
     /// Helper to deserialize the Request from json.
@@ -366,25 +895,22 @@ impl<M: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned> Request<M
 }
 
 impl<M: Serialize + DeserializeOwned> Request<M, ()> {
-    /// Create a new Request.
+    /// Create a new Request without params. The `params` member is omitted from the json.
     ///
     /// # Examples
     ///
-    /// # extern crate jrpc;
-    ///
     /// ```rust
     /// # extern crate jrpc;
-    /// extern crate serde_json;
     /// use jrpc::{Id, Request};
     ///
     /// # fn main() {
-    /// let value: Vec<u32> = vec![1, 2, 3];
     /// let request = Request::new(
     ///     Id::from(4),
     ///     "CreateFoo".to_string(),
     /// );
-    /// println!("{}", request.to_string());
+    /// assert_eq!(request.to_string(), r#"{"jsonrpc":"2.0","method":"CreateFoo","id":4}"#);
     /// # }
+    /// ```
     pub fn new<I>(id: I, method: M) -> Self
     where
         I: Into<IdReq>,
@@ -414,14 +940,92 @@ impl<M: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned> Request<M
     }
 }
 
-// Return whether the `id` is a `Notification`.
-//
-// Per JSON-RPC-2.0-Section-4.1, we must exclude the `id` field in this case.
-fn id_req_is_notification(id: &IdReq) -> bool {
-  match id {
-    IdReq::Notification => true,
-    _ => false,
-  }
+impl<M, T> Request<M, T> {
+    /// The same Request with the `new_id`, i.e. to send it again.
+    ///
+    /// A retry needs a fresh id: with the old one, a late Response to the first attempt would be
+    /// taken as the Response to the retry.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{Id, IdReq, Request};
+    ///
+    /// # fn main() {
+    /// let request = Request::with_params(Id::from(1), "subtract".to_string(), [42, 23]);
+    /// let retry = request.re_id(Id::from(2));
+    /// assert_eq!(retry.id, IdReq::Int(2));
+    /// assert_eq!(retry.params, Some([42, 23]));
+    /// # }
+    /// ```
+    pub fn re_id<I: Into<IdReq>>(self, new_id: I) -> Request<M, T> {
+        Request {
+            jsonrpc: self.jsonrpc,
+            method: self.method,
+            params: self.params,
+            id: new_id.into(),
+        }
+    }
+
+    /// Give the Request the next id of the `ids`, returning the old one so that it can be removed
+    /// from the pending Requests.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::client::IdGenerator;
+    /// use jrpc::{Id, IdReq, Request};
+    ///
+    /// # fn main() {
+    /// let mut ids = IdGenerator::starting_at(10);
+    /// let mut request = Request::new(Id::from("a"), "ping".to_string());
+    /// assert_eq!(request.re_id_with(&mut ids), IdReq::String("a".into()));
+    /// assert_eq!(request.id, IdReq::Int(10));
+    /// # }
+    /// ```
+    pub fn re_id_with(&mut self, ids: &mut client::IdGenerator) -> IdReq {
+        ::std::mem::replace(&mut self.id, IdReq::from(ids.next_id()))
+    }
+
+    /// The same Request as a Notification, i.e. without an id: the Server won't reply to it.
+    pub fn strip_id(self) -> Request<M, T> {
+        self.re_id(IdReq::Notification)
+    }
+}
+
+impl<M: Serialize, T> Request<M, T> {
+    /// Check the requirements of the spec which are not upheld when parsing with
+    /// [`parse_request`](fn.parse_request.html), with all of the
+    /// [`ParseOptions::strict`](struct.ParseOptions.html#method.strict) checks enabled.
+    ///
+    /// Returns the error object a strict Server would reply with.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, Request};
+    ///
+    /// # fn main() {
+    /// let request = Request::new(Id::from(1), "rpc.foo".to_string());
+    /// assert_eq!(request.validate().unwrap_err().code, ErrorCode::MethodNotFound);
+    ///
+    /// let request = Request::new(Id::from(1), "foo".to_string());
+    /// assert!(request.validate().is_ok());
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), ErrorObject<Value>> {
+        self.validate_with(&ParseOptions::strict())
+    }
+
+    /// Check the requirements of the spec which are enabled in the `options`.
+    pub fn validate_with(&self, options: &ParseOptions) -> Result<(), ErrorObject<Value>> {
+        let method = match serde_json::to_value(&self.method) {
+            Ok(Value::String(method)) => method,
+            _ => return Ok(()),
+        };
+        options.check_method(&method).map_err(|message| ErrorObject {
+            code: ErrorCode::MethodNotFound,
+            message: message.into(),
+            data: None,
+        })
+    }
 }
 
 /// Parse a json string, returning either:
@@ -432,11 +1036,18 @@ fn id_req_is_notification(id: &IdReq) -> bool {
 /// failure:
 ///
 /// - `ParseError`
-/// - `InvalidRequest`
-/// - `MethodNotFound`
+/// - `InvalidRequest`, i.e. for a `method` which is not a String
+/// - `MethodNotFound`, only for a String `method` which `M` doesn't know
 ///
 /// > Reminder: It is up to the user to return the `InvalidParams` error if the `request.params` is
-/// > invalid.
+/// > invalid, or to use [`parse_request_typed`](fn.parse_request_typed.html).
+///
+/// The message of the `MethodNotFound` error is serde's. Use
+/// [`parse_request_listed`](fn.parse_request_listed.html) for a structured error instead.
+///
+/// A leading UTF-8 BOM is skipped, as are leading and trailing whitespace. An input which is
+/// empty (or only whitespace) is a `ParseError` with the message `empty request body` and the data
+/// `{"kind": "empty"}`.
 ///
 /// # Examples
 ///
@@ -514,7 +1125,10 @@ fn id_req_is_notification(id: &IdReq) -> bool {
 ///
 /// let error = result.unwrap_err();
 /// assert_eq!(error.error.code, jrpc::ErrorCode::InvalidRequest);
-/// assert!(error.error.message.contains("missing field `jsonrpc`"));
+/// assert_eq!(
+///     error.error.message,
+///     "missing required jsonrpc member (is this a 1.0 request?)",
+/// );
 /// assert_eq!(error.id, jrpc::Id::Null);
 /// # }
 /// ```
@@ -558,75 +1172,1631 @@ pub fn parse_request<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
 where
     M: Serialize + DeserializeOwned,
 {
-    let value: serde_json::Value = serde_json::from_str(json)
-        .map_err(|err| Error::new(Id::Null, ErrorCode::ParseError, err.to_string(), None))?;
-
-    let request: Request<Value, Value> = serde_json::from_value(value)
-        .map_err(|err| Error::new(Id::Null, ErrorCode::InvalidRequest, err.to_string(), None))?;
-
-    let (id, method, params) = (request.id, request.method, request.params);
-
-    let method: M = serde_json::from_value(method).map_err(|err| {
-        Error::new(
-            id.clone().to_id().unwrap_or(Id::Null),
-            ErrorCode::MethodNotFound,
-            err.to_string(),
-            None,
-        )
-    })?;
-
-    Ok(Request {
-        jsonrpc: V2_0,
-        method: method,
-        params: params,
-        id: id,
-    })
+    parse_request_with(json, &ParseOptions::default())
 }
 
-/// The Result is either:
-/// - a jsonrpc Response (with a result of a specific type)
-/// - a Error (with an error of type `serde_json::Value`).
-///
-/// # Example
+/// Return whether the method name is reserved for system extensions, i.e. begins with `"rpc."`.
 ///
 /// ```rust
 /// # extern crate jrpc;
-/// extern crate serde_json;
-/// use jrpc::{Id, Response};
-///
 /// # fn main() {
-/// let data: Vec<u32> = vec![1, 2, 3];
-/// let example = Response::success(Id::from(4), data.clone());
-/// let json = r#"
-/// {
-///     "jsonrpc": "2.0",
-///     "result": [1,2,3],
-///     "id": 4
-/// }
-/// "#;
-/// let json = json.replace("\n", "").replace(" ", "");
-/// let result = serde_json::to_string(&example).unwrap();
-/// assert_eq!(json, result);
+/// assert!(jrpc::is_reserved_method("rpc.discover"));
+/// assert!(!jrpc::is_reserved_method("rpcx.foo"));
 /// # }
 /// ```
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(untagged)]
-pub enum Response<T> {
-    /// The Response has a `result` object and not an `error` object.
-    Ok(Success<T>),
-    /// The Response has a `error` object and not an `result` object.
-    Err(Error<Value>),
+pub fn is_reserved_method(name: &str) -> bool {
+    name.starts_with("rpc.")
 }
 
-impl<T: Serialize + DeserializeOwned> Response<T> {
-    /// Retrieve the `id` regardless of whether there was an error or not.
-    pub fn id(&self) -> &Id {
-        match *self {
-            Response::Ok(ref r) => &r.id,
+/// Additional checks done by [`parse_request_with`](fn.parse_request_with.html).
+///
+/// The default does no additional checks, i.e. is the same as
+/// [`parse_request`](fn.parse_request.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject the methods reserved for system extensions (see
+    /// [`is_reserved_method`](fn.is_reserved_method.html)) with `MethodNotFound`, except for the
+    /// `allowed_extensions`.
+    pub reject_reserved_methods: bool,
+
+    /// The system extensions the Server implements, i.e. `rpc.discover`.
+    pub allowed_extensions: Vec<String>,
+
+    /// Reject the params which are not Structured (an Array or an Object) with `InvalidRequest`,
+    /// as the spec requires, instead of leaving them to the Server.
+    pub reject_unstructured_params: bool,
+
+    /// The maximum number of nested Arrays and Objects, deeper json is a `ParseError` with the
+    /// message `"maximum nesting depth exceeded"`.
+    ///
+    /// Defaults to [`DEFAULT_MAX_DEPTH`](constant.DEFAULT_MAX_DEPTH.html), which is also the
+    /// most `serde_json` accepts: a larger value has no effect.
+    pub max_depth: usize,
+
+    /// The maximum length in bytes of a String method, a longer one is an `InvalidRequest`.
+    pub max_method_len: Option<usize>,
+
+    /// The maximum length in bytes of a String id, a longer one is an `InvalidRequest` (with a
+    /// `null` id).
+    pub max_id_len: Option<usize>,
+
+    /// The maximum length in bytes of the message of an error, a longer one is cut (at a
+    /// character boundary) and followed by `...`.
+    ///
+    /// The messages of serde can contain a part of the input, i.e. an unknown method name: this
+    /// keeps a client from having it reflected back in full. Defaults to
+    /// [`DEFAULT_MAX_MESSAGE_LEN`](constant.DEFAULT_MAX_MESSAGE_LEN.html), `None` is unlimited.
+    pub max_message_len: Option<usize>,
+
+    /// Keep the full message of a cut error as the `"full_message"` member of its `data` (when
+    /// the data is absent or an Object).
+    ///
+    /// Only enable this when the errors are not sent back to the Client, i.e. for the logs of the
+    /// Server.
+    pub full_message_in_data: bool,
+
+    /// The former names of renamed methods, to their current (canonical) name.
+    ///
+    /// An alias is replaced by its canonical name only when the method type doesn't know the
+    /// alias itself: a name which is both a method and an alias stays the method, and a method
+    /// type accepting any name (i.e. a `String`) never replaces it. The `MethodNotFound` errors
+    /// only know about the name which was sent. See
+    /// [`parse_request_aliased`](fn.parse_request_aliased.html) to know which alias was sent.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            reject_reserved_methods: false,
+            allowed_extensions: Vec::new(),
+            reject_unstructured_params: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_method_len: None,
+            max_id_len: None,
+            max_message_len: Some(DEFAULT_MAX_MESSAGE_LEN),
+            full_message_in_data: false,
+            aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The options used by [`parse_request_strict`](fn.parse_request_strict.html): all checks are
+    /// enabled and no extensions are allowed.
+    pub fn strict() -> ParseOptions {
+        ParseOptions {
+            reject_reserved_methods: true,
+            reject_unstructured_params: true,
+            ..ParseOptions::default()
+        }
+    }
+
+    /// Allow the system extension `method`.
+    pub fn allow_extension<S: Into<String>>(mut self, method: S) -> ParseOptions {
+        self.allowed_extensions.push(method.into());
+        self
+    }
+
+    /// Set [`reject_unstructured_params`](#structfield.reject_unstructured_params).
+    ///
+    /// The `data` of the error is `{"member": "params"}`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, ParseOptions};
+    ///
+    /// # fn main() {
+    /// let options = ParseOptions::default().reject_unstructured_params(true);
+    /// let json = r#"{"jsonrpc": "2.0", "method": "sum", "params": "1, 2", "id": 1}"#;
+    /// let error = jrpc::parse_request_with::<String>(json, &options).unwrap_err();
+    /// assert_eq!(error.id, Id::Int(1));
+    /// assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    /// assert_eq!(
+    ///     error.error.message,
+    ///     "invalid type for member `params`: expected an array or an object, found a string",
+    /// );
+    /// # }
+    /// ```
+    pub fn reject_unstructured_params(mut self, reject_unstructured_params: bool) -> ParseOptions {
+        self.reject_unstructured_params = reject_unstructured_params;
+        self
+    }
+
+    /// Set the [`max_depth`](#structfield.max_depth).
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, ParseOptions};
+    ///
+    /// # fn main() {
+    /// let json = r#"{"jsonrpc": "2.0", "method": "sum", "params": [[1], [2]], "id": 1}"#;
+    /// assert!(jrpc::parse_request_with::<String>(json, &ParseOptions::default()).is_ok());
+    ///
+    /// let options = ParseOptions::default().max_depth(2);
+    /// let error = jrpc::parse_request_with::<String>(json, &options).unwrap_err();
+    /// assert_eq!(error.error.code, ErrorCode::ParseError);
+    /// assert_eq!(error.error.message, "maximum nesting depth exceeded");
+    /// # }
+    /// ```
+    pub fn max_depth(mut self, max_depth: usize) -> ParseOptions {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the [`max_method_len`](#structfield.max_method_len).
+    ///
+    /// The `data` of the error is `{"field": "method", "limit": N, "actual": M}`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, ParseOptions};
+    ///
+    /// # fn main() {
+    /// let options = ParseOptions::default().max_method_len(8);
+    /// let json = r#"{"jsonrpc": "2.0", "method": "subtract_all", "id": 1}"#;
+    /// let error = jrpc::parse_request_with::<String>(json, &options).unwrap_err();
+    /// assert_eq!(error.id, Id::Int(1));
+    /// assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    /// assert_eq!(error.error.message, "method is 12 bytes, longer than the limit of 8");
+    /// # }
+    /// ```
+    pub fn max_method_len(mut self, max_method_len: usize) -> ParseOptions {
+        self.max_method_len = Some(max_method_len);
+        self
+    }
+
+    /// Set the [`max_id_len`](#structfield.max_id_len).
+    pub fn max_id_len(mut self, max_id_len: usize) -> ParseOptions {
+        self.max_id_len = Some(max_id_len);
+        self
+    }
+
+    /// Set the [`max_message_len`](#structfield.max_message_len), `None` is unlimited.
+    pub fn max_message_len(mut self, max_message_len: Option<usize>) -> ParseOptions {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// Set [`full_message_in_data`](#structfield.full_message_in_data).
+    pub fn full_message_in_data(mut self, full_message_in_data: bool) -> ParseOptions {
+        self.full_message_in_data = full_message_in_data;
+        self
+    }
+
+    /// Accept the method `alias` as the method `canonical`, see
+    /// [`aliases`](#structfield.aliases).
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// #[macro_use] extern crate serde_derive;
+    /// use jrpc::ParseOptions;
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// enum Method {
+    ///     #[serde(rename = "chain_getBlock")]
+    ///     ChainGetBlock,
+    /// }
+    ///
+    /// # fn main() {
+    /// let options = ParseOptions::default().alias("getBlock", "chain_getBlock");
+    /// let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "id": 1}"#;
+    /// let request = jrpc::parse_request_with::<Method>(json, &options).unwrap();
+    /// assert_eq!(request.method, Method::ChainGetBlock);
+    /// # }
+    /// ```
+    pub fn alias<A, C>(mut self, alias: A, canonical: C) -> ParseOptions
+    where
+        A: Into<String>,
+        C: Into<String>,
+    {
+        self.aliases.insert(alias.into(), canonical.into());
+        self
+    }
+
+    /// Cut the message of the `error` to the
+    /// [`max_message_len`](#structfield.max_message_len).
+    ///
+    /// All of the parse functions already do this: it is for the errors created elsewhere.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{Error, ErrorCode, Id, ParseOptions, Value};
+    ///
+    /// # fn main() {
+    /// let options = ParseOptions::default().max_message_len(Some(8));
+    /// let message = "é".repeat(8);
+    /// let error: Error<Value> = Error::new(Id::Null, ErrorCode::InternalError, message, None);
+    /// assert_eq!(options.sanitize(error).error.message, "éééé...");
+    /// # }
+    /// ```
+    pub fn sanitize(&self, mut error: Error<Value>) -> Error<Value> {
+        let limit = match self.max_message_len {
+            Some(limit) if error.error.message.len() > limit => limit,
+            _ => return error,
+        };
+        let cut = cut_message(&error.error.message, limit);
+        let full = ::std::mem::replace(&mut error.error.message, cut.into());
+        if self.full_message_in_data {
+            let data = error
+                .error
+                .data
+                .get_or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if let Value::Object(ref mut data) = *data {
+                data.entry("full_message").or_insert(Value::String(full.into_owned()));
+            }
+        }
+        error
+    }
+
+    /// Check the lengths of the String id and method.
+    fn check_lengths(&self, id: &IdReq, method: &str) -> Result<(), Error<Value>> {
+        if let (IdReq::String(id), Some(limit)) = (id, self.max_id_len) {
+            if id.len() > limit {
+                return Err(too_long(Id::Null, "id", limit, id.len()));
+            }
+        }
+        if let Some(limit) = self.max_method_len {
+            if method.len() > limit {
+                return Err(too_long(
+                    id.as_id().unwrap_or(Id::Null),
+                    "method",
+                    limit,
+                    method.len(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the params are Structured, if enabled.
+    fn check_params(&self, id: &IdReq, params: Option<&Value>) -> Result<(), Error<Value>> {
+        match params {
+            Some(&Value::Array(_)) | Some(&Value::Object(_)) | None => Ok(()),
+            Some(params) if self.reject_unstructured_params => Err(wrong_type(
+                id.as_id().unwrap_or(Id::Null),
+                "params",
+                "an array or an object",
+                params,
+            )),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Check the method, returning the message of the error.
+    fn check_method(&self, method: &str) -> Result<(), String> {
+        if self.reject_reserved_methods
+            && is_reserved_method(method)
+            && !self.allowed_extensions.iter().any(|e| e == method)
+        {
+            return Err(format!(
+                "method `{}` is reserved for system extensions",
+                method
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) but with all of the
+/// [`ParseOptions::strict`](struct.ParseOptions.html#method.strict) checks enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, ParseOptions, Value};
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "rpc.discover", "id": 1}"#;
+/// let error = jrpc::parse_request_strict::<String>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+/// assert_eq!(
+///     error.error.message,
+///     "method `rpc.discover` is reserved for system extensions",
+/// );
+///
+/// let options = ParseOptions::strict().allow_extension("rpc.discover");
+/// let request = jrpc::parse_request_with::<String>(json, &options).unwrap();
+/// assert_eq!(request.method, "rpc.discover");
+/// # }
+/// ```
+pub fn parse_request_strict<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    parse_request_with(json, &ParseOptions::strict())
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) but with the additional checks of the
+/// `options`.
+pub fn parse_request_with<M>(
+    json: &str,
+    options: &ParseOptions,
+) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    parse_envelope(json, options)?
+        .into_request(options)
+        .map(|(request, _)| request)
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) but also decode the params as a `T`.
+///
+/// The params are only decoded once the rest of the Request is valid, so params which are not a
+/// `T` are an `InvalidParams` error with the id of the Request (the errors of the envelope are
+/// unchanged). Missing params are `None`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, Id, Request};
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+/// let request: Request<String, (i64, i64)> = jrpc::parse_request_typed(json).unwrap();
+/// assert_eq!(request.params, Some((42, 23)));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": ["a"], "id": 2}"#;
+/// let error = jrpc::parse_request_typed::<String, (i64, i64)>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::InvalidParams);
+/// assert_eq!(error.id, Id::from(2));
+/// # }
+/// ```
+pub fn parse_request_typed<M, T>(json: &str) -> Result<Request<M, T>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+    T: Serialize + DeserializeOwned,
+{
+    let request = parse_request::<M>(json)?;
+    let (id, method, params) = (request.id, request.method, request.params);
+    let params = match params {
+        Some(params) => Some(sanitized(serde_json::from_value(params).map_err(|err| {
+            trace!(warn!(code = ErrorCode::INVALID_PARAMS_CODE, "{}", err));
+            let id = id.as_id().unwrap_or(Id::Null);
+            Error::new(id, ErrorCode::InvalidParams, err.to_string(), None)
+        }))?),
+        None => None,
+    };
+    Ok(Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: params,
+        id: id,
+    })
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) but from an already parsed json
+/// `Value`, i.e. an element of a batch.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::ErrorCode;
+///
+/// # fn main() {
+/// let value = json!({"jsonrpc": "2.0", "method": "sum", "params": [1, 2], "id": 1});
+/// let request = jrpc::parse_request_value::<String>(value).unwrap();
+/// assert_eq!(request.method, "sum");
+///
+/// let error = jrpc::parse_request_value::<String>(json!(1)).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+/// # }
+/// ```
+pub fn parse_request_value<M>(value: Value) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    parse_request_value_with(value, &ParseOptions::default())
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that the payload must be exactly
+/// one json value: anything but whitespace after it is a `ParseError`.
+///
+/// The error reports the byte offset where the trailing data begins, in the message and as
+/// `{"kind": "trailing", "offset": N}` in the data, so that framing bugs can be diagnosed.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::ErrorCode;
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "a", "id": 1} {"jsonrpc": "2.0", "method": "b"}"#;
+/// let error = jrpc::parse_request_exact::<String>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::ParseError);
+/// assert_eq!(error.error.message, "trailing data at byte 43");
+/// assert_eq!(error.error.data.unwrap()["offset"], 43);
+///
+/// assert!(jrpc::parse_request_exact::<String>(&json[..42]).is_ok());
+/// # }
+/// ```
+pub fn parse_request_exact<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    sanitized(parse_json_exact(json).and_then(parse_request_value))
+}
+
+/// Parse a json Response, with the same checks of trailing data as
+/// [`parse_request_exact`](fn.parse_request_exact.html).
+///
+/// Fails with a `ParseError` if the json is invalid or is not a Response.
+pub fn parse_response_exact<T>(json: &str) -> Result<Response<T>, Error<Value>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    sanitized(parse_json_exact(json).and_then(JrpcValueExt::into_response))
+}
+
+/// Identical to [`parse_response_exact`](fn.parse_response_exact.html) but the `data` of an
+/// Error Response is also decoded, as an `E`, i.e. the error data struct of a known Server.
+///
+/// The Response is checked as a `Value` first, so the hints of a malformed error object are the
+/// same whatever the `E`. Data which is not an `E` is a `ParseError` naming the `data` member.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// use jrpc::ErrorCode;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Field {
+///     field: String,
+/// }
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "error": {"code": -32602, "message": "bad",
+///     "data": {"field": "minuend"}}, "id": 1}"#;
+/// let error = jrpc::parse_response_typed::<i64, Field>(json).unwrap().unwrap_err();
+/// assert_eq!(error.error.data, Some(Field { field: "minuend".into() }));
+///
+/// let json = r#"{"jsonrpc": "2.0", "error": {"code": -32602, "message": "bad",
+///     "data": 4}, "id": 1}"#;
+/// let error = jrpc::parse_response_typed::<i64, Field>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::ParseError);
+/// assert_eq!(
+///     error.error.message,
+///     "invalid response: error.data is invalid: invalid type: integer `4`, expected struct Field",
+/// );
+/// # }
+/// ```
+pub fn parse_response_typed<T, E>(
+    json: &str,
+) -> Result<Result<Success<T>, Error<E>>, Error<Value>>
+where
+    T: Serialize + DeserializeOwned,
+    E: Serialize + DeserializeOwned,
+{
+    let response = parse_json_exact(json).and_then(JrpcValueExt::into_response);
+    let (id, object) = match sanitized(response)? {
+        Response::Ok(success) => return Ok(Ok(success)),
+        Response::Err(error) => error.into_parts(),
+    };
+    let data = match object.data {
+        Some(data) => sanitized(serde_json::from_value(data).map_err(|err| {
+            let message = format!("invalid response: error.data is invalid: {}", err);
+            trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+            Error::new(id.clone(), ErrorCode::ParseError, message, None)
+        }))?,
+        None => None,
+    };
+    let object = ErrorObject {
+        code: object.code,
+        message: object.message,
+        data: data,
+    };
+    Ok(Err(object.with_id(id)))
+}
+
+/// Parse a json Response which must be a [`Success`](struct.Success.html), i.e. for a probe where
+/// any error means "unsupported".
+///
+/// The envelope is checked as for [`parse_response_exact`](fn.parse_response_exact.html), then
+/// an Error Response fails with a `ParseError` naming its code instead of the confusing message
+/// of the untagged `Response`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+/// assert_eq!(jrpc::parse_success::<i64>(json).unwrap().result, 19);
+///
+/// let json = r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "nope"}, "id": 1}"#;
+/// let error = jrpc::parse_success::<i64>(json).unwrap_err();
+/// assert_eq!(error.error.code, jrpc::ErrorCode::ParseError);
+/// assert_eq!(
+///     error.error.message,
+///     "expected a result member but found error (code -32601)",
+/// );
+/// assert_eq!(error.id, jrpc::Id::from(1));
+/// # }
+/// ```
+pub fn parse_success<T>(json: &str) -> Result<Success<T>, Error<Value>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    sanitized(parse_json_exact(json).and_then(|value| parse_response_value(value, Some("result"))))
+}
+
+/// Parse a json Response which must be an [`Error`](struct.Error.html), the opposite of
+/// [`parse_success`](fn.parse_success.html).
+///
+/// ```rust
+/// # extern crate jrpc;
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "nope"}, "id": 1}"#;
+/// let error = jrpc::parse_error(json).unwrap();
+/// assert_eq!(error.error.code, jrpc::ErrorCode::MethodNotFound);
+///
+/// let json = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+/// let error = jrpc::parse_error(json).unwrap_err();
+/// assert_eq!(error.error.message, "expected an error member but found result");
+/// # }
+/// ```
+pub fn parse_error(json: &str) -> Result<Error<Value>, Error<Value>> {
+    sanitized(parse_json_exact(json).and_then(|value| parse_response_value(value, Some("error"))))
+}
+
+/// Convert the `value` to a `Response`, `Success` or `Error` after checking its envelope (an
+/// Object with the `jsonrpc` member `"2.0"` and a valid `id`) and that it has the `expected`
+/// member, if any.
+///
+/// The errors are `ParseError`s with the id of the Response (when it is valid), not sanitized.
+pub(crate) fn parse_response_value<R>(
+    value: Value,
+    expected: Option<&'static str>,
+) -> Result<R, Error<Value>>
+where
+    R: DeserializeOwned,
+{
+    let id = check_response_envelope(&value)?;
+    let message: Option<Cow<'static, str>> = match (expected, &value) {
+        (Some("result"), Value::Object(object)) if !object.contains_key("result") => {
+            match object.get("error").map(|error| error.get("code")) {
+                Some(Some(code)) => {
+                    Some(format!("expected a result member but found error (code {})", code).into())
+                }
+                Some(None) => Some("expected a result member but found error".into()),
+                None => Some("expected a result member".into()),
+            }
+        }
+        (Some("error"), Value::Object(object)) if !object.contains_key("error") => {
+            if object.contains_key("result") {
+                Some("expected an error member but found result".into())
+            } else {
+                Some("expected an error member".into())
+            }
+        }
+        _ => None,
+    };
+    if let Some(message) = message {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+        return Err(Error::new(id, ErrorCode::ParseError, message, None));
+    }
+    // replaces serde's unhelpful message for the untagged Response (i.e. "data did not match any
+    // variant"), nothing is allocated unless the error object is unusual
+    let (hint, unusual) = match value {
+        Value::Object(ref object) if !object.contains_key("result") => match object.get("error") {
+            Some(error) => (error_object_hint(error), unusual_members(error)),
+            None => (None, None),
+        },
+        _ => (None, None),
+    };
+    serde_json::from_value(value).map_err(|err| {
+        let mut message = match hint {
+            Some(hint) => format!("invalid response: {}", hint),
+            None => format!("invalid response: {}", err),
+        };
+        if let Some(unusual) = unusual {
+            message.push_str(&format!(
+                " (error also has the unusual members {}, which are ignored)",
+                unusual
+            ));
+        }
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+        Error::new(id, ErrorCode::ParseError, message, None)
+    })
+}
+
+/// What is wrong with the `error` member of a Response, if anything.
+fn error_object_hint(error: &Value) -> Option<String> {
+    let object = match *error {
+        Value::Object(ref object) => object,
+        ref other => return Some(format!("error is not an object, got {}", describe(other))),
+    };
+    match object.get("code") {
+        None => return Some("error.code is missing".to_string()),
+        Some(code) if !(code.is_i64() || code.is_u64()) => {
+            return Some(format!("error.code must be an integer, got {}", code));
+        }
+        Some(_) => {}
+    }
+    match object.get("message") {
+        None => Some("error.message is missing".to_string()),
+        Some(message) if !message.is_string() => {
+            Some(format!("error.message must be a string, got {}", message))
+        }
+        Some(_) => None,
+    }
+}
+
+/// The members of the `error` member of a Response other than `code`, `message` and `data`, i.e.
+/// "`stack`, `type`".
+fn unusual_members(error: &Value) -> Option<String> {
+    let object = error.as_object()?;
+    let mut unusual = object
+        .keys()
+        .filter(|key| !["code", "message", "data"].contains(&key.as_str()));
+    let first = unusual.next()?;
+    Some(unusual.fold(format!("`{}`", first), |list, key| format!("{}, `{}`", list, key)))
+}
+
+/// Check that the `value` is an Object with the `jsonrpc` member `"2.0"` and a valid `id`,
+/// returning the id.
+fn check_response_envelope(value: &Value) -> Result<Id, Error<Value>> {
+    use std::convert::TryFrom;
+
+    let object = match *value {
+        Value::Object(ref object) => object,
+        ref other => {
+            let message =
+                format!("invalid response: expected a JSON object, got {}", describe(other));
+            trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+            return Err(Error::new(Id::Null, ErrorCode::ParseError, message, None));
+        }
+    };
+    let id = match object.get("id").cloned().map(Id::try_from) {
+        Some(Ok(id)) => Ok(id),
+        Some(Err(err)) => Err(format!("invalid response: {}", err)),
+        None => Err("invalid response: missing id member".to_string()),
+    };
+    let id = id.map_err(|message| {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+        Error::new(Id::Null, ErrorCode::ParseError, message, None)
+    })?;
+    let message = match object.get("jsonrpc") {
+        Some(Value::String(ref version)) if version == "2.0" => return Ok(id),
+        Some(version) => format!(
+            "invalid response: jsonrpc member must be the string \"2.0\", got {}",
+            describe(version),
+        ),
+        None => "invalid response: missing required jsonrpc member".to_string(),
+    };
+    trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", message));
+    Err(Error::new(id, ErrorCode::ParseError, message, None))
+}
+
+/// Parse a json Response from a noncompliant Server, ignoring any members other than `jsonrpc`,
+/// `result`, `error` and `id`.
+///
+/// `Success` and `Error` reject unknown members: this drops them before converting. The error
+/// object already ignores unknown members.
+///
+/// Some servers also send JSON-RPC 1.0 style Responses with both members, one of them `null`: an
+/// `"error": null` (or a `"result": null` next to an `error`) is treated as absent. Everything
+/// else is still checked, i.e. a Response with both a `result` and an `error` is a
+/// `ParseError`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::Response;
+///
+/// # fn main() {
+/// for json in &[
+///     r#"{"jsonrpc": "2.0", "result": 19, "id": 1, "time_ms": 5}"#,
+///     r#"{"jsonrpc": "2.0", "result": 19, "error": null, "id": 1}"#,
+/// ] {
+///     assert!(jrpc::parse_response_exact::<i64>(json).is_err());
+///     match jrpc::parse_response_lenient::<i64>(json).unwrap() {
+///         Response::Ok(success) => assert_eq!(success.result, 19),
+///         Response::Err(error) => panic!("{}", error.to_string()),
+///     }
+/// }
+/// # }
+/// ```
+pub fn parse_response_lenient<T>(json: &str) -> Result<Response<T>, Error<Value>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut value = parse_json(json)?;
+    if let Value::Object(ref mut object) = value {
+        object.retain(|key, _| {
+            key == "jsonrpc" || key == "result" || key == "error" || key == "id"
+        });
+        if object.get("error") == Some(&Value::Null) {
+            object.remove("error");
+        } else if object.contains_key("error") && object.get("result") == Some(&Value::Null) {
+            object.remove("result");
+        }
+    }
+    sanitized(value.into_response())
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that the syntax is parsed as
+/// [JSON5](https://json5.org): trailing commas, single quoted strings, comments, etc. are
+/// accepted.
+///
+/// Only the syntax is relaxed: the `InvalidRequest` and `MethodNotFound` checks are unchanged, and
+/// json nested deeper than [`DEFAULT_MAX_DEPTH`](constant.DEFAULT_MAX_DEPTH.html) is still a
+/// `ParseError`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// # fn main() {
+/// let json = "{jsonrpc: '2.0', method: 'subtract', params: [42, 23,], id: 1, /* sloppy */}";
+/// let request = jrpc::parse_request_lenient_json::<String>(json).unwrap();
+/// assert_eq!(request.method, "subtract");
+/// assert!(jrpc::parse_request::<String>(json).is_err());
+/// # }
+/// ```
+#[cfg(feature = "json5")]
+pub fn parse_request_lenient_json<M>(input: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    let value = strip_input(input).and_then(|input| {
+        check_depth_json5(input.as_bytes(), DEFAULT_MAX_DEPTH)?;
+        json5::from_str(input).map_err(|err| {
+            trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", err));
+            Error::new(Id::Null, ErrorCode::ParseError, err.to_string(), None)
+        })
+    });
+    sanitized(value.and_then(parse_request_value))
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that the json is parsed with
+/// `simd-json`, which is faster for large payloads.
+///
+/// `simd-json` parses in place, so the `json` is overwritten. The Request and the error codes are
+/// the same as `parse_request`, only the messages of the `ParseError`s differ.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// # fn main() {
+/// let mut json = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#.to_vec();
+/// let request = jrpc::parse_request_simd::<String>(&mut json).unwrap();
+/// assert_eq!(request.method, "subtract");
+///
+/// let error = jrpc::parse_request_simd::<String>(&mut b"{".to_vec()).unwrap_err();
+/// assert_eq!(error.error.code, jrpc::ErrorCode::ParseError);
+/// # }
+/// ```
+#[cfg(feature = "simd")]
+pub fn parse_request_simd<M>(json: &mut [u8]) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    sanitized(parse_json_simd(json).and_then(parse_request_value))
+}
+
+/// Identical to [`parse_request_with`](fn.parse_request_with.html) but from an already parsed
+/// json `Value`.
+pub fn parse_request_value_with<M>(
+    value: Value,
+    options: &ParseOptions,
+) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    check_envelope(value, options)?
+        .into_request(options)
+        .map(|(request, _)| request)
+}
+
+/// A Request and the alias of its method which was sent, if any. See
+/// [`parse_request_aliased`](fn.parse_request_aliased.html).
+pub type Aliased<M> = (Request<M, Value>, Option<String>);
+
+/// Identical to [`parse_request_with`](fn.parse_request_with.html) but also returns the alias
+/// (see [`ParseOptions::aliases`](struct.ParseOptions.html#structfield.aliases)) the Client sent
+/// instead of the canonical method, i.e. to count the Clients still using it.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// use jrpc::ParseOptions;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Method {
+///     #[serde(rename = "chain_getBlock")]
+///     ChainGetBlock,
+/// }
+///
+/// # fn main() {
+/// let options = ParseOptions::default().alias("getBlock", "chain_getBlock");
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "id": 1}"#;
+/// let (request, alias) = jrpc::parse_request_aliased::<Method>(json, &options).unwrap();
+/// assert_eq!(request.method, Method::ChainGetBlock);
+/// assert_eq!(alias, Some("getBlock".to_string()));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "chain_getBlock", "id": 2}"#;
+/// let (request, alias) = jrpc::parse_request_aliased::<Method>(json, &options).unwrap();
+/// assert_eq!(request.method, Method::ChainGetBlock);
+/// assert_eq!(alias, None);
+/// # }
+/// ```
+pub fn parse_request_aliased<M>(
+    json: &str,
+    options: &ParseOptions,
+) -> Result<Aliased<M>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned,
+{
+    parse_envelope(json, options)?.into_request(options)
+}
+
+/// A Request whose envelope is checked but whose method is not deserialized yet.
+///
+/// Everything but deserializing the method doesn't depend on its type `M`, so it's done by the
+/// non-generic `parse_envelope` and `check_envelope`: their code is not duplicated for every
+/// method type.
+struct Envelope {
+    id: IdReq,
+    method: String,
+    params: Option<Value>,
+    canonical: Option<String>,
+}
+
+/// Parse the json and check the envelope of the Request, the error is sanitized.
+fn parse_envelope(json: &str, options: &ParseOptions) -> Result<Envelope, Error<Value>> {
+    parse_json_with_depth(json, options.max_depth)
+        .and_then(|value| Envelope::check(value, options))
+        .map_err(|error| options.sanitize(error))
+}
+
+/// Check the envelope of the Request, the error is sanitized.
+fn check_envelope(value: Value, options: &ParseOptions) -> Result<Envelope, Error<Value>> {
+    Envelope::check(value, options).map_err(|error| options.sanitize(error))
+}
+
+impl Envelope {
+    /// Check the `value` is a Request and passes the checks of the `options`, and look up the
+    /// canonical name of its method if it is an alias.
+    fn check(value: Value, options: &ParseOptions) -> Result<Envelope, Error<Value>> {
+        let request = parse_raw_value(value)?;
+        let (id, method, params) = (request.id, request.method, request.params);
+        options.check_lengths(&id, &method)?;
+        options.check_params(&id, params.as_ref())?;
+        if let Err(message) = options.check_method(&method) {
+            trace!(warn!(code = ErrorCode::METHOD_NOT_FOUND_CODE, method = ?method, "{}", message));
+            return Err(Error::new(
+                id.into_id().unwrap_or(Id::Null),
+                ErrorCode::MethodNotFound,
+                message,
+                None,
+            ));
+        }
+        trace!(debug!(
+            method = ?method,
+            id = id_kind(&id),
+            params_len = params.as_ref().and_then(|p| serialize::json_len(p).ok()),
+            "parsed the request",
+        ));
+        let canonical = options.aliases.get(method.as_str()).cloned();
+        Ok(Envelope {
+            id: id,
+            method: method,
+            params: params,
+            canonical: canonical,
+        })
+    }
+
+    /// Deserialize the method: the only step of parsing a Request which is generic.
+    ///
+    /// The method is a String, so an `M` which doesn't know it is a `MethodNotFound`. An alias
+    /// is only replaced by its canonical name when `M` doesn't know the alias itself.
+    fn into_request<M>(self, options: &ParseOptions) -> Result<Aliased<M>, Error<Value>>
+    where
+        M: DeserializeOwned,
+    {
+        let Envelope {
+            id,
+            method: name,
+            params,
+            canonical,
+        } = self;
+        let deserialized = match canonical {
+            Some(canonical) => serde_json::from_value(Value::from(name.as_str()))
+                .map(|method| (method, None))
+                .or_else(|err| {
+                    let method = serde_json::from_value(Value::from(canonical.as_str()))
+                        .map_err(|_| err)?;
+                    trace!(debug!(alias = %name, method = %canonical, "replaced the alias"));
+                    Ok((method, Some(name)))
+                }),
+            None => serde_json::from_value(Value::String(name)).map(|method| (method, None)),
+        };
+        let (method, alias) = match deserialized {
+            Ok(deserialized) => deserialized,
+            Err(err) => return Err(options.sanitize(method_not_found(&id, err))),
+        };
+        let request = Request {
+            jsonrpc: V2_0,
+            method: method,
+            params: params,
+            id: id,
+        };
+        Ok((request, alias))
+    }
+}
+
+/// A method type which can represent methods it doesn't know about, i.e. with an
+/// `Other(String)` variant.
+///
+/// See [`parse_request_lenient`](fn.parse_request_lenient.html).
+pub trait MethodFallback {
+    /// Create the method for an unknown method `name`.
+    fn unknown(name: String) -> Self;
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that a String method which `M`
+/// doesn't know about becomes `M::unknown(name)` instead of a `MethodNotFound` error.
+///
+/// This makes it possible to forward (or log and ignore) methods that are not handled locally.
+/// The fallback variant is typically marked `#[serde(skip_deserializing)]` so that it can only be
+/// created through [`MethodFallback`](trait.MethodFallback.html).
+///
+/// A method which is not a String at all is still an `InvalidRequest` error.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// use jrpc::{MethodFallback, Value};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Method {
+///     Subtract,
+///     #[serde(skip_deserializing)]
+///     Other(String),
+/// }
+///
+/// impl MethodFallback for Method {
+///     fn unknown(name: String) -> Method {
+///         Method::Other(name)
+///     }
+/// }
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "foo.get", "id": 1}"#;
+/// let request = jrpc::parse_request_lenient::<Method>(json).unwrap();
+/// assert_eq!(request.method, Method::Other("foo.get".into()));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [2, 1], "id": 2}"#;
+/// let request = jrpc::parse_request_lenient::<Method>(json).unwrap();
+/// assert_eq!(request.method, Method::Subtract);
+/// # }
+/// ```
+pub fn parse_request_lenient<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned + MethodFallback,
+{
+    sanitized(parse_fallback(json))
+}
+
+/// `parse_request_lenient` without cutting the message of the error.
+fn parse_fallback<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned + MethodFallback,
+{
+    let request = parse_raw_request(json)?;
+    let (id, method, params) = (request.id, request.method, request.params);
+    let method: M = match serde_json::from_value(Value::String(method.clone())) {
+        Ok(m) => m,
+        Err(_) => M::unknown(method),
+    };
+
+    Ok(Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: params,
+        id: id,
+    })
+}
+
+/// A method type which can list the names of all of its methods.
+///
+/// Implemented by the types generated with [`rpc_methods!`](macro.rpc_methods.html). See
+/// [`parse_request_listed`](fn.parse_request_listed.html).
+pub trait MethodList {
+    /// The names of all of the methods, as they appear on the wire.
+    fn method_names() -> &'static [&'static str];
+}
+
+/// Identical to [`parse_request`](fn.parse_request.html) except that the `MethodNotFound` error
+/// is structured for programmatic clients.
+///
+/// Instead of serde's "unknown variant" text, the error has the message
+/// `unknown method "Three"` and the `data` `{"method": "Three", "known": ["One", "Two"]}`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::{ErrorCode, MethodList};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// enum Method {
+///     One,
+///     Two,
+/// }
+///
+/// impl MethodList for Method {
+///     fn method_names() -> &'static [&'static str] {
+///         &["One", "Two"]
+///     }
+/// }
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "Three", "id": 4}"#;
+/// let error = jrpc::parse_request_listed::<Method>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+/// assert_eq!(error.error.message, r#"unknown method "Three""#);
+/// assert_eq!(
+///     error.error.data,
+///     Some(json!({"method": "Three", "known": ["One", "Two"]})),
+/// );
+/// # }
+/// ```
+pub fn parse_request_listed<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned + MethodList,
+{
+    sanitized(parse_listed(json))
+}
+
+/// `parse_request_listed` without cutting the message of the error.
+fn parse_listed<M>(json: &str) -> Result<Request<M, Value>, Error<Value>>
+where
+    M: Serialize + DeserializeOwned + MethodList,
+{
+    let request = parse_raw_request(json)?;
+    let (id, method, params) = (request.id, request.method, request.params);
+    let method = Value::String(method);
+    let method: M = match serde_json::from_value(method.clone()) {
+        Ok(m) => m,
+        Err(_) => {
+            let mut data = serde_json::Map::new();
+            data.insert("known".into(), Value::from(M::method_names().to_vec()));
+            let message = format!("unknown method {}", method);
+            data.insert("method".into(), method);
+            return Err(Error::new(
+                id.into_id().unwrap_or(Id::Null),
+                ErrorCode::MethodNotFound,
+                message,
+                Some(Value::Object(data)),
+            ));
+        }
+    };
+
+    Ok(Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: params,
+        id: id,
+    })
+}
+
+/// Parse the json and the Request object, leaving the method unparsed.
+fn parse_raw_request(json: &str) -> Result<Request<String, Value>, Error<Value>> {
+    parse_json(json).and_then(parse_raw_value)
+}
+
+/// The kind of the id, for the `tracing` events.
+#[cfg(feature = "tracing")]
+fn id_kind(id: &IdReq) -> &'static str {
+    match *id {
+        IdReq::String(_) => "string",
+        IdReq::Int(_) => "int",
+        IdReq::Null => "null",
+        IdReq::Notification => "notification",
+    }
+}
+
+/// Parse the json, skipping a leading UTF-8 BOM (which some Windows clients send).
+fn parse_json(json: &str) -> Result<Value, Error<Value>> {
+    parse_json_with_depth(json, DEFAULT_MAX_DEPTH)
+}
+
+/// Identical to `parse_json` but json nested deeper than `max_depth` is an error.
+fn parse_json_with_depth(json: &str, max_depth: usize) -> Result<Value, Error<Value>> {
+    let json = strip_input(json)?;
+    check_depth(json.as_bytes(), max_depth)?;
+    serde_json::from_str(json).map_err(|err| {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", err));
+        Error::new(Id::Null, ErrorCode::ParseError, err.to_string(), None)
+    })
+}
+
+/// The default
+/// [`ParseOptions::max_message_len`](struct.ParseOptions.html#structfield.max_message_len).
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 256;
+
+/// Cut the `message` to `limit` bytes at a character boundary, followed by `...`.
+pub(crate) fn cut_message(message: &str, limit: usize) -> String {
+    if message.len() <= limit {
+        return message.to_string();
+    }
+    let mut end = limit;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &message[..end])
+}
+
+/// Cut the message of the error with the default `ParseOptions`.
+pub(crate) fn sanitized<R>(result: Result<R, Error<Value>>) -> Result<R, Error<Value>> {
+    result.map_err(|error| ParseOptions::default().sanitize(error))
+}
+
+/// The default [`ParseOptions::max_depth`](struct.ParseOptions.html#structfield.max_depth): the
+/// most nested Arrays and Objects `serde_json` parses.
+pub const DEFAULT_MAX_DEPTH: usize = 127;
+
+/// Fail if the Arrays and Objects of the json are nested deeper than `max_depth`, with
+/// `{"kind": "depth", "max_depth": N}` as the data.
+///
+/// Only the brackets outside of strings are counted: invalid json is left to `serde_json`.
+fn check_depth(json: &[u8], max_depth: usize) -> Result<(), Error<Value>> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &b in json {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(depth_exceeded(max_depth));
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Identical to `check_depth` but for json5: the strings may be single quoted and the brackets of
+/// the comments are not counted.
+#[cfg(feature = "json5")]
+fn check_depth_json5(json: &[u8], max_depth: usize) -> Result<(), Error<Value>> {
+    // the line terminators of json5 also include U+2028 and U+2029
+    let ends_line = |rest: &[u8]| {
+        rest[0] == b'\n'
+            || rest[0] == b'\r'
+            || rest.starts_with("\u{2028}".as_bytes())
+            || rest.starts_with("\u{2029}".as_bytes())
+    };
+    let mut depth = 0_usize;
+    let mut i = 0;
+    while i < json.len() {
+        match json[i] {
+            quote @ b'"' | quote @ b'\'' => {
+                i += 1;
+                while i < json.len() && json[i] != quote {
+                    i += if json[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if json[i..].starts_with(b"//") => {
+                while i < json.len() && !ends_line(&json[i..]) {
+                    i += 1;
+                }
+            }
+            b'/' if json[i..].starts_with(b"/*") => {
+                i += 2;
+                while i < json.len() && !json[i..].starts_with(b"*/") {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(depth_exceeded(max_depth));
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// The error of `check_depth`.
+fn depth_exceeded(max_depth: usize) -> Error<Value> {
+    let message = "maximum nesting depth exceeded";
+    trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, max_depth, "{}", message));
+    let mut data = serde_json::Map::new();
+    data.insert("kind".into(), Value::from("depth"));
+    data.insert("max_depth".into(), Value::from(max_depth));
+    Error::new(Id::Null, ErrorCode::ParseError, message, Some(Value::Object(data)))
+}
+
+/// Identical to `parse_json` except that trailing data is an error reporting its byte offset.
+fn parse_json_exact(json: &str) -> Result<Value, Error<Value>> {
+    let stripped = strip_input(json)?;
+    check_depth(stripped.as_bytes(), DEFAULT_MAX_DEPTH)?;
+    let parse_error = |err: serde_json::Error| {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", err));
+        Error::new(Id::Null, ErrorCode::ParseError, err.to_string(), None)
+    };
+    let mut values = serde_json::Deserializer::from_str(stripped).into_iter::<Value>();
+    let value = match values.next() {
+        Some(value) => value.map_err(parse_error)?,
+        None => unreachable!("the input is not blank"),
+    };
+    let trailing = stripped[values.byte_offset()..].trim_start_matches(|c| " \t\n\r".contains(c));
+    if trailing.is_empty() {
+        return Ok(value);
+    }
+    let offset = json.len() - trailing.len();
+    trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, offset = offset, "trailing data"));
+    let mut data = serde_json::Map::new();
+    data.insert("kind".into(), Value::from("trailing"));
+    data.insert("offset".into(), Value::from(offset));
+    Err(Error::new(
+        Id::Null,
+        ErrorCode::ParseError,
+        format!("trailing data at byte {}", offset),
+        Some(Value::Object(data)),
+    ))
+}
+
+/// Identical to `parse_json` but with `simd-json`.
+#[cfg(feature = "simd")]
+fn parse_json_simd(json: &mut [u8]) -> Result<Value, Error<Value>> {
+    let start = input_start(json)?;
+    let json = &mut json[start..];
+    check_depth(json, DEFAULT_MAX_DEPTH)?;
+    simd_json::serde::from_slice(json).map_err(|err| {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "{}", err));
+        Error::new(Id::Null, ErrorCode::ParseError, err.to_string(), None)
+    })
+}
+
+/// Skip a leading UTF-8 BOM, failing if nothing but whitespace is left.
+fn strip_input(json: &str) -> Result<&str, Error<Value>> {
+    // the BOM is 3 bytes: the start is a character boundary
+    let start = input_start(json.as_bytes())?;
+    Ok(&json[start..])
+}
+
+/// The start of the input after a leading UTF-8 BOM, failing if nothing but whitespace is left.
+fn input_start(json: &[u8]) -> Result<usize, Error<Value>> {
+    let start = if json.starts_with(b"\xef\xbb\xbf") { 3 } else { 0 };
+    if is_blank(&json[start..]) {
+        trace!(warn!(code = ErrorCode::PARSE_ERROR_CODE, "empty request body"));
+        return Err(empty_body("empty request body"));
+    }
+    Ok(start)
+}
+
+/// Return whether the input is empty or only json whitespace.
+fn is_blank(json: &[u8]) -> bool {
+    json.iter().all(|&b| b == b' ' || b == b'\t' || b == b'\n' || b == b'\r')
+}
+
+/// The `ParseError` of an empty input, with `{"kind": "empty"}` as the data.
+fn empty_body(message: &'static str) -> Error<Value> {
+    let mut data = serde_json::Map::new();
+    data.insert("kind".into(), Value::from("empty"));
+    Error::new(Id::Null, ErrorCode::ParseError, message, Some(Value::Object(data)))
+}
+
+/// Check the `value` is a Request Object, with a String method.
+fn parse_raw_value(value: Value) -> Result<Request<String, Value>, Error<Value>> {
+    let message = match value {
+        Value::Object(_) => None,
+        Value::Array(_) => Some(
+            "expected a JSON object for a single request \
+             (arrays are batches — use router::handle_batch)",
+        ),
+        Value::Number(_) => Some("expected a JSON object, got a number"),
+        Value::String(_) => Some("expected a JSON object, got a string"),
+        Value::Bool(_) => Some("expected a JSON object, got a boolean"),
+        Value::Null => Some("expected a JSON object, got null"),
+    };
+    if let Some(message) = message {
+        trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, "{}", message));
+        return Err(Error::new(Id::Null, ErrorCode::InvalidRequest, message, None));
+    }
+    check_version(&value)?;
+    check_id_type(&value)?;
+    check_method_type(&value)?;
+    serde_json::from_value(value).map_err(|err| {
+        trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, "{}", err));
+        Error::new(Id::Null, ErrorCode::InvalidRequest, err.to_string(), None)
+    })
+}
+
+/// Check the `jsonrpc` member of a Request Object, with a message saying what it is instead.
+///
+/// The id of the error is the id of the Request, if it is valid.
+fn check_version(value: &Value) -> Result<(), Error<Value>> {
+    let message = match value.get("jsonrpc") {
+        Some(Value::String(ref version)) if version == "2.0" => return Ok(()),
+        Some(version) => {
+            format!("jsonrpc member must be the string \"2.0\", got {}", describe(version))
+        }
+        None => {
+            let message = "missing required jsonrpc member (is this a 1.0 request?)";
+            trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, "{}", message));
+            return Err(Error::new(request_id(value), ErrorCode::InvalidRequest, message, None));
+        }
+    };
+    Err(invalid_member(request_id(value), "jsonrpc", message))
+}
+
+/// The id of a Request Object for its errors: `Null` if it is missing or invalid.
+fn request_id(value: &Value) -> Id {
+    value
+        .get("id")
+        .and_then(|id| serde_json::from_value(id.clone()).ok())
+        .unwrap_or(Id::Null)
+}
+
+/// Describe the `value` in an error message, i.e. `the number 2.0` or `an array`.
+fn describe(value: &Value) -> String {
+    match *value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("the boolean {}", b),
+        Value::Number(ref n) => format!("the number {}", n),
+        Value::String(ref s) => format!("the string {:?}", s),
+        Value::Array(_) => "an array".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+/// Check the type of the `id` member of a Request Object, with a message saying what it is
+/// instead. The id of the error is always `Null`.
+fn check_id_type(value: &Value) -> Result<(), Error<Value>> {
+    let got = match value.get("id") {
+        Some(Value::Bool(_)) => "a boolean",
+        Some(Value::Array(_)) => "an array",
+        Some(Value::Object(_)) => "an object",
+        Some(Value::Number(ref n)) if n.is_f64() => "a float",
+        _ => return Ok(()),
+    };
+    Err(invalid_member(Id::Null, "id", InvalidId { got: got }.to_string()))
+}
+
+/// Check the `method` member of a Request Object is a String, when it is present.
+///
+/// The id of the error is the id of the Request.
+fn check_method_type(value: &Value) -> Result<(), Error<Value>> {
+    match value.get("method") {
+        Some(&Value::String(_)) | None => Ok(()),
+        Some(method) => Err(wrong_type(request_id(value), "method", "a string", method)),
+    }
+}
+
+/// The kind of the `value` in an error message, i.e. `a number`.
+fn kind(value: &Value) -> &'static str {
+    match *value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// The `InvalidRequest` of a `member` which is not of the `expected` type.
+fn wrong_type(id: Id, member: &'static str, expected: &str, value: &Value) -> Error<Value> {
+    let message = format!(
+        "invalid type for member `{}`: expected {}, found {}",
+        member,
+        expected,
+        kind(value)
+    );
+    invalid_member(id, member, message)
+}
+
+/// The `InvalidRequest` of an invalid `member` of the Request, with `{"member": member}` as the
+/// data.
+fn invalid_member<S>(id: Id, member: &'static str, message: S) -> Error<Value>
+where
+    S: Into<Cow<'static, str>>,
+{
+    let message = message.into();
+    trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, member = member, "{}", message));
+    let mut data = serde_json::Map::new();
+    data.insert("member".into(), Value::from(member));
+    Error::new(id, ErrorCode::InvalidRequest, message, Some(Value::Object(data)))
+}
+
+/// The `InvalidRequest` of a `field` longer than the `limit` of the `ParseOptions`.
+fn too_long(id: Id, field: &str, limit: usize, actual: usize) -> Error<Value> {
+    let message = format!("{} is {} bytes, longer than the limit of {}", field, actual, limit);
+    trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, "{}", message));
+    let mut data = serde_json::Map::new();
+    data.insert("field".into(), Value::from(field));
+    data.insert("limit".into(), Value::from(limit));
+    data.insert("actual".into(), Value::from(actual));
+    Error::new(id, ErrorCode::InvalidRequest, message, Some(Value::Object(data)))
+}
+
+fn method_not_found(id: &IdReq, err: serde_json::Error) -> Error<Value> {
+    trace!(warn!(code = ErrorCode::METHOD_NOT_FOUND_CODE, "{}", err));
+    Error::new(
+        id.as_id().unwrap_or(Id::Null),
+        ErrorCode::MethodNotFound,
+        err.to_string(),
+        None,
+    )
+}
+
+/// The Result is either:
+/// - a jsonrpc Response (with a result of a specific type)
+/// - a Error (with an error of type `serde_json::Value`).
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::{Id, Response};
+///
+/// # fn main() {
+/// let data: Vec<u32> = vec![1, 2, 3];
+/// let example = Response::success(Id::from(4), data.clone());
+/// let json = r#"
+/// {
+///     "jsonrpc": "2.0",
+///     "result": [1,2,3],
+///     "id": 4
+/// }
+/// "#;
+/// let result = serde_json::to_string(&example).unwrap();
+/// jrpc::testing::assert_json_eq(&result, json);
+/// # }
+/// ```
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "Either a Success or an Error response.")
+)]
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Response<T> {
+    /// The Response has a `result` object and not an `error` object.
+    Ok(Success<T>),
+    /// The Response has a `error` object and not an `result` object.
+    Err(Error<Value>),
+}
+
+impl<T> Response<T> {
+    /// Retrieve the `id` regardless of whether there was an error or not.
+    pub fn id(&self) -> &Id {
+        match *self {
+            Response::Ok(ref r) => &r.id,
             Response::Err(ref e) => &e.id,
         }
     }
 
+    /// The `result` of a `Success`, or the `Error`. The id of a `Success` is dropped.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, Response, Value};
+    ///
+    /// # fn main() {
+    /// let response: Response<i64> = Response::success(Id::from(1), 19);
+    /// assert_eq!(response.into_result().unwrap(), 19);
+    ///
+    /// let response: Response<i64> =
+    ///     Response::error(Id::from(1), ErrorCode::InternalError, "oops", None);
+    /// assert_eq!(response.into_result().unwrap_err().error.code, ErrorCode::InternalError);
+    /// # }
+    /// ```
+    pub fn into_result(self) -> Result<T, Error<Value>> {
+        match self {
+            Response::Ok(success) => Ok(success.into_result()),
+            Response::Err(error) => Err(error),
+        }
+    }
+
+    /// The `result` of a `Success`, i.e. in tests.
+    ///
+    /// # Panics
+    ///
+    /// If the Response is an `Error`, with its id, code and message:
+    ///
+    /// ```rust,should_panic
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, Response};
+    ///
+    /// # fn main() {
+    /// let response: Response<i64> =
+    ///     Response::error(Id::from(4), ErrorCode::MethodNotFound, "no foo", None);
+    /// // called `Response::unwrap_result()` on an Error: id=4 Method not found (-32601): no foo
+    /// response.unwrap_result();
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn unwrap_result(self) -> T {
+        match self {
+            Response::Ok(success) => success.into_result(),
+            Response::Err(error) => {
+                panic!("called `Response::unwrap_result()` on an Error: {}", error)
+            }
+        }
+    }
+
+    /// Identical to [`unwrap_result`](#method.unwrap_result), but the panic message starts with
+    /// `msg`: `{msg}: id=4 Method not found (-32601): no foo`.
+    #[track_caller]
+    pub fn expect(self, msg: &str) -> T {
+        match self {
+            Response::Ok(success) => success.into_result(),
+            Response::Err(error) => panic!("{}: {}", msg, error),
+        }
+    }
+}
+
+impl<T: ::std::fmt::Debug> Response<T> {
+    /// The `Error`, i.e. in tests.
+    ///
+    /// # Panics
+    ///
+    /// If the Response is a `Success`, with its id and result.
+    #[track_caller]
+    pub fn unwrap_err(self) -> Error<Value> {
+        match self {
+            Response::Ok(success) => panic!(
+                "called `Response::unwrap_err()` on a Success: id={} result={:?}",
+                success.id, success.result
+            ),
+            Response::Err(error) => error,
+        }
+    }
+}
+
+/// Sort the Responses of a batch by their id (see the [ordering of `Id`](enum.Id.html#impl-Ord)),
+/// i.e. so that the output of a Server handling the batch concurrently is deterministic.
+///
+/// The errors with a `null` id are first, in the order they were in. Responses with the same id
+/// also keep their order.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, Id, Response};
+///
+/// # fn main() {
+/// let mut responses = vec![
+///     Response::success(Id::from(2), 19),
+///     Response::error(Id::Null, ErrorCode::ParseError, "Parse error", None),
+///     Response::success(Id::from(1), 7),
+/// ];
+/// jrpc::sort_responses(&mut responses);
+/// let ids: Vec<_> = responses.iter().map(|response| response.id().clone()).collect();
+/// assert_eq!(ids, vec![Id::Null, Id::from(1), Id::from(2)]);
+/// # }
+/// ```
+pub fn sort_responses<T>(responses: &mut [Response<T>]) {
+    responses.sort_by(|a, b| a.id().cmp(b.id()));
+}
+
+impl<T: Serialize + DeserializeOwned> Response<T> {
     /// Construct a `Success`
     pub fn success(id: Id, result: T) -> Self {
         Response::Ok(Success::new(id, result))
@@ -636,14 +2806,33 @@ impl<T: Serialize + DeserializeOwned> Response<T> {
     pub fn error<C, S>(id: Id, code: C, message: S, data: Option<Value>) -> Self
     where
         C: Into<ErrorCode>,
-        S: Into<String>,
+        S: Into<Cow<'static, str>>,
     {
         Response::Err(Error::new(id, code, message, data))
     }
 
     /// Helper to serialize the Response as json.
+    ///
+    /// # Panics
+    ///
+    /// If it fails to serialize (i.e. a map with keys which are not strings), see
+    /// [`try_to_string`](#method.try_to_string).
     pub fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap()
+        match self.try_to_string() {
+            Ok(json) => json,
+            Err(err) => panic!("the Response failed to serialize: {}", err),
+        }
+    }
+
+    /// Serialize the Response as json.
+    pub fn try_to_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// The length in bytes of the json of the Response, i.e. `to_string().len()`, without
+    /// allocating it.
+    pub fn serialized_len(&self) -> serde_json::Result<usize> {
+        serialize::json_len(self)
     }
 
     /// Helper to deserialize the Response from json.
@@ -673,11 +2862,15 @@ impl<T: Serialize + DeserializeOwned> Response<T> {
 ///     "id": 4
 /// }
 /// "#;
-/// let json = json.replace("\n", "").replace(" ", "");
 /// let result = serde_json::to_string(&example).unwrap();
-/// assert_eq!(json, result);
+/// jrpc::testing::assert_json_eq(&result, json);
 /// # }
 /// ```
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "The jsonrpc Success response, indicating a successful result.")
+)]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Success<T> {
@@ -707,8 +2900,27 @@ impl<T: Serialize + DeserializeOwned> Success<T> {
     }
 
     /// Helper to serialize the Success as json.
+    ///
+    /// # Panics
+    ///
+    /// If it fails to serialize (i.e. a map with keys which are not strings), see
+    /// [`try_to_string`](#method.try_to_string).
     pub fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap()
+        match self.try_to_string() {
+            Ok(json) => json,
+            Err(err) => panic!("the Success failed to serialize: {}", err),
+        }
+    }
+
+    /// Serialize the Success as json.
+    pub fn try_to_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// The length in bytes of the json of the Success, i.e. `to_string().len()`, without
+    /// allocating it.
+    pub fn serialized_len(&self) -> serde_json::Result<usize> {
+        serialize::json_len(self)
     }
 
     /// Helper to deserialize the Success from json.
@@ -717,6 +2929,36 @@ impl<T: Serialize + DeserializeOwned> Success<T> {
     }
 }
 
+impl<T> Success<T> {
+    /// The `result`, dropping the id.
+    pub fn into_result(self) -> T {
+        self.result
+    }
+
+    /// Split into the id and the `result`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{Id, Success};
+    ///
+    /// # fn main() {
+    /// let success = Success::new(Id::from(4), vec![1, 2, 3]);
+    /// assert_eq!(success.as_result(), &[1, 2, 3]);
+    /// let (id, result) = success.into_parts();
+    /// assert_eq!(id, Id::from(4));
+    /// assert_eq!(result, vec![1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn into_parts(self) -> (Id, T) {
+        (self.id, self.result)
+    }
+
+    /// Borrow the `result`.
+    pub fn as_result(&self) -> &T {
+        &self.result
+    }
+}
+
 /// The jsonrpc Error response, indicating an error.
 ///
 /// # Examples
@@ -724,22 +2966,18 @@ impl<T: Serialize + DeserializeOwned> Success<T> {
 /// Since the `T` in the `ErrorObject` will _at least_ be based on the `ErrorCode` it is
 /// recommended that you deserialize this type as `T=Value` first.
 ///
+/// Build it with [`Error::new`](#method.new) or, for an `Error<Value>`, with
+/// [`Response::builder`](enum.Response.html#method.builder) which checks the error code.
+///
 /// ```rust
 /// # extern crate jrpc;
 /// extern crate serde_json;
-/// use jrpc::{Id, Error, ErrorCode, ErrorObject, V2_0};
+/// use jrpc::{Id, Error, ErrorCode};
 ///
 /// # fn main() {
 /// let data: Vec<u32> = vec![1, 2, 3];
-/// let example = Error {
-///     jsonrpc: V2_0,
-///     error: ErrorObject {
-///         code: ErrorCode::from(-32000),
-///         message: "BadIndexes".into(),
-///         data: Some(data.clone()),
-///     },
-///     id: Id::from(4),
-/// };
+/// let code = ErrorCode::from(-32000);
+/// let example = Error::new(Id::from(4), code, "BadIndexes", Some(data.clone()));
 ///
 /// let json = r#"
 /// {
@@ -752,9 +2990,8 @@ impl<T: Serialize + DeserializeOwned> Success<T> {
 ///     "id": 4
 /// }
 /// "#;
-/// let json = json.replace("\n", "").replace(" ", "");
 /// let result = serde_json::to_string(&example).unwrap();
-/// assert_eq!(json, result);
+/// jrpc::testing::assert_json_eq(&result, json);
 ///
 /// // This is how it is recommended you deserialize:
 /// let error: Error<serde_json::Value> =
@@ -768,6 +3005,11 @@ impl<T: Serialize + DeserializeOwned> Success<T> {
 /// assert_eq!(data, result);
 /// # }
 /// ```
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "The jsonrpc Error response, indicating an error.")
+)]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Error<T> {
@@ -784,7 +3026,7 @@ impl<T: Serialize + DeserializeOwned> Error<T> {
     pub fn new<C, S>(id: Id, code: C, message: S, data: Option<T>) -> Self
     where
         C: Into<ErrorCode>,
-        S: Into<String>,
+        S: Into<Cow<'static, str>>,
     {
         Error {
             jsonrpc: V2_0,
@@ -798,8 +3040,27 @@ impl<T: Serialize + DeserializeOwned> Error<T> {
     }
 
     /// Helper to serialize the Error as json.
+    ///
+    /// # Panics
+    ///
+    /// If it fails to serialize (i.e. a map with keys which are not strings), see
+    /// [`try_to_string`](#method.try_to_string).
     pub fn to_string(&self) -> String {
-        serde_json::to_string(self).unwrap()
+        match self.try_to_string() {
+            Ok(json) => json,
+            Err(err) => panic!("the Error failed to serialize: {}", err),
+        }
+    }
+
+    /// Serialize the Error as json.
+    pub fn try_to_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// The length in bytes of the json of the Error, i.e. `to_string().len()`, without
+    /// allocating it.
+    pub fn serialized_len(&self) -> serde_json::Result<usize> {
+        serialize::json_len(self)
     }
 
     /// Helper to deserialize the Error from json.
@@ -808,10 +3069,67 @@ impl<T: Serialize + DeserializeOwned> Error<T> {
     }
 }
 
+impl<T> Error<T> {
+    /// Split into the id and the error object, see
+    /// [`ErrorObject::with_id`](struct.ErrorObject.html#method.with_id).
+    pub fn into_parts(self) -> (Id, ErrorObject<T>) {
+        (self.id, self.error)
+    }
+}
+
+impl Error<Value> {
+    /// Decode the `data` of the error object as a `D`. See
+    /// [`ErrorObject::data_as`](struct.ErrorObject.html#method.data_as).
+    pub fn data_as<D: DeserializeOwned>(&self) -> Option<Result<D, serde_json::Error>> {
+        self.error.data_as()
+    }
+}
+
 /// The jsonrpc Error object, with details of the error.
 ///
 /// When a rpc call encounters an error, the Response Object MUST contain the error member with a
 /// value that is a Object. See the attributes for details.
+///
+/// # Examples
+///
+/// The `data` is defined by the Server and differs between the errors, so deserialize the error
+/// with `Value` as the data and then decode the data of the errors you know about with
+/// [`data_as`](#method.data_as):
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// extern crate serde_json;
+/// use jrpc::{Error, ErrorCode, Value};
+///
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct ValidationError {
+///     field: String,
+///     reason: String,
+/// }
+///
+/// # fn main() {
+/// let json = r#"{
+///     "jsonrpc": "2.0",
+///     "error": {
+///         "code": -32602,
+///         "message": "invalid params",
+///         "data": {"field": "age", "reason": "must be positive"}
+///     },
+///     "id": 4
+/// }"#;
+/// let error: Error<Value> = serde_json::from_str(json).unwrap();
+/// match error.data_as::<ValidationError>() {
+///     Some(Ok(ref data)) if error.error.code == ErrorCode::InvalidParams => {
+///         assert_eq!(data.field, "age");
+///     }
+///     Some(Ok(_)) => panic!("another error"),
+///     Some(Err(err)) => panic!("unexpected data: {}", err),
+///     None => panic!("no data"),
+/// }
+/// # }
+/// ```
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorObject<T> {
     /// The error code. See [`ErrorCode`](enum.ErrorCode.html)
@@ -820,7 +3138,10 @@ pub struct ErrorObject<T> {
     /// A String providing a short description of the error.
     ///
     /// The message SHOULD be limited to a concise single sentence.
-    pub message: String,
+    ///
+    /// The standard messages (i.e. `"Method not found"`) and other constants are borrowed, so that
+    /// building the error doesn't allocate.
+    pub message: Cow<'static, str>,
 
     /// A Primitive or Structured value that contains additional information about the error.
     ///
@@ -829,9 +3150,138 @@ pub struct ErrorObject<T> {
     /// The value of this member is defined by the Server (e.g. detailed error
     /// information, nested errors etc.).
     #[serde(default = "default_t")]
+    #[cfg_attr(feature = "schemars", schemars(default))]
     pub data: Option<T>,
 }
 
+impl<T> ErrorObject<T> {
+    /// An error without data whose message is the
+    /// [`standard_message`](enum.ErrorCode.html#method.standard_message) of the `code`.
+    ///
+    /// This is a `const fn`, so the errors the Server sends often can be `static` items:
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, ErrorObject, Id, Value};
+    ///
+    /// static PARSE_ERROR: ErrorObject<Value> = ErrorObject::standard(ErrorCode::ParseError);
+    /// static BUSY: ErrorObject<Value> =
+    ///     ErrorObject::new_static(ErrorCode::from_code_const(-32001), "the server is busy");
+    ///
+    /// # fn main() {
+    /// assert_eq!(PARSE_ERROR.message, "Parse error");
+    /// assert_eq!(BUSY.code, ErrorCode::ServerError(-32001));
+    /// # }
+    /// ```
+    pub const fn standard(code: ErrorCode) -> Self {
+        ErrorObject::new_static(code, code.standard_message())
+    }
+
+    /// An error without data with a `'static` message, usable in `const` and `static` items.
+    pub const fn new_static(code: ErrorCode, message: &'static str) -> Self {
+        ErrorObject {
+            code: code,
+            message: Cow::Borrowed(message),
+            data: None,
+        }
+    }
+
+    /// An `InternalError` without data, i.e. for a failing database call.
+    pub fn internal<S: Into<Cow<'static, str>>>(message: S) -> Self {
+        ErrorObject {
+            code: ErrorCode::InternalError,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// An `InvalidParams` error without data.
+    pub fn invalid_params<S: Into<Cow<'static, str>>>(message: S) -> Self {
+        ErrorObject {
+            code: ErrorCode::InvalidParams,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// The `Error` Response replying with this error object to the Request with the `id`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, ErrorObject, Id, Value};
+    ///
+    /// # fn main() {
+    /// let object: ErrorObject<Value> = ErrorObject::invalid_params("need two numbers");
+    /// let error = object.with_id(Id::from(4));
+    /// assert_eq!(error.id, Id::from(4));
+    ///
+    /// let (id, object) = error.into_parts();
+    /// assert_eq!(id, Id::from(4));
+    /// assert_eq!(object.code, ErrorCode::InvalidParams);
+    /// # }
+    /// ```
+    pub const fn with_id(self, id: Id) -> Error<T> {
+        Error {
+            jsonrpc: V2_0,
+            error: self,
+            id: id,
+        }
+    }
+}
+
+/// So that handlers can use `?` on their error object, and box it with other errors.
+impl<T: Serialize + ::std::fmt::Debug> ::std::error::Error for ErrorObject<T> {}
+
+/// A handler's failure to convert a `Value`: `InvalidParams` if the `Value` didn't match the
+/// expected type, otherwise an `InternalError`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// extern crate serde_json;
+/// use jrpc::{ErrorCode, ErrorObject, Value};
+///
+/// #[derive(Deserialize)]
+/// struct Subtract {
+///     minuend: i64,
+///     subtrahend: i64,
+/// }
+///
+/// fn subtract(params: Value) -> Result<Value, ErrorObject<Value>> {
+///     let params: Subtract = serde_json::from_value(params)?;
+///     if params.subtrahend < 0 {
+///         return Err(ErrorObject::invalid_params("the subtrahend must be positive"));
+///     }
+///     Ok(Value::from(params.minuend - params.subtrahend))
+/// }
+///
+/// # fn main() {
+/// let params = serde_json::from_str(r#"{"minuend": 42, "subtrahend": 23}"#).unwrap();
+/// assert_eq!(subtract(params).unwrap(), 19);
+///
+/// let error = subtract(Value::from("42 - 23")).unwrap_err();
+/// assert_eq!(error.code, ErrorCode::InvalidParams);
+/// # }
+/// ```
+impl From<serde_json::Error> for ErrorObject<Value> {
+    fn from(err: serde_json::Error) -> Self {
+        if err.is_data() {
+            ErrorObject::invalid_params(err.to_string())
+        } else {
+            ErrorObject::internal(err.to_string())
+        }
+    }
+}
+
+impl ErrorObject<Value> {
+    /// Decode the `data` as a `D`.
+    ///
+    /// Returns `None` if there is no `data`, otherwise the data or the error if it is not a `D`.
+    pub fn data_as<D: DeserializeOwned>(&self) -> Option<Result<D, serde_json::Error>> {
+        self.data.as_ref().map(D::deserialize)
+    }
+}
+
 /// A Number that indicates the error type that occurred.
 /// This MUST be an integer.
 ///
@@ -859,31 +3309,142 @@ pub enum ErrorCode {
 }
 
 impl ErrorCode {
+    /// `-32700`, the code of [`ParseError`](#variant.ParseError).
+    pub const PARSE_ERROR_CODE: i64 = -32700;
+    /// `-32600`, the code of [`InvalidRequest`](#variant.InvalidRequest).
+    pub const INVALID_REQUEST_CODE: i64 = -32600;
+    /// `-32601`, the code of [`MethodNotFound`](#variant.MethodNotFound).
+    pub const METHOD_NOT_FOUND_CODE: i64 = -32601;
+    /// `-32602`, the code of [`InvalidParams`](#variant.InvalidParams).
+    pub const INVALID_PARAMS_CODE: i64 = -32602;
+    /// `-32603`, the code of [`InternalError`](#variant.InternalError).
+    pub const INTERNAL_ERROR_CODE: i64 = -32603;
+    /// `-32099`, the lowest code of the implementation-defined server errors.
+    pub const SERVER_ERROR_MIN: i64 = -32099;
+    /// `-32000`, the highest code of the implementation-defined server errors.
+    pub const SERVER_ERROR_MAX: i64 = -32000;
+    /// `-32768`, the lowest code reserved for pre-defined errors.
+    pub const RESERVED_MIN: i64 = -32768;
+    /// `-32000`, the highest code reserved for pre-defined errors.
+    pub const RESERVED_MAX: i64 = -32000;
+
     /// Return whether the ErrorCode is correct.
     ///
     /// This will only return `false` if this is `ServerError` and is outside of the range of -32000
     /// to -32099.
-    pub fn is_valid(&self) -> bool {
+    pub const fn is_valid(&self) -> bool {
         match *self {
-            ErrorCode::ServerError(value) => (-32099 <= value) && (value <= -32000),
+            ErrorCode::ServerError(value) => {
+                ErrorCode::SERVER_ERROR_MIN <= value && value <= ErrorCode::SERVER_ERROR_MAX
+            }
             _ => true,
         }
     }
+
+    /// The numeric code.
+    pub const fn code(&self) -> i64 {
+        match *self {
+            ErrorCode::ParseError => ErrorCode::PARSE_ERROR_CODE,
+            ErrorCode::InvalidRequest => ErrorCode::INVALID_REQUEST_CODE,
+            ErrorCode::MethodNotFound => ErrorCode::METHOD_NOT_FOUND_CODE,
+            ErrorCode::InvalidParams => ErrorCode::INVALID_PARAMS_CODE,
+            ErrorCode::InternalError => ErrorCode::INTERNAL_ERROR_CODE,
+            ErrorCode::ServerError(value) => value,
+        }
+    }
+
+    /// Identical to `From<i64>`, usable in `const` items.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::ErrorCode;
+    ///
+    /// const INVALID_PARAMS: ErrorCode = ErrorCode::from_code_const(-32602);
+    /// const BUSY: ErrorCode = ErrorCode::from_code_const(-32001);
+    ///
+    /// # fn main() {
+    /// assert_eq!(INVALID_PARAMS, ErrorCode::InvalidParams);
+    /// assert_eq!(BUSY, ErrorCode::ServerError(-32001));
+    /// # }
+    /// ```
+    pub const fn from_code_const(code: i64) -> ErrorCode {
+        match code {
+            ErrorCode::PARSE_ERROR_CODE => ErrorCode::ParseError,
+            ErrorCode::INVALID_REQUEST_CODE => ErrorCode::InvalidRequest,
+            ErrorCode::METHOD_NOT_FOUND_CODE => ErrorCode::MethodNotFound,
+            ErrorCode::INVALID_PARAMS_CODE => ErrorCode::InvalidParams,
+            ErrorCode::INTERNAL_ERROR_CODE => ErrorCode::InternalError,
+            _ => ErrorCode::ServerError(code),
+        }
+    }
+
+    /// The name of the error in the spec, i.e. `"Invalid params"`: the default message of the
+    /// error objects built by [`ErrorObject::standard`](struct.ErrorObject.html#method.standard)
+    /// and the [`builder`](struct.ErrorObject.html#method.builder).
+    ///
+    /// All of the server errors are `"Server error"`.
+    pub const fn standard_message(&self) -> &'static str {
+        match *self {
+            ErrorCode::ParseError => "Parse error",
+            ErrorCode::InvalidRequest => "Invalid Request",
+            ErrorCode::MethodNotFound => "Method not found",
+            ErrorCode::InvalidParams => "Invalid params",
+            ErrorCode::InternalError => "Internal error",
+            ErrorCode::ServerError(_) => "Server error",
+        }
+    }
+
+    /// Identical to `From<i64>` except that the codes in the reserved range which the spec
+    /// doesn't define are refused (instead of becoming a `ServerError`).
+    ///
+    /// The codes of the server errors (`-32099` to `-32000`) and the codes outside of the
+    /// reserved range are accepted.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::ErrorCode;
+    ///
+    /// # fn main() {
+    /// assert_eq!(ErrorCode::try_from_code(-32601), Ok(ErrorCode::MethodNotFound));
+    /// assert_eq!(ErrorCode::try_from_code(-32001), Ok(ErrorCode::ServerError(-32001)));
+    /// assert_eq!(ErrorCode::try_from_code(42), Ok(ErrorCode::ServerError(42)));
+    /// assert!(ErrorCode::try_from_code(-32100).is_err());
+    /// assert_eq!(ErrorCode::from(-32100), ErrorCode::ServerError(-32100));
+    /// # }
+    /// ```
+    pub fn try_from_code(code: i64) -> Result<ErrorCode, UnknownReservedCode> {
+        let error = ErrorCode::from(code);
+        match error {
+            ErrorCode::ServerError(code)
+                if (ErrorCode::RESERVED_MIN..=ErrorCode::RESERVED_MAX).contains(&code)
+                    && !error.is_valid() =>
+            {
+                Err(UnknownReservedCode(code))
+            }
+            error => Ok(error),
+        }
+    }
 }
 
 impl From<i64> for ErrorCode {
     fn from(v: i64) -> ErrorCode {
-        match v {
-            -32700 => ErrorCode::ParseError,
-            -32600 => ErrorCode::InvalidRequest,
-            -32601 => ErrorCode::MethodNotFound,
-            -32602 => ErrorCode::InvalidParams,
-            -32603 => ErrorCode::InternalError,
-            _ => ErrorCode::ServerError(v),
-        }
+        ErrorCode::from_code_const(v)
+    }
+}
+
+/// An error code in the reserved range (`-32768` to `-32000`) which the spec doesn't define, see
+/// [`ErrorCode::try_from_code`](enum.ErrorCode.html#method.try_from_code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownReservedCode(pub i64);
+
+impl ::std::fmt::Display for UnknownReservedCode {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "the error code {} is reserved but not defined", self.0)
     }
 }
 
+impl ::std::error::Error for UnknownReservedCode {}
+
 fn notification() -> IdReq {
     IdReq::Notification
 }
@@ -0,0 +1,286 @@
+/// Define the methods of an API: the method enum, a params struct per method and typed Request
+/// builders.
+///
+/// Each method is declared as
+/// `Variant = "wire_name" => fn builder(ParamsStruct { fields }) -> Output;` or, for methods
+/// which are only sent as Notifications,
+/// `Variant = "wire_name" => notify builder(ParamsStruct { fields });`. This generates:
+///
+/// - The enum, with a `#[serde(rename)]` of the wire name for every variant, so that it works with
+///   [`parse_request`](fn.parse_request.html) as-is. `name()` returns the wire name and it
+///   implements [`MethodList`](trait.MethodList.html).
+/// - A params struct per method, with public fields. It implements [`Method`](trait.Method.html)
+///   and [`RpcMethod`](trait.RpcMethod.html) (plus
+///   [`NotificationMethod`](trait.NotificationMethod.html) for `notify` methods), so
+///   [`decode_response`](fn.decode_response.html) works with it.
+/// - A builder on the enum per method, taking the `id` (except for `notify` methods) and the
+///   fields as arguments.
+///
+/// The generated types derive `Serialize` and `Deserialize`, so the calling crate must have
+/// `#[macro_use] extern crate serde_derive`.
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use] extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// extern crate serde_json;
+/// use jrpc::{Id, IdReq};
+///
+/// rpc_methods! {
+///     /// The methods of the calculator.
+///     pub enum Calc {
+///         /// Subtract `subtrahend` from `minuend`.
+///         Subtract = "subtract" => fn subtract(SubtractParams {
+///             minuend: i64,
+///             subtrahend: i64,
+///         }) -> i64;
+///         /// Say hello to `n` people.
+///         NotifyHello = "notify_hello" => notify notify_hello(NotifyHelloParams { n: u32 });
+///     }
+/// }
+///
+/// # fn main() {
+/// // Client
+/// let request = Calc::subtract(Id::from(1), 42, 23);
+/// let json = serde_json::to_string(&request).unwrap();
+/// jrpc::testing::assert_json_eq(&json, r#"{
+///     "jsonrpc": "2.0",
+///     "method": "subtract",
+///     "params": {"minuend": 42, "subtrahend": 23},
+///     "id": 1
+/// }"#);
+///
+/// let notification = Calc::notify_hello(3);
+/// assert_eq!(notification.id, IdReq::Notification);
+///
+/// // Server
+/// let request = jrpc::parse_request::<Calc>(&json).unwrap();
+/// assert_eq!(request.method, Calc::Subtract);
+/// let params: SubtractParams = serde_json::from_value(request.params.unwrap()).unwrap();
+/// let reply = jrpc::Response::success(Id::from(1), params.minuend - params.subtrahend);
+///
+/// // Client
+/// let result = jrpc::decode_response::<SubtractParams>(&reply.to_string()).unwrap();
+/// assert_eq!(result, 19);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! rpc_methods {
+    (
+        $(#[$enum_attr:meta])*
+        $vis:vis enum $name:ident {
+            $($methods:tt)*
+        }
+    ) => {
+        $crate::rpc_methods!(@munch [$(#[$enum_attr])*] $vis $name [] $($methods)*);
+    };
+
+    // A method with a reply.
+    (
+        @munch $head:tt $vis:vis $name:ident [$($variants:tt)*]
+        $(#[$attr:meta])*
+        $variant:ident = $wire:literal => fn $builder:ident($params:ident {
+            $($(#[$field_attr:meta])* $field:ident: $field_ty:ty),* $(,)*
+        }) -> $output:ty;
+        $($rest:tt)*
+    ) => {
+        $crate::rpc_methods!(
+            @params $vis $params $wire $output [$($(#[$field_attr])* $field: $field_ty,)*]
+        );
+
+        impl $name {
+            #[doc = concat!("Create a Request calling `", $wire, "`.")]
+            pub fn $builder<I>(id: I, $($field: $field_ty),*) -> $crate::Request<$name, $params>
+            where
+                I: Into<$crate::IdReq>,
+            {
+                $crate::Request {
+                    jsonrpc: $crate::V2_0,
+                    method: $name::$variant,
+                    params: Some($params { $($field: $field),* }),
+                    id: id.into(),
+                }
+            }
+        }
+
+        $crate::rpc_methods!(
+            @munch $head $vis $name [$($variants)* { [$(#[$attr])*] $variant $wire }] $($rest)*
+        );
+    };
+
+    // A method which is only sent as a Notification.
+    (
+        @munch $head:tt $vis:vis $name:ident [$($variants:tt)*]
+        $(#[$attr:meta])*
+        $variant:ident = $wire:literal => notify $builder:ident($params:ident {
+            $($(#[$field_attr:meta])* $field:ident: $field_ty:ty),* $(,)*
+        });
+        $($rest:tt)*
+    ) => {
+        $crate::rpc_methods!(
+            @params $vis $params $wire () [$($(#[$field_attr])* $field: $field_ty,)*]
+        );
+
+        impl $crate::NotificationMethod for $params {}
+
+        impl $name {
+            #[doc = concat!("Create a Notification of `", $wire, "`.")]
+            pub fn $builder($($field: $field_ty),*) -> $crate::Request<$name, $params> {
+                $crate::Request {
+                    jsonrpc: $crate::V2_0,
+                    method: $name::$variant,
+                    params: Some($params { $($field: $field),* }),
+                    id: $crate::IdReq::Notification,
+                }
+            }
+        }
+
+        $crate::rpc_methods!(
+            @munch $head $vis $name [$($variants)* { [$(#[$attr])*] $variant $wire }] $($rest)*
+        );
+    };
+
+    // All methods have been munched: emit the enum.
+    (
+        @munch [$($head:tt)*] $vis:vis $name:ident
+        [$({ [$(#[$attr:meta])*] $variant:ident $wire:literal })*]
+    ) => {
+        $($head)*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        $vis enum $name {
+            $(
+                $(#[$attr])*
+                #[serde(rename = $wire)]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// The name of the method on the wire.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $($name::$variant => $wire,)*
+                }
+            }
+        }
+
+        impl $crate::MethodList for $name {
+            fn method_names() -> &'static [&'static str] {
+                &[$($wire),*]
+            }
+        }
+    };
+
+    (
+        @params $vis:vis $params:ident $wire:literal $output:ty
+        [$($(#[$field_attr:meta])* $field:ident: $field_ty:ty,)*]
+    ) => {
+        #[doc = concat!("The params of `", $wire, "`.")]
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        $vis struct $params {
+            $($(#[$field_attr])* pub $field: $field_ty,)*
+        }
+
+        impl $crate::Method for $params {
+            const NAME: &'static str = $wire;
+            type Params = $params;
+        }
+
+        impl $crate::RpcMethod for $params {
+            type Output = $output;
+        }
+    };
+}
+
+/// Build the `data` of an error object: an `Option<Value>` holding an Object, for
+/// [`Error::new`](struct.Error.html#method.new) and [`Response::error`](enum.Response.html).
+///
+/// The entries are `key => value`, where the value is anything `Serialize` or a nested
+/// `{ entries }` Object. An entry `key =>? value` takes an `Option` and is skipped when it is
+/// `None`, i.e. for a hint which is not always known.
+///
+/// Unlike `serde_json::json!` it never panics, so that replying with an error can't fail: a value
+/// which fails to serialize (i.e. a map with non-string keys) is replaced by the reason, as a
+/// String.
+///
+/// # Examples
+///
+/// ```rust
+/// #[macro_use] extern crate jrpc;
+/// use jrpc::{ErrorCode, Id, Response};
+///
+/// # fn main() {
+/// let hint: Option<&str> = None;
+/// let data = error_data! {
+///     "field" => "minuend",
+///     "expected" => { "type" => "integer", "range" => [0, 100] },
+///     "hint" =>? hint,
+/// };
+/// let response: Response<i64> =
+///     Response::error(Id::from(1), ErrorCode::InvalidParams, "minuend out of range", data);
+/// jrpc::testing::assert_json_eq(&response.to_string(), r#"{
+///     "jsonrpc": "2.0",
+///     "error": {
+///         "code": -32602,
+///         "message": "minuend out of range",
+///         "data": {"field": "minuend", "expected": {"type": "integer", "range": [0, 100]}}
+///     },
+///     "id": 1
+/// }"#);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! error_data {
+    (@entries $map:ident) => {};
+
+    // An optional entry, skipped when `None`.
+    (@entries $map:ident $key:expr =>? $value:expr $(, $($rest:tt)*)?) => {
+        if let Some(value) = $value {
+            $crate::error_data!(@insert $map $key, value);
+        }
+        $crate::error_data!(@entries $map $($($rest)*)?);
+    };
+
+    // A nested Object.
+    (@entries $map:ident $key:expr => { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        $map.insert(::std::convert::Into::into($key), $crate::error_data!(@object $($inner)*));
+        $crate::error_data!(@entries $map $($($rest)*)?);
+    };
+
+    (@entries $map:ident $key:expr => $value:expr $(, $($rest:tt)*)?) => {
+        $crate::error_data!(@insert $map $key, $value);
+        $crate::error_data!(@entries $map $($($rest)*)?);
+    };
+
+    (@insert $map:ident $key:expr, $value:expr) => {
+        $map.insert(
+            ::std::convert::Into::into($key),
+            $crate::__private::to_data(&$value),
+        );
+    };
+
+    (@object $($entries:tt)*) => {{
+        let mut map = $crate::__private::Map::new();
+        $crate::error_data!(@entries map $($entries)*);
+        $crate::Value::Object(map)
+    }};
+
+    ($($entries:tt)*) => {
+        ::std::option::Option::Some($crate::error_data!(@object $($entries)*))
+    };
+}
+
+/// Emit a `tracing` event, i.e. `trace!(debug!("parsed"))`. Expands to nothing (and the arguments
+/// are not evaluated) without the `tracing` feature.
+#[cfg(feature = "tracing")]
+macro_rules! trace {
+    ($level:ident!($($arg:tt)*)) => {
+        ::tracing::$level!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace {
+    ($level:ident!($($arg:tt)*)) => {};
+}
@@ -0,0 +1,206 @@
+//! Typed method definitions shared by the Client and the Server.
+
+use super::*;
+
+/// A method of a jsonrpc API: its name on the wire and the type of its params.
+///
+/// Define each method once in a crate shared by the Client and the Server. The Client builds its
+/// Requests with [`call`](fn.call.html) and the Server routes on `M::NAME`, so the two can't drift
+/// apart.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// extern crate serde_json;
+/// use jrpc::{Id, Method, Value};
+///
+/// struct Subtract;
+///
+/// impl Method for Subtract {
+///     const NAME: &'static str = "subtract";
+///     type Params = (i64, i64);
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct GreetParams {
+///     name: String,
+/// }
+///
+/// struct Greet;
+///
+/// impl Method for Greet {
+///     const NAME: &'static str = "greet";
+///     type Params = GreetParams;
+/// }
+///
+/// # fn main() {
+/// // Client
+/// let request = jrpc::call::<Subtract>(Id::from(1), (42, 23));
+/// let json = serde_json::to_string(&request).unwrap();
+/// jrpc::testing::assert_json_eq(
+///     &json,
+///     r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+/// );
+///
+/// // Server
+/// let request = jrpc::parse_request::<String>(&json).unwrap();
+/// let result = match request.method.as_str() {
+///     Subtract::NAME => {
+///         let (a, b): <Subtract as Method>::Params =
+///             serde_json::from_value(request.params.unwrap()).unwrap();
+///         Value::from(a - b)
+///     }
+///     Greet::NAME => {
+///         let params: <Greet as Method>::Params =
+///             serde_json::from_value(request.params.unwrap()).unwrap();
+///         Value::from(format!("hello {}", params.name))
+///     }
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(result, Value::from(19));
+/// # }
+/// ```
+pub trait Method {
+    /// The name of the method, i.e. `Request.method`.
+    const NAME: &'static str;
+
+    /// The type of `Request.params`.
+    type Params: Serialize;
+}
+
+/// Create a Request calling the method `M` with `params`.
+///
+/// See [`Method`](trait.Method.html) for an example.
+pub fn call<M: Method>(
+    id: impl Into<IdReq>,
+    params: M::Params,
+) -> Request<&'static str, M::Params> {
+    Request {
+        jsonrpc: V2_0,
+        method: M::NAME,
+        params: Some(params),
+        id: id.into(),
+    }
+}
+
+/// A [`Method`](trait.Method.html) which the Server replies to with a `result` of type `Output`.
+///
+/// Build the Request with [`call`](fn.call.html) and decode the reply with
+/// [`decode_response`](fn.decode_response.html), which ties the type of the `result` to the method
+/// that was called.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, Id, Method, RpcMethod};
+///
+/// struct Subtract;
+///
+/// impl Method for Subtract {
+///     const NAME: &'static str = "subtract";
+///     type Params = (i64, i64);
+/// }
+///
+/// impl RpcMethod for Subtract {
+///     type Output = i64;
+/// }
+///
+/// # fn main() {
+/// let _request = jrpc::call::<Subtract>(Id::from(1), (42, 23));
+///
+/// let result = jrpc::decode_response::<Subtract>(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#);
+/// assert_eq!(result.unwrap(), 19);
+///
+/// let error = jrpc::decode_response::<Subtract>(r#"{"jsonrpc": "2.0", "result": "19", "id": 1}"#)
+///     .unwrap_err();
+/// assert_eq!(error.id, Id::from(1));
+/// assert_eq!(error.error.code, ErrorCode::ParseError);
+/// assert_eq!(
+///     error.error.message,
+///     "invalid result for `subtract`: invalid type: string \"19\", expected i64",
+/// );
+/// # }
+/// ```
+pub trait RpcMethod: Method {
+    /// The type of `Success.result`.
+    type Output: DeserializeOwned;
+}
+
+/// A [`RpcMethod`](trait.RpcMethod.html) which is only ever sent as a Notification, so the Server
+/// never replies.
+///
+/// Send it with [`notify`](fn.notify.html).
+pub trait NotificationMethod: RpcMethod<Output = ()> {}
+
+/// Create a Notification (a Request without an `id`) of the method `M` with `params`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::{Method, NotificationMethod, RpcMethod};
+///
+/// struct Update;
+///
+/// impl Method for Update {
+///     const NAME: &'static str = "update";
+///     type Params = Vec<u32>;
+/// }
+///
+/// impl RpcMethod for Update {
+///     type Output = ();
+/// }
+///
+/// impl NotificationMethod for Update {}
+///
+/// # fn main() {
+/// let request = jrpc::notify::<Update>(vec![1, 2, 3]);
+/// jrpc::testing::assert_json_eq(
+///     &serde_json::to_string(&request).unwrap(),
+///     r#"{"jsonrpc": "2.0", "method": "update", "params": [1, 2, 3]}"#,
+/// );
+/// # }
+/// ```
+pub fn notify<M: NotificationMethod>(params: M::Params) -> Request<&'static str, M::Params> {
+    call::<M>(IdReq::Notification, params)
+}
+
+/// Decode the json Response to a call of the method `M`.
+///
+/// Returns the `result` if the Response is a `Success` and the `error` if it is an `Error`.
+///
+/// If the Response can't be decoded, an `Error` is made up locally with the code `ParseError`, a
+/// message naming the method and the `id` of the Response (if it could be read):
+///
+/// - the json is invalid or is not a Response. An empty input has the message
+///   `empty response body` and the data `{"kind": "empty"}`.
+/// - the `result` is not a valid `M::Output`.
+pub fn decode_response<M: RpcMethod>(json: &str) -> Result<M::Output, Error<Value>> {
+    if is_blank(json.as_bytes()) {
+        return Err(empty_body("empty response body"));
+    }
+    let response: Response<Value> = sanitized(serde_json::from_str(json).map_err(|err| {
+        Error::new(
+            Id::Null,
+            ErrorCode::ParseError,
+            format!("invalid response to `{}`: {}", M::NAME, err),
+            None,
+        )
+    }))?;
+
+    match response {
+        Response::Ok(Success { result, id, .. }) => {
+            sanitized(serde_json::from_value(result).map_err(|err| {
+                Error::new(
+                    id,
+                    ErrorCode::ParseError,
+                    format!("invalid result for `{}`: {}", M::NAME, err),
+                    None,
+                )
+            }))
+        }
+        Response::Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,178 @@
+//! The set of methods of a Server with String methods, for better `MethodNotFound` errors.
+
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+use super::*;
+
+/// The default [`MethodSet::max_listed`](struct.MethodSet.html#method.max_listed).
+pub const DEFAULT_MAX_LISTED: usize = 20;
+
+/// The most suggestions of a `MethodNotFound` error.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The methods a Server with String methods implements, i.e. to build the data of its
+/// `MethodNotFound` errors or to serve discovery.
+///
+/// The names are kept sorted.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::{ErrorCode, Id, MethodSet};
+///
+/// # fn main() {
+/// let methods: MethodSet = vec!["subtract", "sum", "get_data"].into_iter().collect();
+/// assert!(methods.contains("sum"));
+///
+/// let error = methods.method_not_found(Id::from(1), "subtrat");
+/// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+/// assert_eq!(error.error.message, "method `subtrat` not found, did you mean `subtract`?");
+/// assert_eq!(
+///     error.error.data,
+///     Some(json!({"methods": ["get_data", "subtract", "sum"], "suggestions": ["subtract"]})),
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSet {
+    names: BTreeSet<String>,
+    max_listed: usize,
+    suggest: bool,
+}
+
+impl Default for MethodSet {
+    fn default() -> Self {
+        MethodSet {
+            names: BTreeSet::new(),
+            max_listed: DEFAULT_MAX_LISTED,
+            suggest: true,
+        }
+    }
+}
+
+impl MethodSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        MethodSet::default()
+    }
+
+    /// List at most `max_listed` methods in the data of the errors, the others are counted as
+    /// `"omitted"`. Defaults to [`DEFAULT_MAX_LISTED`](constant.DEFAULT_MAX_LISTED.html).
+    pub fn max_listed(mut self, max_listed: usize) -> Self {
+        self.max_listed = max_listed;
+        self
+    }
+
+    /// Whether the errors suggest the closest methods. Enabled by default.
+    pub fn suggest(mut self, suggest: bool) -> Self {
+        self.suggest = suggest;
+        self
+    }
+
+    /// Add the method `name`, returning whether it was not already in the set.
+    pub fn insert(&mut self, name: &str) -> bool {
+        self.names.insert(name.to_string())
+    }
+
+    /// Remove the method `name`, returning whether it was in the set.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.names.remove(name)
+    }
+
+    /// Return whether the method `name` is in the set.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// The number of methods.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Return whether the set has no methods.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The names of the methods, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// The methods closest to `attempted`, closest first: the ones within an edit distance of a
+    /// third of its length (at least 1), at most 3.
+    pub fn suggestions(&self, attempted: &str) -> Vec<&str> {
+        let limit = ::std::cmp::max(1, attempted.chars().count() / 3);
+        let mut close: Vec<(usize, &str)> = self
+            .names()
+            .map(|name| (edit_distance(attempted, name), name))
+            .filter(|&(distance, _)| distance <= limit)
+            .collect();
+        // sorting is stable: the names are already in order
+        close.sort_by_key(|&(distance, _)| distance);
+        close.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name).collect()
+    }
+
+    /// The `MethodNotFound` error for the `attempted` method.
+    ///
+    /// The data is an Object with the (sorted) `"methods"`, at most
+    /// [`max_listed`](#method.max_listed) of them and the count of the others as `"omitted"`.
+    /// If [`suggest`](#method.suggest) is enabled and some methods are close to `attempted`, they
+    /// are the `"suggestions"` and the message asks whether the closest was meant.
+    pub fn method_not_found(&self, id: Id, attempted: &str) -> Error<Value> {
+        let mut data = serde_json::Map::new();
+        let listed: Vec<Value> = self.names().take(self.max_listed).map(Value::from).collect();
+        data.insert("methods".into(), Value::Array(listed));
+        if self.names.len() > self.max_listed {
+            let omitted = self.names.len() - self.max_listed;
+            data.insert("omitted".into(), Value::from(omitted));
+        }
+
+        let mut message = format!("method `{}` not found", attempted);
+        if self.suggest {
+            let suggestions = self.suggestions(attempted);
+            if let Some(closest) = suggestions.first() {
+                message.push_str(&format!(", did you mean `{}`?", closest));
+            }
+            if !suggestions.is_empty() {
+                let suggestions = suggestions.into_iter().map(Value::from).collect();
+                data.insert("suggestions".into(), Value::Array(suggestions));
+            }
+        }
+        let message = cut_message(&message, DEFAULT_MAX_MESSAGE_LEN);
+        Error::new(id, ErrorCode::MethodNotFound, message, Some(Value::Object(data)))
+    }
+}
+
+impl<'a> FromIterator<&'a str> for MethodSet {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut methods = MethodSet::new();
+        methods.extend(iter);
+        methods
+    }
+}
+
+impl<'a> Extend<&'a str> for MethodSet {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        self.names.extend(iter.into_iter().map(str::to_string));
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        ::std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
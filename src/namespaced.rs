@@ -0,0 +1,147 @@
+//! Methods following the `namespace.name` convention.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::result;
+use serde::{de, ser};
+
+/// The separator between the namespace and the name of a
+/// [`NamespacedMethod`](struct.NamespacedMethod.html).
+pub trait Separator {
+    /// The separator, i.e. `"."`.
+    const SEPARATOR: &'static str;
+}
+
+/// Separate with a `.`, i.e. `state.get`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Dot;
+
+impl Separator for Dot {
+    const SEPARATOR: &'static str = ".";
+}
+
+/// Separate with a `_`, i.e. `eth_getBalance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Underscore;
+
+impl Separator for Underscore {
+    const SEPARATOR: &'static str = "_";
+}
+
+/// A method made of a namespace and a name, serialized as the String `namespace.name`.
+///
+/// The separator is `.` by default and can be changed with the `S` parameter, i.e.
+/// `NamespacedMethod<Underscore>` for `eth_getBalance`.
+///
+/// The method is split at the _first_ separator: `chain.subscribe.new` has the namespace `chain`
+/// and the name `subscribe.new`. A method without a separator fails to deserialize, which makes
+/// [`parse_request`](fn.parse_request.html) return `MethodNotFound`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, NamespacedMethod};
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc": "2.0", "method": "state.get", "params": ["foo"], "id": 1}"#;
+/// let request = jrpc::parse_request::<NamespacedMethod>(json).unwrap();
+/// assert_eq!(request.method.namespace(), "state");
+/// assert_eq!(request.method.name(), "get");
+/// assert!(request.method.matches_namespace("state"));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "get", "id": 1}"#;
+/// let error = jrpc::parse_request::<NamespacedMethod>(json).unwrap_err();
+/// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NamespacedMethod<S = Dot> {
+    namespace: String,
+    name: String,
+    separator: PhantomData<S>,
+}
+
+impl<S: Separator> NamespacedMethod<S> {
+    /// Create a method from its namespace and name.
+    ///
+    /// The `namespace` must not contain the separator, otherwise the method will be split
+    /// differently once deserialized again.
+    pub fn new<N, M>(namespace: N, name: M) -> Self
+    where
+        N: Into<String>,
+        M: Into<String>,
+    {
+        NamespacedMethod {
+            namespace: namespace.into(),
+            name: name.into(),
+            separator: PhantomData,
+        }
+    }
+
+    /// Split `method` at the first separator. Returns `None` if there is no separator.
+    pub fn parse(method: &str) -> Option<Self> {
+        let mut split = method.splitn(2, S::SEPARATOR);
+        match (split.next(), split.next()) {
+            (Some(namespace), Some(name)) => Some(NamespacedMethod::new(namespace, name)),
+            _ => None,
+        }
+    }
+
+    /// The namespace, i.e. `state` in `state.get`.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The name within the namespace, i.e. `get` in `state.get`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return whether the method is in the `namespace`, for routing.
+    pub fn matches_namespace(&self, namespace: &str) -> bool {
+        self.namespace == namespace
+    }
+}
+
+impl<S: Separator> fmt::Display for NamespacedMethod<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}{}", self.namespace, S::SEPARATOR, self.name)
+    }
+}
+
+impl<S: Separator> ser::Serialize for NamespacedMethod<S> {
+    fn serialize<Z>(&self, serializer: Z) -> result::Result<Z::Ok, Z::Error>
+    where
+        Z: ser::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+struct NamespacedMethodVisitor<S>(PhantomData<S>);
+
+impl<'de, S: Separator> de::Visitor<'de> for NamespacedMethodVisitor<S> {
+    type Value = NamespacedMethod<S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a method of the form `namespace{}name`", S::SEPARATOR)
+    }
+
+    fn visit_str<E>(self, value: &str) -> result::Result<NamespacedMethod<S>, E>
+    where
+        E: de::Error,
+    {
+        NamespacedMethod::parse(value)
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de, S: Separator> de::Deserialize<'de> for NamespacedMethod<S> {
+    fn deserialize<D>(deserializer: D) -> result::Result<NamespacedMethod<S>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(NamespacedMethodVisitor(PhantomData))
+    }
+}
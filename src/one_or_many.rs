@@ -0,0 +1,80 @@
+//! A single value or an Array of them, i.e. a Request or a batch.
+
+use std::slice;
+
+use super::*;
+
+/// Either a single `T` or an Array of them, like the top level of jsonrpc (a Request or a batch)
+/// and some error data.
+///
+/// It serializes back to the form it was deserialized from, so that a proxy doesn't change the
+/// shape on the wire. An Array is always `Many`, so `T` must not itself serialize to an Array.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::{OneOrMany, Request, Value};
+///
+/// # fn main() {
+/// let json = r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#;
+/// let requests: OneOrMany<Request<String, Value>> = serde_json::from_str(json).unwrap();
+/// assert_eq!(requests.len(), 1);
+/// assert_eq!(serde_json::to_string(&requests).unwrap(), json);
+///
+/// let json = format!("[{}]", json);
+/// let requests: OneOrMany<Request<String, Value>> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(requests.iter().next().unwrap().method, "subtract");
+/// assert_eq!(serde_json::to_string(&requests).unwrap(), json);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    /// An Array, i.e. a batch. It may be empty.
+    Many(Vec<T>),
+    /// A single value.
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    /// Iterate over the values.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        match *self {
+            OneOrMany::Many(ref values) => values.iter(),
+            OneOrMany::One(ref value) => slice::from_ref(value).iter(),
+        }
+    }
+
+    /// The number of values.
+    pub fn len(&self) -> usize {
+        match *self {
+            OneOrMany::Many(ref values) => values.len(),
+            OneOrMany::One(_) => 1,
+        }
+    }
+
+    /// Return whether it is an empty Array.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values, whatever the form.
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::Many(values) => values,
+            OneOrMany::One(value) => vec![value],
+        }
+    }
+}
+
+/// An Array is `Many`, anything else is `One`.
+impl From<Value> for OneOrMany<Value> {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Array(values) => OneOrMany::Many(values),
+            value => OneOrMany::One(value),
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! Types for [OpenRPC][openrpc] service description documents.
+//!
+//! An OpenRPC document describes the methods a JSON-RPC server provides. Servers conventionally
+//! return it from the [`rpc.discover`](constant.RPC_DISCOVER.html) method.
+//!
+//! These are plain serde types: this module does not generate documents, it only lets servers
+//! build them and clients parse them. Any member which is not part of the specification
+//! (i.e. specification extensions such as `x-foo`) is kept in the `extensions` map of the object it
+//! was found in, so that documents round trip losslessly.
+//!
+//! JSON Schemas (the `schema` of a `ContentDescriptor` and the `schemas` of the `Components`) are
+//! kept as raw `Value`s.
+//!
+//! [openrpc]: https://spec.open-rpc.org
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate serde_json;
+//! use jrpc::openrpc::OpenRpcDocument;
+//!
+//! # fn main() {
+//! let json = r#"
+//! {
+//!     "openrpc": "1.2.6",
+//!     "info": {"title": "Calculator", "version": "1.0.0"},
+//!     "methods": [
+//!         {
+//!             "name": "subtract",
+//!             "params": [
+//!                 {"name": "minuend", "schema": {"type": "integer"}},
+//!                 {"name": "subtrahend", "schema": {"type": "integer"}}
+//!             ],
+//!             "result": {"name": "difference", "schema": {"type": "integer"}}
+//!         }
+//!     ]
+//! }
+//! "#;
+//! let document: OpenRpcDocument = serde_json::from_str(json).unwrap();
+//! assert_eq!(document.info.title, "Calculator");
+//! assert_eq!(document.methods[0].as_item().unwrap().name, "subtract");
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use super::{ErrorCode, Value};
+
+/// The reserved method name a server uses to return its `OpenRpcDocument`.
+pub const RPC_DISCOVER: &str = "rpc.discover";
+
+/// Specification extensions (and any other unknown members) of an object.
+pub type Extensions = BTreeMap<String, Value>;
+
+/// Either a [`Reference`](struct.Reference.html) to an object defined in the `Components` or the
+/// object itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReferenceOr<T> {
+    /// A `{"$ref": "..."}` object.
+    Reference(Reference),
+    /// The object itself.
+    Item(T),
+}
+
+impl<T> ReferenceOr<T> {
+    /// Return the object if this is not a `Reference`.
+    pub fn as_item(&self) -> Option<&T> {
+        match *self {
+            ReferenceOr::Item(ref item) => Some(item),
+            ReferenceOr::Reference(_) => None,
+        }
+    }
+
+    /// Return the reference path if this is a `Reference`.
+    pub fn as_reference(&self) -> Option<&str> {
+        match *self {
+            ReferenceOr::Reference(ref r) => Some(&r.reference),
+            ReferenceOr::Item(_) => None,
+        }
+    }
+}
+
+/// A simple object to allow referencing other components in the document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Reference {
+    /// The reference string, i.e. `"#/components/schemas/Pet"`.
+    #[serde(rename = "$ref")]
+    pub reference: String,
+}
+
+/// The root object of an OpenRPC document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenRpcDocument {
+    /// The semantic version number of the OpenRPC Specification the document uses.
+    pub openrpc: String,
+
+    /// Metadata about the API.
+    pub info: InfoObject,
+
+    /// Connectivity information of the target servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<ServerObject>>,
+
+    /// The available methods.
+    pub methods: Vec<ReferenceOr<MethodObject>>,
+
+    /// Reusable objects for the different aspects of the document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<Components>,
+
+    /// Additional external documentation.
+    #[serde(rename = "externalDocs")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocumentationObject>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Metadata about the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfoObject {
+    /// The title of the application.
+    pub title: String,
+
+    /// A verbose description of the application.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// A URL to the Terms of Service for the API.
+    #[serde(rename = "termsOfService")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terms_of_service: Option<String>,
+
+    /// The version of the OpenRPC document (not of the OpenRPC specification).
+    pub version: String,
+
+    /// The contact information for the exposed API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<ContactObject>,
+
+    /// The license information for the exposed API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<LicenseObject>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Contact information for the exposed API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactObject {
+    /// The identifying name of the contact person/organization.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The URL pointing to the contact information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// The email address of the contact person/organization.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// License information for the exposed API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseObject {
+    /// The license name used for the API.
+    pub name: String,
+
+    /// A URL to the license used for the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// A server which provides the methods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerObject {
+    /// A name to be used as the canonical name for the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A URL to the target host. Variables are written in `{brackets}`.
+    pub url: String,
+
+    /// A short summary of what the server is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// An optional string describing the host designated by the URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The values used for substitution in the server's URL template.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, ServerVariableObject>>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// A variable for server URL template substitution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerVariableObject {
+    /// An enumeration of the allowed values.
+    #[serde(rename = "enum")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enumeration: Option<Vec<String>>,
+
+    /// The default value to use for substitution.
+    pub default: String,
+
+    /// An optional description for the server variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Describes the interface for a single method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodObject {
+    /// The canonical name of the method, i.e. the `Request.method`.
+    pub name: String,
+
+    /// Tags for logical grouping of methods.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<ReferenceOr<TagObject>>>,
+
+    /// A short summary of what the method does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// A verbose explanation of the method behavior.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Additional external documentation for this method.
+    #[serde(rename = "externalDocs")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocumentationObject>,
+
+    /// The parameters which are applicable for this method.
+    pub params: Vec<ReferenceOr<ContentDescriptor>>,
+
+    /// The description of the result returned by the method. Absent for notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<ReferenceOr<ContentDescriptor>>,
+
+    /// Whether the method is deprecated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+
+    /// Alternative servers for this method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<ServerObject>>,
+
+    /// The application level errors the method may return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<ReferenceOr<ErrorObject>>>,
+
+    /// Possible links from this method call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<ReferenceOr<LinkObject>>>,
+
+    /// The expected format of the parameters.
+    #[serde(rename = "paramStructure")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_structure: Option<ParamStructure>,
+
+    /// Examples of params and results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<ReferenceOr<ExamplePairingObject>>>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// The expected format of a method's parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamStructure {
+    /// Params must be an Object.
+    #[serde(rename = "by-name")]
+    ByName,
+    /// Params must be an Array.
+    #[serde(rename = "by-position")]
+    ByPosition,
+    /// Params may be either.
+    #[serde(rename = "either")]
+    Either,
+}
+
+/// Describes a piece of content: a parameter or a result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContentDescriptor {
+    /// The name of the content being described.
+    pub name: String,
+
+    /// A short summary of the content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// A verbose explanation of the content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Whether the content is required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+
+    /// The JSON Schema of the content.
+    pub schema: Value,
+
+    /// Whether the content is deprecated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// An application level error a method may return.
+///
+/// This has the same members as the jsonrpc [`ErrorObject`](../struct.ErrorObject.html), except
+/// that `data` is omitted (instead of serialized as `null`) when it is absent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorObject {
+    /// The error code.
+    pub code: ErrorCode,
+
+    /// A short description of the error.
+    pub message: String,
+
+    /// Additional information about the error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Holds reusable objects which can be referenced from elsewhere in the document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Components {
+    /// Reusable content descriptors.
+    #[serde(rename = "contentDescriptors")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_descriptors: Option<BTreeMap<String, ContentDescriptor>>,
+
+    /// Reusable JSON Schemas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<BTreeMap<String, Value>>,
+
+    /// Reusable examples.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<BTreeMap<String, ExampleObject>>,
+
+    /// Reusable links.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<BTreeMap<String, LinkObject>>,
+
+    /// Reusable errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<BTreeMap<String, ErrorObject>>,
+
+    /// Reusable example pairings.
+    #[serde(rename = "examplePairingObjects")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example_pairing_objects: Option<BTreeMap<String, ExamplePairingObject>>,
+
+    /// Reusable tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<BTreeMap<String, TagObject>>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Adds metadata to a tag used by methods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagObject {
+    /// The name of the tag.
+    pub name: String,
+
+    /// A short summary of the tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// A verbose explanation of the tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Additional external documentation for this tag.
+    #[serde(rename = "externalDocs")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocumentationObject>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Allows referencing an external resource for extended documentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalDocumentationObject {
+    /// A verbose explanation of the target documentation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The URL for the target documentation.
+    pub url: String,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// A set of example params and result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExamplePairingObject {
+    /// The name of the pairing.
+    pub name: String,
+
+    /// A verbose explanation of the pairing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// A short summary of the pairing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Example parameters.
+    pub params: Vec<ReferenceOr<ExampleObject>>,
+
+    /// Example result. Absent for notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<ReferenceOr<ExampleObject>>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// An example value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExampleObject {
+    /// Canonical name of the example.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A short summary of the example.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// A verbose explanation of the example.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The embedded example value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+
+    /// A URL that points to the example value, mutually exclusive with `value`.
+    #[serde(rename = "externalValue")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_value: Option<String>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// A possible design-time link for a result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkObject {
+    /// Canonical name of the link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// A description of the link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// A short summary of the link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// The name of an existing, resolvable OpenRPC method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+
+    /// The params to pass to the linked method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+
+    /// A server object to be used by the target method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<ServerObject>,
+
+    /// Specification extensions.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
@@ -0,0 +1,24 @@
+//! The common types and functions, to import with `use jrpc::prelude::*;`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! use jrpc::prelude::*;
+//!
+//! # fn main() {
+//! let mut router = Router::new();
+//! router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+//!
+//! let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+//! let request: Request<String, Value> = parse_request(json).unwrap();
+//! let response = router.handle_request(&(), request).unwrap();
+//! assert_eq!(response.id(), &Id::from(1));
+//! # }
+//! ```
+
+pub use client::{ClientState, IdGenerator};
+pub use router::Router;
+pub use {call, decode_response, notify, parse_request, parse_request_value, parse_request_with};
+pub use {Error, ErrorCode, ErrorObject, Id, IdReq, ParseOptions, Request, Response, Success, V2_0};
+pub use {JrpcValueExt, Method, NotificationMethod, RpcMethod, Value};
@@ -0,0 +1,88 @@
+//! Progress Notifications, reporting on long-running work (i.e. LSP's `$/progress`).
+//!
+//! The work is identified by a token, which the Client usually passed in the params of the
+//! Request which started it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate serde_json;
+//! use jrpc::progress::{self, ProgressToken};
+//!
+//! # fn main() {
+//! // Server
+//! let report = serde_json::json!({"kind": "report", "percentage": 50});
+//! let notification = progress::notification(ProgressToken::from("indexing"), report);
+//! let json = serde_json::to_string(&notification).unwrap();
+//! jrpc::testing::assert_json_eq(&json, r#"{
+//!     "jsonrpc": "2.0",
+//!     "method": "$/progress",
+//!     "params": {"token": "indexing", "value": {"kind": "report", "percentage": 50}}
+//! }"#);
+//!
+//! // Client
+//! let request = jrpc::parse_request::<String>(&json).unwrap();
+//! let params = progress::decode(&request, progress::METHOD).unwrap().unwrap();
+//! assert_eq!(params.token, ProgressToken::from("indexing"));
+//! assert_eq!(params.value["percentage"], 50);
+//! # }
+//! ```
+
+use serde::Deserialize;
+
+use super::*;
+
+/// The method of the progress Notifications of LSP.
+pub const METHOD: &str = "$/progress";
+
+/// The token identifying the work reported on: a String or an integer Number.
+pub type ProgressToken = StringOrInt;
+
+/// The params of a progress Notification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressParams<T> {
+    /// The work reported on.
+    pub token: ProgressToken,
+    /// The progress, whose shape depends on the protocol.
+    pub value: T,
+}
+
+/// Create the progress Notification of [`METHOD`](constant.METHOD.html).
+pub fn notification<T>(token: ProgressToken, value: T) -> Request<&'static str, ProgressParams<T>> {
+    notification_of(METHOD, token, value)
+}
+
+/// Create the progress Notification of `method`, for protocols using another method than
+/// [`METHOD`](constant.METHOD.html).
+pub fn notification_of<T>(
+    method: &'static str,
+    token: ProgressToken,
+    value: T,
+) -> Request<&'static str, ProgressParams<T>> {
+    Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: Some(ProgressParams {
+            token: token,
+            value: value,
+        }),
+        id: IdReq::Notification,
+    }
+}
+
+/// Decode the params of the `request` if it is a progress Notification of `method`.
+///
+/// Returns `None` if the `request` is of another method or has an `id`. Otherwise returns the
+/// params, or the error if they are not a `{"token", "value"}` Object.
+pub fn decode(
+    request: &Request<String, Value>,
+    method: &str,
+) -> Option<Result<ProgressParams<Value>, serde_json::Error>> {
+    if request.method != method || request.id != IdReq::Notification {
+        return None;
+    }
+    Some(ProgressParams::deserialize(
+        request.params.as_ref().unwrap_or(&Value::Null),
+    ))
+}
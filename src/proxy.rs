@@ -0,0 +1,118 @@
+//! Helpers for proxies multiplexing many downstream Clients onto one upstream connection.
+
+use std::collections::HashMap;
+
+use client::IdGenerator;
+
+use super::*;
+
+/// Rewrite the ids of the Requests forwarded upstream, so that the ids of different downstream
+/// Clients can't collide, and map them back on the Responses.
+///
+/// Every forwarded Request gets a fresh upstream id and the original is remembered until its
+/// Response comes back. Notifications are never replied to, so they are passed through untouched.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::proxy::IdMapper;
+/// use jrpc::{Id, IdReq};
+///
+/// # fn main() {
+/// let mut mapper = IdMapper::new();
+/// // Two downstream Clients both use the id 1.
+/// let a = mapper.map_outbound(IdReq::from(Id::from(1)));
+/// let b = mapper.map_outbound(IdReq::from(Id::from(1)));
+/// assert_ne!(a, b);
+///
+/// let b = b.into_id().unwrap();
+/// assert_eq!(mapper.map_inbound(&b), Some(Id::from(1)));
+/// assert_eq!(mapper.map_inbound(&b), None, "the mapping is forgotten");
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdMapper {
+    ids: IdGenerator,
+    originals: HashMap<Id, Id>,
+}
+
+impl IdMapper {
+    /// Create a mapper without any mapping.
+    pub fn new() -> Self {
+        IdMapper::default()
+    }
+
+    /// Create a mapper allocating the upstream ids with `ids`.
+    pub fn with_ids(ids: IdGenerator) -> Self {
+        IdMapper {
+            ids: ids,
+            originals: HashMap::new(),
+        }
+    }
+
+    /// The number of Requests waiting for their Response.
+    pub fn len(&self) -> usize {
+        self.originals.len()
+    }
+
+    /// Return whether no Request is waiting for its Response.
+    pub fn is_empty(&self) -> bool {
+        self.originals.is_empty()
+    }
+
+    /// Allocate a fresh upstream id for the `original` id and remember it.
+    ///
+    /// Notifications are returned untouched.
+    pub fn map_outbound(&mut self, original: IdReq) -> IdReq {
+        let original = match original.into_id() {
+            Some(id) => id,
+            None => return IdReq::Notification,
+        };
+        let mut upstream = self.ids.next_id();
+        while self.originals.contains_key(&upstream) {
+            upstream = self.ids.next_id();
+        }
+        self.originals.insert(upstream.clone(), original);
+        IdReq::from(upstream)
+    }
+
+    /// Return the original id of the `upstream` id and forget the mapping.
+    ///
+    /// Returns `None` if the `upstream` id was not allocated by `map_outbound` or was already
+    /// mapped back.
+    pub fn map_inbound(&mut self, upstream: &Id) -> Option<Id> {
+        self.originals.remove(upstream)
+    }
+
+    /// Rewrite the id of the `request` with [`map_outbound`](#method.map_outbound).
+    pub fn map_request<M, T>(&mut self, mut request: Request<M, T>) -> Request<M, T> {
+        request.id = self.map_outbound(request.id);
+        request
+    }
+
+    /// Restore the original id of the `response` with [`map_inbound`](#method.map_inbound).
+    ///
+    /// Returns `None` if its id is unknown, i.e. an `Error` with a `null` id.
+    pub fn map_response<T>(&mut self, mut response: Response<T>) -> Option<Response<T>> {
+        {
+            let id = match response {
+                Response::Ok(ref mut success) => &mut success.id,
+                Response::Err(ref mut error) => &mut error.id,
+            };
+            *id = self.map_inbound(id)?;
+        }
+        Some(response)
+    }
+
+    /// Rewrite the ids of a batch of Requests with [`map_request`](#method.map_request).
+    pub fn map_requests<M, T>(&mut self, requests: Vec<Request<M, T>>) -> Vec<Request<M, T>> {
+        requests.into_iter().map(|r| self.map_request(r)).collect()
+    }
+
+    /// Restore the original ids of a batch of Responses with
+    /// [`map_response`](#method.map_response), dropping the Responses whose id is unknown.
+    pub fn map_responses<T>(&mut self, responses: Vec<Response<T>>) -> Vec<Response<T>> {
+        responses.into_iter().filter_map(|r| self.map_response(r)).collect()
+    }
+}
@@ -0,0 +1,62 @@
+//! Hide sensitive values from `Debug` output.
+
+use std::fmt;
+
+/// A value (i.e. a password in the params) which is hidden from `Debug` and `Display`, which
+/// print `<redacted>`.
+///
+/// It serializes and deserializes exactly like the wrapped value, so it can be used for the
+/// fields of params structs. Access the value with `.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_derive;
+/// extern crate serde_json;
+/// use jrpc::{Id, Redacted, Request};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct LoginParams {
+///     user: String,
+///     password: Redacted<String>,
+/// }
+///
+/// # fn main() {
+/// let params = LoginParams {
+///     user: "alice".into(),
+///     password: Redacted("hunter2".into()),
+/// };
+/// let request = Request::with_params(Id::from(1), "login".to_string(), params);
+/// assert!(!format!("{:?}", request).contains("hunter2"));
+/// assert!(serde_json::to_string(&request).unwrap().contains(r#""password":"hunter2""#));
+/// # }
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Redacted<T>(pub T);
+
+impl<T> Redacted<T> {
+    /// Return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Redacted<T> {
+    fn from(value: T) -> Self {
+        Redacted(value)
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
@@ -0,0 +1,51 @@
+//! Ready-made Responses for the garbage a Server receives, so that replying doesn't serialize
+//! anything.
+//!
+//! The Responses have a `null` id and the standard message of their error (i.e. `"Parse error"`)
+//! instead of the more useful reason of [`parse_request`](../fn.parse_request.html): use them on
+//! the hot paths where the reason doesn't matter, i.e. when rejecting a flood of invalid json.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! use jrpc::responses;
+//!
+//! fn reply(json: &str) -> String {
+//!     match jrpc::parse_request::<String>(json) {
+//!         Ok(request) => format!("handled {}", request.method),
+//!         Err(ref error) if error.error.code == jrpc::ErrorCode::ParseError => {
+//!             responses::PARSE_ERROR_JSON.to_string()
+//!         }
+//!         Err(_) => responses::INVALID_REQUEST_JSON.to_string(),
+//!     }
+//! }
+//!
+//! # fn main() {
+//! assert_eq!(reply("{"), responses::PARSE_ERROR_JSON);
+//! assert_eq!(reply("[]"), responses::INVALID_REQUEST_JSON);
+//! assert_eq!(responses::parse_error().to_string(), responses::PARSE_ERROR_JSON);
+//! # }
+//! ```
+
+use super::*;
+
+/// The json of [`parse_error`](fn.parse_error.html), exactly as its `to_string`.
+pub const PARSE_ERROR_JSON: &str =
+    r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error","data":null},"id":null}"#;
+
+/// The json of [`invalid_request`](fn.invalid_request.html), exactly as its `to_string`.
+pub const INVALID_REQUEST_JSON: &str = concat!(
+    r#"{"jsonrpc":"2.0","error":{"code":-32600,"message":"Invalid Request","data":null},"#,
+    r#""id":null}"#,
+);
+
+/// The `ParseError` with a `null` id and the standard message.
+pub const fn parse_error() -> Error<Value> {
+    ErrorObject::standard(ErrorCode::ParseError).with_id(Id::Null)
+}
+
+/// The `InvalidRequest` with a `null` id and the standard message.
+pub const fn invalid_request() -> Error<Value> {
+    ErrorObject::standard(ErrorCode::InvalidRequest).with_id(Id::Null)
+}
@@ -0,0 +1,882 @@
+//! A minimal router dispatching Requests to handlers registered per method.
+//!
+//! The [`Router`](struct.Router.html) does the loop every Server writes: parse the Request, find
+//! the handler of the method, decode the params, call the handler and build the Response. It
+//! never does any IO: it takes and returns json strings (or the parsed types).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! use jrpc::router::Router;
+//! use jrpc::Value;
+//!
+//! struct Ctx {
+//!     offset: i64,
+//! }
+//!
+//! # fn main() {
+//! let mut router = Router::new();
+//! router.register("subtract", |ctx: &Ctx, (a, b): (i64, i64)| {
+//!     Ok(Value::from(a - b + ctx.offset))
+//! });
+//!
+//! let ctx = Ctx { offset: 0 };
+//! let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+//! jrpc::testing::assert_json_eq(
+//!     &router.handle(&ctx, json).unwrap(),
+//!     r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#,
+//! );
+//!
+//! // Notifications are never replied to.
+//! let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23]}"#;
+//! assert!(router.handle(&ctx, json).is_none());
+//! # }
+//! ```
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::*;
+
+#[cfg(feature = "futures")]
+mod async_router;
+
+#[cfg(feature = "futures")]
+pub use self::async_router::{AsyncRouter, HandlerFuture};
+
+#[cfg(feature = "tower")]
+mod service;
+
+#[cfg(feature = "tower")]
+pub use self::service::RouterService;
+#[cfg(all(feature = "tower", feature = "futures"))]
+pub use self::service::AsyncRouterService;
+
+type Handler<Ctx> =
+    Box<dyn Fn(&Ctx, Option<&Value>) -> Result<Value, ErrorObject<Value>> + Send + Sync>;
+
+type MethodHook = Box<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Hooks called by the [`Router`](struct.Router.html) around the handlers.
+///
+/// Middleware is called in the order it was added with
+/// [`with_middleware`](struct.Router.html#method.with_middleware): the first added runs first
+/// on the request and last on the response.
+///
+/// - `on_request` is called before the handler. Returning an error short-circuits: the remaining
+///   `on_request` hooks and the handler are not called and the error is the Response.
+/// - `on_response` is called in reverse order with the Response, for every middleware whose
+///   `on_request` was called (including the one which short-circuited).
+///
+/// Notifications go through `on_request` but never through `on_response`, since they have no
+/// Response. Payloads which are not a valid Request (i.e. `ParseError`) never reach the
+/// middleware.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::router::{Middleware, Router};
+/// use jrpc::{ErrorCode, ErrorObject, Request, Response, Value};
+///
+/// /// Hide the messages of internal errors.
+/// struct HideInternal;
+///
+/// impl<Ctx> Middleware<Ctx> for HideInternal {
+///     fn on_response(&self, _: &Ctx, _: &Request<String, Value>, r: &mut Response<Value>) {
+///         if let Response::Err(ref mut error) = *r {
+///             if error.error.code == ErrorCode::InternalError {
+///                 error.error.message = "internal error".into();
+///             }
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut router = Router::new().with_middleware(HideInternal);
+/// router.register("query", |_: &(), (): ()| -> Result<Value, _> {
+///     Err(ErrorObject {
+///         code: ErrorCode::InternalError,
+///         message: "connection to 10.0.0.3 refused".into(),
+///         data: None,
+///     })
+/// });
+/// let reply = router.handle(&(), r#"{"jsonrpc": "2.0", "method": "query", "id": 1}"#);
+/// assert!(reply.unwrap().contains(r#""message":"internal error""#));
+/// # }
+/// ```
+pub trait Middleware<Ctx>: Send + Sync {
+    /// Called before the handler. Return an error to reply with it instead of calling the
+    /// handler.
+    fn on_request(
+        &self,
+        ctx: &Ctx,
+        request: &Request<String, Value>,
+    ) -> Result<(), ErrorObject<Value>> {
+        let _ = (ctx, request);
+        Ok(())
+    }
+
+    /// Called with the Response before it is returned.
+    fn on_response(
+        &self,
+        ctx: &Ctx,
+        request: &Request<String, Value>,
+        response: &mut Response<Value>,
+    ) {
+        let _ = (ctx, request, response);
+    }
+}
+
+/// Dispatch Requests to the handler registered for their method.
+///
+/// The error codes of the Responses are:
+///
+/// - `ParseError` and `InvalidRequest` (i.e. for a method which is not a String): returned by
+///   [`parse_request`](../fn.parse_request.html).
+/// - `MethodNotFound`: no handler is registered for the method.
+/// - `InvalidParams`: the params could not be decoded into the handler's params type.
+/// - whatever the handler returns.
+pub struct Router<Ctx> {
+    handlers: HashMap<String, Handler<Ctx>>,
+    /// The lowercased methods, to the method they were registered as.
+    folded: HashMap<String, String>,
+    /// The former names of renamed methods, to their canonical name.
+    aliases: HashMap<String, String>,
+    on_alias: Option<MethodHook>,
+    /// The deprecated methods, to the note of their deprecation.
+    deprecated: HashMap<String, String>,
+    on_deprecated: Option<MethodHook>,
+    middleware: Vec<Box<dyn Middleware<Ctx>>>,
+    catch_panics: bool,
+    case_insensitive: bool,
+}
+
+impl<Ctx> Default for Router<Ctx> {
+    fn default() -> Self {
+        Router {
+            handlers: HashMap::new(),
+            folded: HashMap::new(),
+            aliases: HashMap::new(),
+            on_alias: None,
+            deprecated: HashMap::new(),
+            on_deprecated: None,
+            middleware: Vec::new(),
+            catch_panics: false,
+            case_insensitive: false,
+        }
+    }
+}
+
+impl<Ctx> Router<Ctx> {
+    /// Create a router without any handlers.
+    pub fn new() -> Self {
+        Router::default()
+    }
+
+    /// Register the `handler` of `method`, replacing any previous handler.
+    ///
+    /// The params are decoded into `P` before calling the handler. Absent params are decoded from
+    /// `null`, so use `()` or an `Option` for methods without params. The result is serialized
+    /// into the `Success` (or an `InternalError` if it fails to serialize).
+    pub fn register<P, R, F>(&mut self, method: &str, handler: F) -> &mut Self
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&Ctx, P) -> Result<R, ErrorObject<Value>> + Send + Sync + 'static,
+    {
+        let handler = move |ctx: &Ctx, params: Option<&Value>| {
+            let params = decode_params(params)?;
+            handler(ctx, params).and_then(|r| encode_result(&r))
+        };
+        self.handlers.insert(method.to_string(), Box::new(handler));
+        self.folded.insert(method.to_lowercase(), method.to_string());
+        self
+    }
+
+    /// Register a `handler` of `method` which doesn't use the context, i.e. a plain function.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::router::Router;
+    /// use jrpc::{ErrorCode, ErrorObject, Value};
+    ///
+    /// fn divide((a, b): (i64, i64)) -> Result<i64, ErrorObject<Value>> {
+    ///     if b == 0 {
+    ///         return Err(ErrorObject {
+    ///             code: ErrorCode::InvalidParams,
+    ///             message: "division by zero".into(),
+    ///             data: None,
+    ///         });
+    ///     }
+    ///     Ok(a / b)
+    /// }
+    ///
+    /// # fn main() {
+    /// let mut router = Router::new();
+    /// router.register_fn("divide", divide);
+    /// let json = r#"{"jsonrpc": "2.0", "method": "divide", "params": [6, 3], "id": 1}"#;
+    /// jrpc::testing::assert_json_eq(
+    ///     &router.handle(&(), json).unwrap(),
+    ///     r#"{"jsonrpc": "2.0", "result": 2, "id": 1}"#,
+    /// );
+    /// # }
+    /// ```
+    pub fn register_fn<P, R, F>(&mut self, method: &str, handler: F) -> &mut Self
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: Fn(P) -> Result<R, ErrorObject<Value>> + Send + Sync + 'static,
+    {
+        self.register(method, move |_: &Ctx, params: P| handler(params))
+    }
+
+    /// Register the `handler` of a `method` which is sent as a Notification, i.e. which only has
+    /// side effects.
+    ///
+    /// If the method is called with an `id` anyway, the result is `null`. Params which fail to
+    /// decode are still an `InvalidParams` error (which is only replied when there is an `id`).
+    pub fn register_notification<P, F>(&mut self, method: &str, handler: F) -> &mut Self
+    where
+        P: DeserializeOwned,
+        F: Fn(&Ctx, P) + Send + Sync + 'static,
+    {
+        self.register(method, move |ctx: &Ctx, params: P| {
+            handler(ctx, params);
+            Ok(Value::Null)
+        })
+    }
+
+    /// Accept the method `alias` as the method `canonical`, i.e. the former name of a renamed
+    /// method.
+    ///
+    /// An alias is only used when no handler is registered for it. The Request is then handled
+    /// (including by the middleware) as if the `canonical` method was sent. See
+    /// [`on_alias`](#method.on_alias) to know when an alias is used.
+    pub fn alias(&mut self, alias: &str, canonical: &str) -> &mut Self {
+        self.aliases.insert(alias.to_string(), canonical.to_string());
+        self
+    }
+
+    /// Call `hook` with the alias and the canonical method whenever a Request uses an
+    /// [`alias`](#method.alias), i.e. to count the Clients still using it.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use jrpc::router::Router;
+    ///
+    /// # fn main() {
+    /// let used = Arc::new(AtomicUsize::new(0));
+    /// let counter = used.clone();
+    /// let mut router = Router::new().on_alias(move |alias, canonical| {
+    ///     assert_eq!((alias, canonical), ("getBlock", "chain_getBlock"));
+    ///     counter.fetch_add(1, Ordering::SeqCst);
+    /// });
+    /// router
+    ///     .register_fn("chain_getBlock", |(): ()| Ok(7))
+    ///     .alias("getBlock", "chain_getBlock");
+    ///
+    /// let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "id": 1}"#;
+    /// assert_eq!(router.handle(&(), json).unwrap(), r#"{"jsonrpc":"2.0","result":7,"id":1}"#);
+    /// assert_eq!(used.load(Ordering::SeqCst), 1);
+    /// # }
+    /// ```
+    pub fn on_alias<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.on_alias = Some(Box::new(hook));
+        self
+    }
+
+    /// Mark the `method` as deprecated, with a `note` such as `"use chain_getBlock instead"`.
+    ///
+    /// The Requests of the method are still handled: see
+    /// [`on_deprecated`](#method.on_deprecated) to know when it is called, and
+    /// [`deprecation_warning`](fn.deprecation_warning.html) to warn the Client.
+    pub fn deprecate(&mut self, method: &str, note: &str) -> &mut Self {
+        self.deprecated.insert(method.to_string(), note.to_string());
+        self
+    }
+
+    /// The note of the `method` if it is [deprecated](#method.deprecate).
+    pub fn deprecation(&self, method: &str) -> Option<&str> {
+        self.deprecated.get(method).map(String::as_str)
+    }
+
+    /// Call `hook` with the method and the note of its deprecation whenever a Request calls a
+    /// [deprecated](#method.deprecate) method which has a handler, i.e. for metrics.
+    ///
+    /// The method is the canonical one when the Request used an [`alias`](#method.alias).
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use std::sync::{Arc, Mutex};
+    /// use jrpc::router::Router;
+    ///
+    /// # fn main() {
+    /// let called = Arc::new(Mutex::new(Vec::new()));
+    /// let log = called.clone();
+    /// let mut router = Router::new().on_deprecated(move |method, note| {
+    ///     log.lock().unwrap().push(format!("{}: {}", method, note));
+    /// });
+    /// router
+    ///     .register_fn("getBlock", |(): ()| Ok(7))
+    ///     .deprecate("getBlock", "use chain_getBlock instead");
+    ///
+    /// let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "id": 1}"#;
+    /// assert_eq!(router.handle(&(), json).unwrap(), r#"{"jsonrpc":"2.0","result":7,"id":1}"#);
+    /// assert_eq!(*called.lock().unwrap(), vec!["getBlock: use chain_getBlock instead"]);
+    /// # }
+    /// ```
+    pub fn on_deprecated<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.on_deprecated = Some(Box::new(hook));
+        self
+    }
+
+    /// Add the `middleware`, after the middleware already added. See
+    /// [`Middleware`](trait.Middleware.html) for the order they are called in.
+    pub fn with_middleware<M: Middleware<Ctx> + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Convert panics of the handlers into `InternalError` Responses instead of unwinding through
+    /// the caller. Disabled by default.
+    ///
+    /// See [`catch_to_internal_error`](fn.catch_to_internal_error.html) for the caveats.
+    pub fn catch_panics(mut self, catch: bool) -> Self {
+        self.catch_panics = catch;
+        self
+    }
+
+    /// Match the methods of the Requests to the registered methods case-insensitively, i.e. for
+    /// a migration period of Clients sending `"Subtract"` for `"subtract"`. Disabled by default,
+    /// since the spec says method names are case-sensitive.
+    ///
+    /// An exact match is always preferred. Otherwise, if several registered methods only differ
+    /// by their case, the one registered last is called.
+    ///
+    /// The Request keeps the method as it was sent, so that the middleware and the error messages
+    /// show it.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::router::Router;
+    ///
+    /// # fn main() {
+    /// let mut router = Router::new().case_insensitive(true);
+    /// router.register_fn("subtract", |(a, b): (i64, i64)| Ok(a - b));
+    /// let json = r#"{"jsonrpc": "2.0", "method": "Subtract", "params": [42, 23], "id": 1}"#;
+    /// assert_eq!(
+    ///     router.handle(&(), json).unwrap(),
+    ///     r#"{"jsonrpc":"2.0","result":19,"id":1}"#,
+    /// );
+    /// # }
+    /// ```
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Return whether a handler is registered for `method`, ignoring its case if the router is
+    /// [`case_insensitive`](#method.case_insensitive).
+    pub fn has_method(&self, method: &str) -> bool {
+        self.handler(method).is_some()
+    }
+
+    /// Call the `on_deprecated` hook if the registered method called by `method` is deprecated.
+    fn check_deprecated(&self, method: &str) {
+        let registered = match self.registered(method) {
+            Some(registered) => registered,
+            None => return,
+        };
+        if let Some(note) = self.deprecated.get(registered) {
+            trace!(info!(method = %registered, note = %note, "called a deprecated method"));
+            if let Some(ref hook) = self.on_deprecated {
+                hook(registered, note);
+            }
+        }
+    }
+
+    /// Replace the method of the `request` by its canonical name if it is an alias, ignoring its
+    /// case if the router is [`case_insensitive`](#method.case_insensitive).
+    fn resolve_alias(&self, request: &mut Request<String, Value>) {
+        if self.registered(&request.method).is_some() {
+            return;
+        }
+        let found = match self.aliases.get_key_value(&request.method) {
+            Some(found) => Some(found),
+            None if self.case_insensitive => {
+                let folded = request.method.to_lowercase();
+                self.aliases.iter().find(|&(alias, _)| alias.to_lowercase() == folded)
+            }
+            None => None,
+        };
+        if let Some((alias, canonical)) = found {
+            trace!(debug!(alias = %request.method, method = %canonical, "replaced the alias"));
+            request.method = canonical.clone();
+            if let Some(ref hook) = self.on_alias {
+                hook(alias, canonical);
+            }
+        }
+    }
+
+    /// The name the handler of the `method` was registered as.
+    fn registered<'a>(&'a self, method: &'a str) -> Option<&'a str> {
+        if self.handlers.contains_key(method) {
+            return Some(method);
+        }
+        if !self.case_insensitive {
+            return None;
+        }
+        self.folded.get(&method.to_lowercase()).map(String::as_str)
+    }
+
+    /// The handler of the `method`.
+    fn handler(&self, method: &str) -> Option<&Handler<Ctx>> {
+        let registered = self.registered(method)?;
+        if registered != method {
+            trace!(debug!(method = %method, registered = %registered, "matched ignoring the case"));
+        }
+        self.handlers.get(registered)
+    }
+
+    /// Handle the json Request, returning the json Response.
+    ///
+    /// Returns `None` if the Request is a Notification.
+    pub fn handle(&self, ctx: &Ctx, json: &str) -> Option<String> {
+        self.respond(ctx, json).map(|r| r.to_string())
+    }
+
+    /// Handle the json Request, returning the Response.
+    ///
+    /// Returns `None` if the Request is a Notification.
+    pub fn respond(&self, ctx: &Ctx, json: &str) -> Option<Response<Value>> {
+        match parse_request::<String>(json) {
+            Ok(request) => self.handle_request(ctx, request),
+            Err(error) => Some(Response::Err(error)),
+        }
+    }
+
+    /// Handle the json Request or batch of Requests, returning the json Response(s).
+    ///
+    /// See [`handle_batch`](fn.handle_batch.html).
+    pub fn handle_batch(&self, ctx: &Ctx, json: &str) -> Option<String> {
+        handle_batch(|value| self.respond_value(ctx, value), json)
+    }
+
+    /// Identical to [`handle_batch`](#method.handle_batch) except that the elements of a batch are
+    /// handled in parallel. See [`handle_batch_parallel`](fn.handle_batch_parallel.html).
+    #[cfg(feature = "rayon")]
+    pub fn handle_batch_parallel(&self, ctx: &Ctx, json: &str, order: BatchOrder) -> Option<String>
+    where
+        Ctx: Sync,
+    {
+        handle_batch_parallel(|value| self.respond_value(ctx, value), json, order)
+    }
+
+    /// Identical to [`handle_batch`](#method.handle_batch) except that the json is parsed with
+    /// `simd-json`. See [`handle_batch_simd`](fn.handle_batch_simd.html).
+    #[cfg(feature = "simd")]
+    pub fn handle_batch_simd(&self, ctx: &Ctx, json: &mut [u8]) -> Option<String> {
+        handle_batch_simd(|value| self.respond_value(ctx, value), json)
+    }
+
+    /// Handle the Request in the json `Value`, returning the Response.
+    ///
+    /// Returns `None` if the Request is a Notification.
+    pub fn respond_value(&self, ctx: &Ctx, value: Value) -> Option<Response<Value>> {
+        match parse_request_value::<String>(value) {
+            Ok(request) => self.handle_request(ctx, request),
+            Err(error) => Some(Response::Err(error)),
+        }
+    }
+
+    /// Handle the parsed Request, returning the Response.
+    ///
+    /// Returns `None` if the Request is a Notification.
+    pub fn handle_request(
+        &self,
+        ctx: &Ctx,
+        mut request: Request<String, Value>,
+    ) -> Option<Response<Value>> {
+        self.resolve_alias(&mut request);
+        self.check_deprecated(&request.method);
+        let mut called = 0;
+        let mut rejected = None;
+        for middleware in &self.middleware {
+            called += 1;
+            if let Err(error) = middleware.on_request(ctx, &request) {
+                rejected = Some(error);
+                break;
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!(
+            "handler",
+            method = %request.method,
+            id = id_kind(&request.id),
+        )
+        .entered();
+        let result = match rejected {
+            Some(error) => Err(error),
+            None if self.catch_panics => catch_to_internal_error(|| self.call(ctx, &request)),
+            None => self.call(ctx, &request),
+        };
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        if let Err(ref error) = result {
+            trace!(warn!(code = error.code.code(), "{}", error.message));
+        }
+
+        if called == 0 {
+            return request.id.into_id().map(|id| into_response(id, result));
+        }
+        // the middleware still needs the Request
+        let id = request.id.clone().into_id()?;
+        let mut response = into_response(id, result);
+        for middleware in self.middleware[..called].iter().rev() {
+            middleware.on_response(ctx, &request, &mut response);
+        }
+        Some(response)
+    }
+
+    fn call(
+        &self,
+        ctx: &Ctx,
+        request: &Request<String, Value>,
+    ) -> Result<Value, ErrorObject<Value>> {
+        match self.handler(&request.method) {
+            Some(handler) => handler(ctx, request.params.as_ref()),
+            None => Err(method_not_registered(&request.method)),
+        }
+    }
+}
+
+/// The method of the Notifications built by [`deprecation_warning`](fn.deprecation_warning.html).
+pub const DEPRECATION_WARNING_METHOD: &str = "deprecation_warning";
+
+/// Build a Notification a Server may send to warn the Client that it called a deprecated
+/// `method`, i.e. from the [`on_deprecated`](struct.Router.html#method.on_deprecated) hook.
+///
+/// A Response can't have members besides the result, so the warning is a separate message. Its
+/// method is [`DEPRECATION_WARNING_METHOD`](constant.DEPRECATION_WARNING_METHOD.html) and its
+/// params are `{"method": method, "message": note}`.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// # fn main() {
+/// let warning = jrpc::router::deprecation_warning("getBlock", "use chain_getBlock instead");
+/// jrpc::testing::assert_json_eq(
+///     &warning.to_string(),
+///     r#"{"jsonrpc": "2.0", "method": "deprecation_warning",
+///         "params": {"method": "getBlock", "message": "use chain_getBlock instead"}}"#,
+/// );
+/// # }
+/// ```
+pub fn deprecation_warning(method: &str, note: &str) -> Request<String, Value> {
+    let mut params = serde_json::Map::new();
+    params.insert("method".into(), Value::from(method));
+    params.insert("message".into(), Value::from(note));
+    Request::with_params(
+        IdReq::Notification,
+        DEPRECATION_WARNING_METHOD.to_string(),
+        Value::Object(params),
+    )
+}
+
+fn method_not_registered(method: &str) -> ErrorObject<Value> {
+    ErrorObject {
+        code: ErrorCode::MethodNotFound,
+        message: cut_message(&format!("method `{}` not found", method), DEFAULT_MAX_MESSAGE_LEN)
+            .into(),
+        data: None,
+    }
+}
+
+fn into_response(id: Id, result: Result<Value, ErrorObject<Value>>) -> Response<Value> {
+    match result {
+        Ok(result) => Response::success(id, result),
+        Err(error) => Response::Err(error.with_id(id)),
+    }
+}
+
+/// Handle a json payload which is either a single Request or a batch of them, following section 6
+/// of the spec.
+///
+/// The `handler` is called with each Request (as a json `Value`, see
+/// [`parse_request_value`](../fn.parse_request_value.html)) and returns its Response, or `None`
+/// for a Notification.
+///
+/// - Invalid json: a single `ParseError` Response.
+/// - An empty Array: a single `InvalidRequest` Response.
+/// - A non-empty Array: an Array of the Responses of each element, skipping Notifications. If
+///   every element is a Notification nothing is returned, not even an empty Array.
+/// - Anything else is handed to `handler` as a single Request.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{Response, Value};
+///
+/// # fn main() {
+/// let echo = |value: Value| {
+///     let request = match jrpc::parse_request_value::<String>(value) {
+///         Ok(r) => r,
+///         Err(err) => return Some(Response::Err(err)),
+///     };
+///     let id = request.id.into_id()?;
+///     Some(Response::success(id, request.params.unwrap_or(Value::Null)))
+/// };
+///
+/// let json = r#"[
+///     {"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1},
+///     {"jsonrpc": "2.0", "method": "echo", "params": [2]}
+/// ]"#;
+/// jrpc::testing::assert_json_eq(
+///     &jrpc::router::handle_batch(echo, json).unwrap(),
+///     r#"[{"jsonrpc": "2.0", "result": [1], "id": 1}]"#,
+/// );
+///
+/// let json = r#"[{"jsonrpc": "2.0", "method": "echo", "params": [2]}]"#;
+/// assert_eq!(jrpc::router::handle_batch(echo, json), None);
+/// # }
+/// ```
+pub fn handle_batch<F>(handler: F, json: &str) -> Option<String>
+where
+    F: FnMut(Value) -> Option<Response<Value>>,
+{
+    handle_payload(handler, split_batch(parse_json(json)))
+}
+
+/// Identical to [`handle_batch`](fn.handle_batch.html) except that the json is parsed (in place)
+/// with `simd-json`. The replies are the same, only the messages of the `ParseError`s differ.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{Response, Value};
+///
+/// # fn main() {
+/// let echo = |value: Value| {
+///     let request = jrpc::parse_request_value::<String>(value).ok()?;
+///     Some(Response::success(request.id.into_id()?, request.params.unwrap_or(Value::Null)))
+/// };
+///
+/// let mut json = br#"[{"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1}]"#.to_vec();
+/// jrpc::testing::assert_json_eq(
+///     &jrpc::router::handle_batch_simd(echo, &mut json).unwrap(),
+///     r#"[{"jsonrpc": "2.0", "result": [1], "id": 1}]"#,
+/// );
+/// # }
+/// ```
+#[cfg(feature = "simd")]
+pub fn handle_batch_simd<F>(handler: F, json: &mut [u8]) -> Option<String>
+where
+    F: FnMut(Value) -> Option<Response<Value>>,
+{
+    handle_payload(handler, split_batch(parse_json_simd(json)))
+}
+
+fn handle_payload<F>(mut handler: F, payload: Result<OneOrMany<Value>, String>) -> Option<String>
+where
+    F: FnMut(Value) -> Option<Response<Value>>,
+{
+    match payload {
+        Err(reply) => Some(reply),
+        Ok(OneOrMany::One(value)) => handler(value).map(|r| r.to_string()),
+        Ok(OneOrMany::Many(values)) => encode_batch(values.into_iter().filter_map(handler).collect()),
+    }
+}
+
+/// Return whether the batch of `requests` gets no Response at all, i.e. whether the transport
+/// can reply as soon as it is received (with HTTP `204 No Content`).
+///
+/// It is the case when every Request is a Notification, except for an empty batch which is
+/// replied with an `InvalidRequest`. See the
+/// [`NotificationBatch`](../client/struct.NotificationBatch.html) of the Client side.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::router::is_all_notifications;
+/// use jrpc::{Id, IdReq, Request};
+///
+/// # fn main() {
+/// let mut requests = vec![Request::new(IdReq::Notification, "notify_hello".to_string())];
+/// assert!(is_all_notifications(&requests));
+///
+/// requests.push(Request::new(Id::from(1), "get_data".to_string()));
+/// assert!(!is_all_notifications(&requests));
+/// assert!(!is_all_notifications::<String, ()>(&[]));
+/// # }
+/// ```
+pub fn is_all_notifications<M, T>(requests: &[Request<M, T>]) -> bool {
+    !requests.is_empty() && requests.iter().all(|request| request.id.is_notification())
+}
+
+/// The order of the Responses of [`handle_batch_parallel`](fn.handle_batch_parallel.html).
+///
+/// The spec allows any order: the Client must match the Responses by `id`.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchOrder {
+    /// In the order of the Requests in the batch.
+    Request,
+    /// In the order the handlers completed.
+    Completion,
+}
+
+/// Identical to [`handle_batch`](fn.handle_batch.html) except that the elements of a batch are
+/// handled in parallel on the rayon thread pool.
+///
+/// Every element, including Notifications, is handed to the `handler`; only the Responses are in
+/// the `order`.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::router::{handle_batch_parallel, BatchOrder};
+/// use jrpc::{Response, Value};
+///
+/// # fn main() {
+/// let echo = |value: Value| {
+///     let request = jrpc::parse_request_value::<String>(value).ok()?;
+///     Some(Response::success(request.id.into_id()?, request.params.unwrap_or(Value::Null)))
+/// };
+///
+/// let json = r#"[
+///     {"jsonrpc": "2.0", "method": "echo", "params": [1], "id": 1},
+///     {"jsonrpc": "2.0", "method": "echo", "params": [2], "id": 2}
+/// ]"#;
+/// jrpc::testing::assert_json_eq(
+///     &handle_batch_parallel(echo, json, BatchOrder::Request).unwrap(),
+///     r#"[
+///         {"jsonrpc": "2.0", "result": [1], "id": 1},
+///         {"jsonrpc": "2.0", "result": [2], "id": 2}
+///     ]"#,
+/// );
+/// # }
+/// ```
+#[cfg(feature = "rayon")]
+pub fn handle_batch_parallel<F>(handler: F, json: &str, order: BatchOrder) -> Option<String>
+where
+    F: Fn(Value) -> Option<Response<Value>> + Sync,
+{
+    use rayon::prelude::*;
+    use std::sync::{Mutex, PoisonError};
+
+    let values = match split_batch(parse_json(json)) {
+        Err(reply) => return Some(reply),
+        Ok(OneOrMany::One(value)) => return handler(value).map(|r| r.to_string()),
+        Ok(OneOrMany::Many(values)) => values,
+    };
+    let responses = match order {
+        BatchOrder::Request => values.into_par_iter().filter_map(&handler).collect(),
+        BatchOrder::Completion => {
+            let responses = Mutex::new(Vec::new());
+            values.into_par_iter().for_each(|value| {
+                if let Some(response) = handler(value) {
+                    responses.lock().unwrap_or_else(PoisonError::into_inner).push(response);
+                }
+            });
+            responses.into_inner().unwrap_or_else(PoisonError::into_inner)
+        }
+    };
+    encode_batch(responses)
+}
+
+/// Split the parsed payload of `handle_batch`, returning the reply if it is invalid.
+fn split_batch(parsed: Result<Value, Error<Value>>) -> Result<OneOrMany<Value>, String> {
+    match parsed {
+        Err(error) => Err(ParseOptions::default().sanitize(error).to_string()),
+        Ok(Value::Array(ref values)) if values.is_empty() => {
+            trace!(warn!(code = ErrorCode::INVALID_REQUEST_CODE, "the batch is an empty Array"));
+            Err(Error::<Value>::new(
+                Id::Null,
+                ErrorCode::InvalidRequest,
+                "the batch is an empty Array",
+                None,
+            ).to_string())
+        }
+        Ok(value) => {
+            let payload = OneOrMany::from(value);
+            if let OneOrMany::Many(_) = payload {
+                trace!(debug!(len = payload.len(), "parsed the batch"));
+            }
+            Ok(payload)
+        }
+    }
+}
+
+/// Serialize the Responses of a batch. Nothing is replied if there are none.
+fn encode_batch(responses: Vec<Response<Value>>) -> Option<String> {
+    if responses.is_empty() {
+        return None;
+    }
+    match serde_json::to_string(&responses) {
+        Ok(json) => Some(json),
+        Err(err) => {
+            let message = format!("failed to serialize the Responses: {}", err);
+            Some(Error::<Value>::new(Id::Null, ErrorCode::InternalError, message, None).to_string())
+        }
+    }
+}
+
+/// Call `f`, converting a panic into an `InternalError` with the panic message.
+///
+/// The closure is wrapped in `AssertUnwindSafe`: state shared with the panicking code (i.e. a
+/// context behind a `RefCell`) may be left inconsistent, so only use this when a handler panics
+/// from bugs that don't corrupt shared state. `Mutex`es are poisoned as usual. The panic hook is
+/// still called, so the panic is still printed to stderr by default.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// use jrpc::{ErrorCode, Value};
+/// use jrpc::router::catch_to_internal_error;
+///
+/// # fn main() {
+/// let error = catch_to_internal_error::<Value, _>(|| panic!("index out of bounds")).unwrap_err();
+/// assert_eq!(error.code, ErrorCode::InternalError);
+/// assert_eq!(error.message, "the handler panicked: index out of bounds");
+/// # }
+/// ```
+pub fn catch_to_internal_error<R, F>(f: F) -> Result<R, ErrorObject<Value>>
+where
+    F: FnOnce() -> Result<R, ErrorObject<Value>>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(ErrorObject::internal(format!(
+            "the handler panicked: {}",
+            panic_message(&*payload)
+        )))
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown panic payload"
+    }
+}
+
+fn decode_params<P: DeserializeOwned>(params: Option<&Value>) -> Result<P, ErrorObject<Value>> {
+    P::deserialize(params.unwrap_or(&Value::Null))
+        .map_err(|err| ErrorObject::invalid_params(err.to_string()))
+}
+
+fn encode_result<R: Serialize>(result: &R) -> Result<Value, ErrorObject<Value>> {
+    serde_json::to_value(result)
+        .map_err(|err| ErrorObject::internal(format!("the result failed to serialize: {}", err)))
+}
@@ -0,0 +1,167 @@
+//! A Router whose handlers return futures.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use futures::future::{self, Either, FutureExt};
+
+use super::*;
+
+/// The future returned by the handlers of an [`AsyncRouter`](struct.AsyncRouter.html).
+pub type HandlerFuture = Pin<Box<dyn Future<Output = Result<Value, ErrorObject<Value>>> + Send>>;
+
+type AsyncHandler<Ctx> = Box<dyn Fn(&Ctx, Option<Value>) -> HandlerFuture + Send + Sync>;
+
+/// Identical to the [`Router`](struct.Router.html) except that the handlers return futures, i.e.
+/// for handlers doing database calls.
+///
+/// The futures returned by the methods don't borrow the router or the context, so they can be
+/// spawned on any runtime. A handler which needs the context must clone what it needs from it
+/// before returning its future. The AsyncRouter has no middleware.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate futures;
+/// use futures::executor::block_on;
+/// use futures::future;
+/// use jrpc::router::AsyncRouter;
+///
+/// # fn main() {
+/// let mut router = AsyncRouter::new();
+/// router.register("subtract", |_: &(), (a, b): (i64, i64)| future::ready(Ok(a - b)));
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+/// jrpc::testing::assert_json_eq(
+///     &block_on(router.handle(&(), json)).unwrap(),
+///     r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#,
+/// );
+/// # }
+/// ```
+pub struct AsyncRouter<Ctx> {
+    handlers: HashMap<String, AsyncHandler<Ctx>>,
+}
+
+impl<Ctx> Default for AsyncRouter<Ctx> {
+    fn default() -> Self {
+        AsyncRouter {
+            handlers: HashMap::new(),
+        }
+    }
+}
+
+impl<Ctx> AsyncRouter<Ctx> {
+    /// Create a router without any handlers.
+    pub fn new() -> Self {
+        AsyncRouter::default()
+    }
+
+    /// Register the `handler` of `method`, replacing any previous handler.
+    ///
+    /// The params are decoded as in [`Router::register`](struct.Router.html#method.register),
+    /// before the handler is called.
+    pub fn register<P, R, F, Fut>(&mut self, method: &str, handler: F) -> &mut Self
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+        F: Fn(&Ctx, P) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, ErrorObject<Value>>> + Send + 'static,
+    {
+        let handler = move |ctx: &Ctx, params: Option<Value>| -> HandlerFuture {
+            match decode_params(params.as_ref()) {
+                Ok(params) => handler(ctx, params)
+                    .map(|result| result.and_then(|r| encode_result(&r)))
+                    .boxed(),
+                Err(error) => future::ready(Err(error)).boxed(),
+            }
+        };
+        self.handlers.insert(method.to_string(), Box::new(handler));
+        self
+    }
+
+    /// Return whether a handler is registered for `method`.
+    pub fn has_method(&self, method: &str) -> bool {
+        self.handlers.contains_key(method)
+    }
+
+    /// Handle the json Request, resolving to the json Response.
+    ///
+    /// Resolves to `None` if the Request is a Notification.
+    pub fn handle(&self, ctx: &Ctx, json: &str) -> impl Future<Output = Option<String>> + Send {
+        self.respond(ctx, json).map(|r| r.map(|r| r.to_string()))
+    }
+
+    /// Handle the json Request, resolving to the Response.
+    ///
+    /// Resolves to `None` if the Request is a Notification.
+    pub fn respond(
+        &self,
+        ctx: &Ctx,
+        json: &str,
+    ) -> impl Future<Output = Option<Response<Value>>> + Send {
+        self.dispatch(ctx, parse_request::<String>(json))
+    }
+
+    /// Handle the json Request or batch of Requests, resolving to the json Response(s).
+    ///
+    /// The elements of a batch are driven concurrently and follow the rules of
+    /// [`handle_batch`](fn.handle_batch.html). The Responses are in the order of the Requests.
+    pub fn handle_batch(
+        &self,
+        ctx: &Ctx,
+        json: &str,
+    ) -> impl Future<Output = Option<String>> + Send {
+        let single = match split_batch(parse_json(json)) {
+            Err(reply) => return Either::Left(future::ready(Some(reply))),
+            Ok(OneOrMany::One(value)) => value,
+            Ok(OneOrMany::Many(values)) => {
+                let responses = values.into_iter().map(|value| self.respond_value(ctx, value));
+                let batch = future::join_all(responses)
+                    .map(|responses| encode_batch(responses.into_iter().flatten().collect()));
+                return Either::Right(Either::Left(batch));
+            }
+        };
+        Either::Right(Either::Right(
+            self.respond_value(ctx, single).map(|r| r.map(|r| r.to_string())),
+        ))
+    }
+
+    /// Handle the Request in the json `Value`, resolving to the Response.
+    ///
+    /// Resolves to `None` if the Request is a Notification.
+    pub fn respond_value(
+        &self,
+        ctx: &Ctx,
+        value: Value,
+    ) -> impl Future<Output = Option<Response<Value>>> + Send {
+        self.dispatch(ctx, parse_request_value::<String>(value))
+    }
+
+    /// Handle the parsed Request, resolving to the Response.
+    ///
+    /// Resolves to `None` if the Request is a Notification. The handler is still called.
+    pub fn handle_request(
+        &self,
+        ctx: &Ctx,
+        request: Request<String, Value>,
+    ) -> impl Future<Output = Option<Response<Value>>> + Send {
+        let result = match self.handlers.get(&request.method) {
+            Some(handler) => handler(ctx, request.params),
+            None => future::ready(Err(method_not_registered(&request.method))).boxed(),
+        };
+        let id = request.id.into_id();
+        result.map(move |result| id.map(|id| into_response(id, result)))
+    }
+
+    fn dispatch(
+        &self,
+        ctx: &Ctx,
+        parsed: Result<Request<String, Value>, Error<Value>>,
+    ) -> impl Future<Output = Option<Response<Value>>> + Send {
+        match parsed {
+            Ok(request) => Either::Left(self.handle_request(ctx, request)),
+            Err(error) => Either::Right(future::ready(Some(Response::Err(error)))),
+        }
+    }
+}
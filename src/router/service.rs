@@ -0,0 +1,180 @@
+//! `tower::Service` adapters of the routers.
+
+use std::convert::Infallible;
+use std::future::{self as std_future, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tower_service::Service;
+
+use super::*;
+
+/// A [`Router`](struct.Router.html) and its context as a `tower::Service`.
+///
+/// It is a `Service` of:
+///
+/// - `Request<String, Value>`, responding with the `Response` or `None` for a Notification.
+/// - `String`, a json Request or batch, responding with the json Response(s) or `None` when
+///   there is nothing to reply. See [`handle_batch`](fn.handle_batch.html).
+///
+/// The error is `Infallible`: a Request which fails (including a json payload which can't be
+/// parsed) is still answered with a jsonrpc Error Response. The Service is always ready and is
+/// cheap to clone.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate futures;
+/// extern crate tower;
+/// use futures::executor::block_on;
+/// use jrpc::router::Router;
+/// use tower::ServiceExt;
+///
+/// # fn main() {
+/// let mut router = Router::new();
+/// router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+/// let service = router.into_service(());
+///
+/// let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+/// let reply = block_on(service.oneshot(json.to_string())).unwrap();
+/// jrpc::testing::assert_json_eq(&reply.unwrap(), r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#);
+/// # }
+/// ```
+pub struct RouterService<Ctx> {
+    router: Arc<Router<Ctx>>,
+    ctx: Arc<Ctx>,
+}
+
+impl<Ctx> Clone for RouterService<Ctx> {
+    fn clone(&self) -> Self {
+        RouterService {
+            router: self.router.clone(),
+            ctx: self.ctx.clone(),
+        }
+    }
+}
+
+impl<Ctx> RouterService<Ctx> {
+    /// Wrap a shared `router` and `ctx`.
+    pub fn new(router: Arc<Router<Ctx>>, ctx: Arc<Ctx>) -> Self {
+        RouterService {
+            router: router,
+            ctx: ctx,
+        }
+    }
+}
+
+impl<Ctx> Router<Ctx> {
+    /// Wrap the router and the `ctx` of its handlers into a `tower::Service`. See
+    /// [`RouterService`](struct.RouterService.html).
+    pub fn into_service(self, ctx: Ctx) -> RouterService<Ctx> {
+        RouterService::new(Arc::new(self), Arc::new(ctx))
+    }
+}
+
+impl<Ctx> Service<Request<String, Value>> for RouterService<Ctx> {
+    type Response = Option<Response<Value>>;
+    type Error = Infallible;
+    type Future = Ready<Result<Option<Response<Value>>, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<String, Value>) -> Self::Future {
+        std_future::ready(Ok(self.router.handle_request(&self.ctx, request)))
+    }
+}
+
+impl<Ctx> Service<String> for RouterService<Ctx> {
+    type Response = Option<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Option<String>, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, json: String) -> Self::Future {
+        std_future::ready(Ok(self.router.handle_batch(&self.ctx, &json)))
+    }
+}
+
+#[cfg(feature = "futures")]
+pub use self::asynchronous::AsyncRouterService;
+
+#[cfg(feature = "futures")]
+mod asynchronous {
+    use std::future::Future;
+    use std::pin::Pin;
+
+    use futures::FutureExt;
+
+    use super::*;
+
+    /// The future of an [`AsyncRouterService`](struct.AsyncRouterService.html).
+    type ServiceFuture<R> = Pin<Box<dyn Future<Output = Result<R, Infallible>> + Send>>;
+
+    /// Identical to the [`RouterService`](struct.RouterService.html) but for an
+    /// [`AsyncRouter`](struct.AsyncRouter.html).
+    pub struct AsyncRouterService<Ctx> {
+        router: Arc<AsyncRouter<Ctx>>,
+        ctx: Arc<Ctx>,
+    }
+
+    impl<Ctx> Clone for AsyncRouterService<Ctx> {
+        fn clone(&self) -> Self {
+            AsyncRouterService {
+                router: self.router.clone(),
+                ctx: self.ctx.clone(),
+            }
+        }
+    }
+
+    impl<Ctx> AsyncRouterService<Ctx> {
+        /// Wrap a shared `router` and `ctx`.
+        pub fn new(router: Arc<AsyncRouter<Ctx>>, ctx: Arc<Ctx>) -> Self {
+            AsyncRouterService {
+                router: router,
+                ctx: ctx,
+            }
+        }
+    }
+
+    impl<Ctx> AsyncRouter<Ctx> {
+        /// Wrap the router and the `ctx` of its handlers into a `tower::Service`. See
+        /// [`AsyncRouterService`](struct.AsyncRouterService.html).
+        pub fn into_service(self, ctx: Ctx) -> AsyncRouterService<Ctx> {
+            AsyncRouterService::new(Arc::new(self), Arc::new(ctx))
+        }
+    }
+
+    impl<Ctx: 'static> Service<Request<String, Value>> for AsyncRouterService<Ctx> {
+        type Response = Option<Response<Value>>;
+        type Error = Infallible;
+        type Future = ServiceFuture<Option<Response<Value>>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request<String, Value>) -> Self::Future {
+            self.router.handle_request(&self.ctx, request).map(Ok).boxed()
+        }
+    }
+
+    impl<Ctx: 'static> Service<String> for AsyncRouterService<Ctx> {
+        type Response = Option<String>;
+        type Error = Infallible;
+        type Future = ServiceFuture<Option<String>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, json: String) -> Self::Future {
+            self.router.handle_batch(&self.ctx, &json).map(Ok).boxed()
+        }
+    }
+}
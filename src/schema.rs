@@ -0,0 +1,65 @@
+//! `JsonSchema` implementations for the types which cannot simply derive it.
+//!
+//! Everything else derives `JsonSchema` directly (see `lib.rs`).
+
+use std::borrow::Cow;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+
+use super::*;
+
+// ##################################################
+// # V2_0
+
+impl JsonSchema for V2_0 {
+    fn schema_name() -> Cow<'static, str> {
+        "V2_0".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "The jsonrpc version, MUST be exactly \"2.0\".",
+            "type": "string",
+            "const": "2.0",
+        })
+    }
+}
+
+// ##################################################
+// # ID REQ
+
+impl JsonSchema for IdReq {
+    fn schema_name() -> Cow<'static, str> {
+        "IdReq".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        // `Notification` is not a value: it is represented by the `id` member being absent, which
+        // is why `Request.id` is not a required property.
+        let mut schema = generator.subschema_for::<Id>();
+        schema.insert(
+            "description".into(),
+            "The request id. If the member is absent the request is a Notification.".into(),
+        );
+        schema
+    }
+}
+
+// ##################################################
+// # ERROR CODE
+
+impl JsonSchema for ErrorCode {
+    fn schema_name() -> Cow<'static, str> {
+        "ErrorCode".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A Number that indicates the error type that occurred. Known values: \
+                -32700 (Parse error), -32600 (Invalid Request), -32601 (Method not found), \
+                -32602 (Invalid params), -32603 (Internal error) and -32000 to -32099 \
+                (Server error).",
+            "type": "integer",
+            "format": "int64",
+        })
+    }
+}
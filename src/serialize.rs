@@ -1,10 +1,35 @@
 use std::result;
 use std::fmt;
+use std::io;
 use serde::{de, ser};
 use std_prelude::*;
 
 use super::*;
 
+// ##################################################
+// # LENGTH
+
+/// Count the bytes written, to get the length of the json without allocating it.
+struct ByteCount(usize);
+
+impl io::Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The length in bytes of the compact json of `value`.
+pub(crate) fn json_len<S: Serialize + ?Sized>(value: &S) -> serde_json::Result<usize> {
+    let mut count = ByteCount(0);
+    serde_json::to_writer(&mut count, value)?;
+    Ok(count.0)
+}
+
 // ##################################################
 // # V2_0
 
@@ -67,15 +92,7 @@ impl ser::Serialize for ErrorCode {
     where
         S: ser::Serializer,
     {
-        let value = match *self {
-            ErrorCode::ParseError => -32700,
-            ErrorCode::InvalidRequest => -32600,
-            ErrorCode::MethodNotFound => -32601,
-            ErrorCode::InvalidParams => -32602,
-            ErrorCode::InternalError => -32603,
-            ErrorCode::ServerError(value) => value,
-        };
-        serializer.serialize_i64(value)
+        serializer.serialize_i64(self.code())
     }
 }
 
@@ -100,6 +117,18 @@ impl<'de> de::Visitor<'de> for ErrorCodeVisitor {
     {
         Ok(ErrorCode::from(value))
     }
+
+    // Buffering deserializers (i.e. untagged enums) hand positive numbers over as `u64`.
+    fn visit_u64<E>(self, value: u64) -> result::Result<ErrorCode, E>
+    where
+        E: de::Error,
+    {
+        if value > i64::MAX as u64 {
+            Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+        } else {
+            Ok(ErrorCode::from(value as i64))
+        }
+    }
 }
 
 impl<'de> de::Deserialize<'de> for ErrorCode {
@@ -110,3 +139,254 @@ impl<'de> de::Deserialize<'de> for ErrorCode {
         deserializer.deserialize_i64(ErrorCodeVisitor)
     }
 }
+
+// ##################################################
+// # ID
+
+// Not derived: an untagged enum buffers the input and then copies a String id out of the buffer.
+
+struct IdVisitor;
+
+impl<'de> de::Visitor<'de> for IdVisitor {
+    type Value = Id;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a String, an integer Number or Null")
+    }
+
+    fn visit_str<E>(self, value: &str) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        Ok(Id::String(value.to_owned()))
+    }
+
+    fn visit_string<E>(self, value: String) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        Ok(Id::String(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        Ok(Id::Int(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        if value > i64::MAX as u64 {
+            Err(de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+        } else {
+            Ok(Id::Int(value as i64))
+        }
+    }
+
+    fn visit_unit<E>(self) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        Ok(Id::Null)
+    }
+
+    fn visit_none<E>(self) -> result::Result<Id, E>
+    where
+        E: de::Error,
+    {
+        Ok(Id::Null)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Id {
+    fn deserialize<D>(deserializer: D) -> result::Result<Id, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IdVisitor)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for IdReq {
+    fn deserialize<D>(deserializer: D) -> result::Result<IdReq, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IdVisitor).map(IdReq::from)
+    }
+}
+
+// ##################################################
+// # RESPONSE
+
+// Not derived: an untagged enum tries both variants and then fails with "data did not match any
+// variant", hiding whether the result or the error object is wrong. The member which is present
+// picks the variant, so the error of its value is the one reported.
+
+const RESPONSE_FIELDS: &[&str] = &["jsonrpc", "result", "error", "id"];
+
+enum ResponseField {
+    Jsonrpc,
+    Result,
+    Error,
+    Id,
+}
+
+struct ResponseFieldVisitor;
+
+impl<'de> de::Visitor<'de> for ResponseFieldVisitor {
+    type Value = ResponseField;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a member of a Response")
+    }
+
+    fn visit_str<E>(self, value: &str) -> result::Result<ResponseField, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            "jsonrpc" => Ok(ResponseField::Jsonrpc),
+            "result" => Ok(ResponseField::Result),
+            "error" => Ok(ResponseField::Error),
+            "id" => Ok(ResponseField::Id),
+            _ => Err(de::Error::unknown_field(value, RESPONSE_FIELDS)),
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for ResponseField {
+    fn deserialize<D>(deserializer: D) -> result::Result<ResponseField, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(ResponseFieldVisitor)
+    }
+}
+
+struct ResponseVisitor<T>(::std::marker::PhantomData<T>);
+
+impl<'de, T: de::Deserialize<'de>> de::Visitor<'de> for ResponseVisitor<T> {
+    type Value = Response<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a Response object")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> result::Result<Response<T>, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut jsonrpc = None;
+        let mut outcome: Option<result::Result<T, ErrorObject<Value>>> = None;
+        let mut id = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                ResponseField::Jsonrpc if jsonrpc.is_some() => {
+                    return Err(de::Error::duplicate_field("jsonrpc"));
+                }
+                ResponseField::Id if id.is_some() => return Err(de::Error::duplicate_field("id")),
+                ResponseField::Result | ResponseField::Error if outcome.is_some() => {
+                    return Err(de::Error::custom(
+                        "a Response has either a result or an error, not both",
+                    ));
+                }
+                ResponseField::Jsonrpc => jsonrpc = Some(map.next_value::<V2_0>()?),
+                ResponseField::Result => outcome = Some(Ok(map.next_value()?)),
+                ResponseField::Error => outcome = Some(Err(map.next_value()?)),
+                ResponseField::Id => id = Some(map.next_value()?),
+            }
+        }
+        let jsonrpc = jsonrpc.ok_or_else(|| de::Error::missing_field("jsonrpc"))?;
+        let id = id.ok_or_else(|| de::Error::missing_field("id"))?;
+        match outcome {
+            Some(Ok(result)) => Ok(Response::Ok(Success {
+                jsonrpc: jsonrpc,
+                result: result,
+                id: id,
+            })),
+            Some(Err(error)) => Ok(Response::Err(Error {
+                jsonrpc: jsonrpc,
+                error: error,
+                id: id,
+            })),
+            None => Err(de::Error::custom("a Response must have a result or an error member")),
+        }
+    }
+}
+
+impl<'de, T: de::Deserialize<'de>> de::Deserialize<'de> for Response<T> {
+    fn deserialize<D>(deserializer: D) -> result::Result<Response<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ResponseVisitor(::std::marker::PhantomData))
+    }
+}
+
+// ##################################################
+// # WRITE INTO
+
+/// Insert the members of the json Object of `value` into the `map`, overwriting its keys.
+fn write_members<S: Serialize>(
+    value: &S,
+    map: &mut serde_json::Map<String, Value>,
+) -> serde_json::Result<()> {
+    match serde_json::to_value(value)? {
+        Value::Object(members) => {
+            map.extend(members);
+            Ok(())
+        }
+        _ => unreachable!("the messages serialize to an Object"),
+    }
+}
+
+impl<M: Serialize, T: Serialize> Request<M, T> {
+    /// Insert the members of the Request into the `map`, i.e. an envelope of the transport,
+    /// instead of nesting it.
+    ///
+    /// The members are the ones of its json: there is no `id` for a Notification and no
+    /// `params` when they are absent. Keys of the `map` with the same name are overwritten.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// #[macro_use] extern crate serde_json;
+    /// use jrpc::{Id, Request, Value};
+    ///
+    /// # fn main() {
+    /// let mut envelope = serde_json::Map::new();
+    /// envelope.insert("channel".into(), Value::from("rpc"));
+    /// envelope.insert("seq".into(), Value::from(9));
+    ///
+    /// let request = Request::with_params(Id::from(1), "sum".to_string(), vec![1, 2]);
+    /// request.write_into(&mut envelope).unwrap();
+    /// assert_eq!(
+    ///     Value::Object(envelope),
+    ///     json!({"channel": "rpc", "seq": 9, "jsonrpc": "2.0", "method": "sum",
+    ///            "params": [1, 2], "id": 1}),
+    /// );
+    /// # }
+    /// ```
+    pub fn write_into(&self, map: &mut serde_json::Map<String, Value>) -> serde_json::Result<()> {
+        write_members(self, map)
+    }
+}
+
+impl<T: Serialize> Success<T> {
+    /// Insert the members of the Success into the `map`, overwriting its keys. See
+    /// [`Request::write_into`](struct.Request.html#method.write_into).
+    pub fn write_into(&self, map: &mut serde_json::Map<String, Value>) -> serde_json::Result<()> {
+        write_members(self, map)
+    }
+}
+
+impl<T: Serialize> Error<T> {
+    /// Insert the members of the Error into the `map`, overwriting its keys. See
+    /// [`Request::write_into`](struct.Request.html#method.write_into).
+    pub fn write_into(&self, map: &mut serde_json::Map<String, Value>) -> serde_json::Result<()> {
+        write_members(self, map)
+    }
+}
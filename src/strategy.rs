@@ -0,0 +1,220 @@
+//! [proptest] strategies for the jsonrpc types.
+//!
+//! Every type also implements `proptest::arbitrary::Arbitrary` with
+//! [`StrategyConfig`](struct.StrategyConfig.html) as its parameters, so `any::<Id>()` works as
+//! expected.
+//!
+//! By default only spec-valid values are generated: ids are never `Null`, error codes are always
+//! [valid](../enum.ErrorCode.html#method.is_valid) and params are always Structured. Set
+//! `StrategyConfig.borderline` to also generate the values a server has to tolerate but which the
+//! spec discourages.
+//!
+//! Numbers inside of generated `Value`s are always integers so that serializing and parsing them
+//! is lossless.
+//!
+//! [proptest]: https://docs.rs/proptest
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate proptest;
+//! extern crate serde_json;
+//! use jrpc::Id;
+//! use proptest::prelude::*;
+//! use proptest::test_runner::TestRunner;
+//!
+//! # fn main() {
+//! let mut runner = TestRunner::default();
+//! runner.run(&any::<Id>(), |id| {
+//!     let json = serde_json::to_string(&id).unwrap();
+//!     prop_assert_eq!(id, serde_json::from_str::<Id>(&json).unwrap());
+//!     Ok(())
+//! }).unwrap();
+//! # }
+//! ```
+
+use proptest::prelude::*;
+use proptest::arbitrary::Arbitrary;
+use proptest::strategy::BoxedStrategy;
+
+use super::*;
+
+/// Configuration for the generated values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrategyConfig {
+    /// Also generate borderline values: `Null` ids, arbitrary (including reserved but undefined)
+    /// error codes, arbitrary method names and non-Structured params.
+    pub borderline: bool,
+
+    /// The maximum nesting depth of generated `Value`s (params, results and error data).
+    pub max_depth: u32,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        StrategyConfig {
+            borderline: false,
+            max_depth: 3,
+        }
+    }
+}
+
+impl StrategyConfig {
+    /// The default config with `borderline` values enabled.
+    pub fn borderline() -> Self {
+        StrategyConfig {
+            borderline: true,
+            ..StrategyConfig::default()
+        }
+    }
+}
+
+/// Generate an `Id`.
+pub fn id(config: StrategyConfig) -> BoxedStrategy<Id> {
+    let valid = prop_oneof![
+        any::<String>().prop_map(Id::String),
+        any::<i64>().prop_map(Id::Int),
+    ];
+    if config.borderline {
+        prop_oneof![valid, Just(Id::Null)].boxed()
+    } else {
+        valid.boxed()
+    }
+}
+
+/// Generate an `IdReq`, including `Notification`.
+pub fn id_req(config: StrategyConfig) -> BoxedStrategy<IdReq> {
+    prop_oneof![
+        3 => id(config).prop_map(IdReq::from),
+        1 => Just(IdReq::Notification),
+    ].boxed()
+}
+
+/// Generate an `ErrorCode`.
+pub fn error_code(config: StrategyConfig) -> BoxedStrategy<ErrorCode> {
+    let valid = prop_oneof![
+        Just(ErrorCode::ParseError),
+        Just(ErrorCode::InvalidRequest),
+        Just(ErrorCode::MethodNotFound),
+        Just(ErrorCode::InvalidParams),
+        Just(ErrorCode::InternalError),
+        (ErrorCode::SERVER_ERROR_MIN..=ErrorCode::SERVER_ERROR_MAX)
+            .prop_map(ErrorCode::ServerError),
+    ];
+    if config.borderline {
+        prop_oneof![
+            valid,
+            // reserved for future use
+            (ErrorCode::RESERVED_MIN..ErrorCode::SERVER_ERROR_MIN).prop_map(ErrorCode::from),
+            any::<i64>().prop_map(ErrorCode::from),
+        ].boxed()
+    } else {
+        valid.boxed()
+    }
+}
+
+/// Generate a `Value` nested up to `config.max_depth`.
+pub fn value(config: StrategyConfig) -> BoxedStrategy<Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        any::<String>().prop_map(Value::String),
+    ];
+    leaf.prop_recursive(config.max_depth, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+            prop::collection::btree_map(any::<String>(), inner, 0..8)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    }).boxed()
+}
+
+/// Generate a Structured `Value`, i.e. an Array or an Object.
+pub fn structured_value(config: StrategyConfig) -> BoxedStrategy<Value> {
+    let inner = value(StrategyConfig {
+        max_depth: config.max_depth.saturating_sub(1),
+        ..config
+    });
+    prop_oneof![
+        prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+        prop::collection::btree_map(any::<String>(), inner, 0..8)
+            .prop_map(|map| Value::Object(map.into_iter().collect())),
+    ].boxed()
+}
+
+/// Generate the `params` of a `Request`.
+///
+/// `Some(Value::Null)` is never generated: `"params": null` is parsed as `None`.
+pub fn params(config: StrategyConfig) -> BoxedStrategy<Option<Value>> {
+    let values = if config.borderline {
+        value(config)
+    } else {
+        structured_value(config)
+    };
+    prop::option::of(values)
+        .prop_map(|params| params.filter(|params| !params.is_null()))
+        .boxed()
+}
+
+/// Generate a method name.
+pub fn method(config: StrategyConfig) -> BoxedStrategy<String> {
+    if config.borderline {
+        any::<String>().boxed()
+    } else {
+        "[a-zA-Z_][a-zA-Z0-9_.]{0,15}".boxed()
+    }
+}
+
+/// Generate an `ErrorObject`.
+pub fn error_object(config: StrategyConfig) -> BoxedStrategy<ErrorObject<Value>> {
+    (error_code(config), any::<String>(), prop::option::of(value(config)))
+        .prop_map(|(code, message, data)| ErrorObject {
+            code: code,
+            message: message.into(),
+            data: data,
+        })
+        .boxed()
+}
+
+/// Generate a `Request`.
+pub fn request(config: StrategyConfig) -> BoxedStrategy<Request<String, Value>> {
+    (id_req(config), method(config), params(config))
+        .prop_map(|(id, method, params)| Request {
+            jsonrpc: V2_0,
+            method: method,
+            params: params,
+            id: id,
+        })
+        .boxed()
+}
+
+/// Generate a `Response`, either `Ok` or `Err`.
+pub fn response(config: StrategyConfig) -> BoxedStrategy<Response<Value>> {
+    prop_oneof![
+        (id(config), value(config)).prop_map(|(id, result)| Response::success(id, result)),
+        (id(config), error_object(config))
+            .prop_map(|(id, error)| Response::Err(error.with_id(id))),
+    ].boxed()
+}
+
+macro_rules! impl_arbitrary {
+    ($ty:ty, $strategy:ident) => {
+        impl Arbitrary for $ty {
+            type Parameters = StrategyConfig;
+            type Strategy = BoxedStrategy<$ty>;
+
+            fn arbitrary_with(config: StrategyConfig) -> Self::Strategy {
+                $strategy(config)
+            }
+        }
+    };
+}
+
+impl_arbitrary!(Id, id);
+impl_arbitrary!(IdReq, id_req);
+impl_arbitrary!(ErrorCode, error_code);
+impl_arbitrary!(ErrorObject<Value>, error_object);
+impl_arbitrary!(Request<String, Value>, request);
+impl_arbitrary!(Response<Value>, response);
@@ -0,0 +1,51 @@
+//! A String or an integer Number, i.e. the tokens and ids established outside of the envelope.
+
+use super::*;
+
+/// A String or an integer Number, like an [`Id`](enum.Id.html) but never Null.
+///
+/// It is the token of a progress Notification
+/// ([`ProgressToken`](progress/type.ProgressToken.html)) and the id of a subscription
+/// ([`SubscriptionId`](subscription/type.SubscriptionId.html)).
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// extern crate serde_json;
+/// use jrpc::StringOrInt;
+///
+/// # fn main() {
+/// let token: StringOrInt = serde_json::from_str("7").unwrap();
+/// assert_eq!(token, StringOrInt::from(7));
+/// let token: StringOrInt = serde_json::from_str(r#""0xabc""#).unwrap();
+/// assert_eq!(token, StringOrInt::from("0xabc"));
+/// assert!(serde_json::from_str::<StringOrInt>("null").is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StringOrInt {
+    /// A String, i.e. `"0xabc"`.
+    String(String),
+    /// An integer Number.
+    Int(i64),
+}
+
+impl From<String> for StringOrInt {
+    fn from(s: String) -> Self {
+        StringOrInt::String(s)
+    }
+}
+
+impl<'a> From<&'a str> for StringOrInt {
+    fn from(s: &'a str) -> Self {
+        StringOrInt::String(s.into())
+    }
+}
+
+impl From<i64> for StringOrInt {
+    fn from(v: i64) -> Self {
+        StringOrInt::Int(v)
+    }
+}
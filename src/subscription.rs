@@ -0,0 +1,92 @@
+//! Subscription Notifications, used by pubsub APIs to push data to the Client.
+//!
+//! After the Client subscribed (i.e. with `eth_subscribe`, which replies with a subscription id),
+//! the Server sends Notifications of the form:
+//!
+//! ```json
+//! {
+//!     "jsonrpc": "2.0",
+//!     "method": "eth_subscription",
+//!     "params": {"subscription": "0xabc", "result": {}}
+//! }
+//! ```
+//!
+//! The name of the method depends on the API and is always given by the caller.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! extern crate serde_json;
+//! use jrpc::subscription::{self, SubscriptionId};
+//! use jrpc::Value;
+//!
+//! # fn main() {
+//! // Server
+//! let block = serde_json::json!({"number": "0x1b4"});
+//! let id = SubscriptionId::from("0xabc");
+//! let notification = subscription::notification("eth_subscription", id, block);
+//! let json = serde_json::to_string(&notification).unwrap();
+//!
+//! // Client
+//! let request = jrpc::parse_request::<String>(&json).unwrap();
+//! let params = subscription::decode::<Value>(&request, "eth_subscription")
+//!     .unwrap()
+//!     .unwrap();
+//! assert_eq!(params.subscription, SubscriptionId::from("0xabc"));
+//! assert_eq!(params.result["number"], "0x1b4");
+//! # }
+//! ```
+
+use serde::Deserialize;
+
+use super::*;
+
+/// The id of a subscription, established by the Server: a String or an integer Number.
+pub type SubscriptionId = StringOrInt;
+
+/// The params of a subscription Notification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionParams<T> {
+    /// The subscription the `result` belongs to.
+    pub subscription: SubscriptionId,
+    /// The pushed data.
+    pub result: T,
+}
+
+/// Create the Notification of `method` pushing the `result` of the `subscription`.
+pub fn notification<M, T>(
+    method: M,
+    subscription: SubscriptionId,
+    result: T,
+) -> Request<M, SubscriptionParams<T>> {
+    Request {
+        jsonrpc: V2_0,
+        method: method,
+        params: Some(SubscriptionParams {
+            subscription: subscription,
+            result: result,
+        }),
+        id: IdReq::Notification,
+    }
+}
+
+/// Decode the params of the `request` if it is a subscription Notification of `method`.
+///
+/// Returns `None` if the `request` is of another method or has an `id`. Otherwise returns the
+/// params, or the error if they fail to decode (i.e. they are not a `{"subscription", "result"}`
+/// Object or the `result` is not a `T`).
+pub fn decode<T>(
+    request: &Request<String, Value>,
+    method: &str,
+) -> Option<Result<SubscriptionParams<T>, serde_json::Error>>
+where
+    T: DeserializeOwned,
+{
+    if request.method != method || request.id != IdReq::Notification {
+        return None;
+    }
+    Some(SubscriptionParams::deserialize(
+        request.params.as_ref().unwrap_or(&Value::Null),
+    ))
+}
@@ -0,0 +1,168 @@
+//! Helpers for testing code which produces jsonrpc messages.
+//!
+//! Comparing serialized messages as strings is fragile: the key order of objects is insignificant
+//! and whitespace can only be stripped outside of strings. These helpers compare json the way a
+//! peer would see it and point at the first difference on failure.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # extern crate jrpc;
+//! use jrpc::{Id, Response};
+//! use jrpc::testing::assert_json_eq;
+//!
+//! # fn main() {
+//! let response = Response::success(Id::from(4), "hello world".to_string());
+//! assert_json_eq(&response.to_string(), r#"
+//! {
+//!     "id": 4,
+//!     "jsonrpc": "2.0",
+//!     "result": "hello world"
+//! }
+//! "#);
+//! # }
+//! ```
+
+use super::Value;
+
+/// Assert that `actual` and `expected` are the same json, ignoring whitespace and the order of
+/// object keys.
+///
+/// # Panics
+///
+/// Panics if either side is not valid json or if they differ, with the path to the first
+/// difference (i.e. `$.error.code`) in the message.
+pub fn assert_json_eq(actual: &str, expected: &str) {
+    let actual_value = parse(actual, "actual");
+    let expected_value = parse(expected, "expected");
+    if let Some(diff) = json_diff(&actual_value, &expected_value) {
+        panic!(
+            "json differs: {}\n  actual: {}\nexpected: {}",
+            diff, actual_value, expected_value
+        );
+    }
+}
+
+/// Assert that `actual` is byte-for-byte equal to `expected` once the insignificant whitespace of
+/// `expected` has been removed.
+///
+/// Use this when the exact bytes matter, i.e. the order of keys. Whitespace inside of strings is
+/// preserved.
+///
+/// # Panics
+///
+/// Panics if they differ, with the byte offset of the first difference in the message.
+pub fn assert_json_bytes_eq(actual: &str, expected: &str) {
+    let expected = minify(expected);
+    if actual != expected {
+        let offset = actual
+            .bytes()
+            .zip(expected.bytes())
+            .position(|(a, e)| a != e)
+            .unwrap_or_else(|| actual.len().min(expected.len()));
+        panic!(
+            "json bytes differ at offset {}\n  actual: {}\nexpected: {}",
+            offset, actual, expected
+        );
+    }
+}
+
+/// Return a description of the first difference between `actual` and `expected`, or `None` if
+/// they are equal.
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::testing::json_diff;
+///
+/// # fn main() {
+/// let actual = json!({"error": {"code": -32601}});
+/// let expected = json!({"error": {"code": -32600}});
+/// assert_eq!(
+///     json_diff(&actual, &expected).unwrap(),
+///     "at $.error.code: expected -32600, got -32601",
+/// );
+/// # }
+/// ```
+pub fn json_diff(actual: &Value, expected: &Value) -> Option<String> {
+    let mut path = "$".to_string();
+    diff_at(&mut path, actual, expected)
+}
+
+fn diff_at(path: &mut String, actual: &Value, expected: &Value) -> Option<String> {
+    match (actual, expected) {
+        (Value::Object(a), Value::Object(e)) => {
+            for (key, e_value) in e.iter() {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                let diff = match a.get(key) {
+                    Some(a_value) => diff_at(path, a_value, e_value),
+                    None => Some(format!("at {}: missing, expected {}", path, e_value)),
+                };
+                if diff.is_some() {
+                    return diff;
+                }
+                path.truncate(len);
+            }
+            a.keys()
+                .find(|key| !e.contains_key(*key))
+                .map(|key| format!("at {}.{}: unexpected member", path, key))
+        }
+        (Value::Array(a), Value::Array(e)) => {
+            for (i, (a_value, e_value)) in a.iter().zip(e.iter()).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", i));
+                let diff = diff_at(path, a_value, e_value);
+                if diff.is_some() {
+                    return diff;
+                }
+                path.truncate(len);
+            }
+            if a.len() != e.len() {
+                Some(format!(
+                    "at {}: expected {} elements, got {}",
+                    path,
+                    e.len(),
+                    a.len()
+                ))
+            } else {
+                None
+            }
+        }
+        _ if actual == expected => None,
+        _ => Some(format!("at {}: expected {}, got {}", path, expected, actual)),
+    }
+}
+
+fn parse(json: &str, side: &str) -> Value {
+    match ::serde_json::from_str(json) {
+        Ok(v) => v,
+        Err(err) => panic!("{} is not valid json ({}): {}", side, err, json),
+    }
+}
+
+/// Remove the whitespace outside of strings.
+fn minify(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if !c.is_whitespace() {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,106 @@
+//! Translate the method of a Request, i.e. between the external and internal names of a gateway.
+
+use std::error;
+use std::fmt;
+
+use super::*;
+
+/// The method couldn't be translated, see
+/// [`Request::try_map_method`](struct.Request.html#method.try_map_method).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMethod<M> {
+    /// The original method.
+    pub method: M,
+    /// The id of the Request.
+    pub id: IdReq,
+}
+
+impl<M: fmt::Display> UnknownMethod<M> {
+    /// The `MethodNotFound` error to reply with, `None` for a Notification.
+    pub fn to_error(&self) -> Option<Error<Value>> {
+        let object = ErrorObject {
+            code: ErrorCode::MethodNotFound,
+            message: format!("method `{}` not found", self.method).into(),
+            data: None,
+        };
+        self.id.clone().into_id().map(|id| object.with_id(id))
+    }
+}
+
+impl<M: fmt::Display> fmt::Display for UnknownMethod<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "method `{}` not found", self.method)
+    }
+}
+
+impl<M: fmt::Debug + fmt::Display> error::Error for UnknownMethod<M> {}
+
+impl<M, T> Request<M, T> {
+    /// The same Request with the method `f(method)`.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{Id, Request};
+    ///
+    /// # fn main() {
+    /// let request = Request::new(Id::from(1), "getUser".to_string());
+    /// let request = request.map_method(|method| format!("v2.{}", method));
+    /// assert_eq!(request.method, "v2.getUser");
+    /// # }
+    /// ```
+    pub fn map_method<N, F>(self, f: F) -> Request<N, T>
+    where
+        F: FnOnce(M) -> N,
+    {
+        Request {
+            jsonrpc: self.jsonrpc,
+            method: f(self.method),
+            params: self.params,
+            id: self.id,
+        }
+    }
+
+    /// The same Request with the method `f(method)`, or the method given back by `f` with the id
+    /// of the Request, to reply with a `MethodNotFound` error.
+    ///
+    /// ```rust
+    /// # extern crate jrpc;
+    /// use jrpc::{ErrorCode, Id, Request};
+    ///
+    /// # fn main() {
+    /// fn to_internal(method: String) -> Result<u32, String> {
+    ///     match method.as_str() {
+    ///         "v2.getUser" => Ok(1),
+    ///         _ => Err(method),
+    ///     }
+    /// }
+    ///
+    /// let request = Request::new(Id::from(1), "v2.getUser".to_string());
+    /// assert_eq!(request.try_map_method(to_internal).unwrap().method, 1);
+    ///
+    /// let request = Request::new(Id::from(2), "v1.getUser".to_string());
+    /// let unknown = request.try_map_method(to_internal).unwrap_err();
+    /// assert_eq!(unknown.method, "v1.getUser");
+    /// let error = unknown.to_error().unwrap();
+    /// assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    /// assert_eq!(error.id, Id::from(2));
+    /// # }
+    /// ```
+    pub fn try_map_method<N, F>(self, f: F) -> Result<Request<N, T>, UnknownMethod<M>>
+    where
+        F: FnOnce(M) -> Result<N, M>,
+    {
+        match f(self.method) {
+            Ok(method) => Ok(Request {
+                jsonrpc: self.jsonrpc,
+                method: method,
+                params: self.params,
+                id: self.id,
+            }),
+            Err(method) => Err(UnknownMethod {
+                method: method,
+                id: self.id,
+            }),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Conversions of already parsed json `Value`s.
+
+use super::*;
+
+/// The kind of a json message, as classified by [`JrpcValueExt::kind`].
+///
+/// The classification only looks at which members are present: a `Request` may still fail to
+/// parse.
+///
+/// [`JrpcValueExt::kind`]: trait.JrpcValueExt.html#tymethod.kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    /// An Object with a `method` and an `id`.
+    Request,
+    /// An Object with a `method` and no `id`.
+    Notification,
+    /// An Object with a `result`.
+    Success,
+    /// An Object with an `error`.
+    Error,
+    /// An Array, i.e. a batch.
+    Batch,
+    /// Anything else.
+    Invalid,
+}
+
+/// Convert a json `Value` into the types of this crate, i.e. when the transport already parsed
+/// the json.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jrpc;
+/// #[macro_use] extern crate serde_json;
+/// use jrpc::{ErrorCode, JrpcValueExt, MessageKind, Value};
+///
+/// # fn main() {
+/// let value = json!({"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1});
+/// assert_eq!(value.kind(), MessageKind::Request);
+/// let request = value.into_request::<String>().unwrap();
+/// assert_eq!(request.method, "subtract");
+///
+/// let error = json!({"jsonrpc": "2.0", "method": 1, "id": 1}).into_request::<String>();
+/// assert_eq!(error.unwrap_err().error.code, ErrorCode::InvalidRequest);
+///
+/// let value = json!({"jsonrpc": "2.0", "result": 19, "id": 1});
+/// assert_eq!(value.kind(), MessageKind::Success);
+/// let response = value.into_response::<i64>().unwrap();
+/// assert_eq!(response.id(), &jrpc::Id::from(1));
+/// # }
+/// ```
+pub trait JrpcValueExt {
+    /// Identical to [`parse_request_value`](fn.parse_request_value.html): the same error codes
+    /// as [`parse_request`](fn.parse_request.html), except that it can't be a `ParseError`.
+    fn into_request<M>(self) -> Result<Request<M, Value>, Error<Value>>
+    where
+        M: Serialize + DeserializeOwned;
+
+    /// Convert into a Response whose `result` is a `T`.
+    ///
+    /// Fails with a `ParseError` (like [`decode_response`](fn.decode_response.html)) if it is not
+    /// a Response or the `result` is not a `T`.
+    fn into_response<T>(self) -> Result<Response<T>, Error<Value>>
+    where
+        T: Serialize + DeserializeOwned;
+
+    /// Classify the message without converting it.
+    fn kind(&self) -> MessageKind;
+}
+
+impl JrpcValueExt for Value {
+    fn into_request<M>(self) -> Result<Request<M, Value>, Error<Value>>
+    where
+        M: Serialize + DeserializeOwned,
+    {
+        parse_request_value(self)
+    }
+
+    fn into_response<T>(self) -> Result<Response<T>, Error<Value>>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        sanitized(parse_response_value(self, None))
+    }
+
+    fn kind(&self) -> MessageKind {
+        let object = match *self {
+            Value::Array(_) => return MessageKind::Batch,
+            Value::Object(ref object) => object,
+            _ => return MessageKind::Invalid,
+        };
+        if object.contains_key("method") {
+            if object.contains_key("id") {
+                MessageKind::Request
+            } else {
+                MessageKind::Notification
+            }
+        } else if object.contains_key("result") {
+            MessageKind::Success
+        } else if object.contains_key("error") {
+            MessageKind::Error
+        } else {
+            MessageKind::Invalid
+        }
+    }
+}
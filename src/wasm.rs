@@ -0,0 +1,44 @@
+//! Conversions between the types and `JsValue`, for clients running in the browser.
+//!
+//! The conversions go through [`serde_wasm_bindgen`](https://docs.rs/serde-wasm-bindgen) directly
+//! instead of a JSON string. Values are converted the same way `JSON.parse` would produce them:
+//! Objects (not `Map`s), `null` for absent `params`/`data`, and no `id` property for a
+//! Notification.
+//!
+//! Only available with the `wasm` feature when compiling for `wasm32`.
+
+use serde_wasm_bindgen::{self, Serializer};
+use wasm_bindgen::JsValue;
+
+use super::*;
+
+fn to_js<S: Serialize>(value: &S) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    value.serialize(&Serializer::json_compatible())
+}
+
+impl<M: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned> Request<M, T> {
+    /// Convert a JavaScript object into a Request.
+    pub fn from_js(value: &JsValue) -> Result<Self, serde_wasm_bindgen::Error>
+    where
+        Self: DeserializeOwned,
+    {
+        serde_wasm_bindgen::from_value(value.clone())
+    }
+
+    /// Convert the Request into a plain JavaScript object.
+    pub fn to_js(&self) -> Result<JsValue, serde_wasm_bindgen::Error> {
+        to_js(self)
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Response<T> {
+    /// Convert a JavaScript object into a Response.
+    pub fn from_js(value: &JsValue) -> Result<Self, serde_wasm_bindgen::Error> {
+        serde_wasm_bindgen::from_value(value.clone())
+    }
+
+    /// Convert the Response into a plain JavaScript object.
+    pub fn to_js(&self) -> Result<JsValue, serde_wasm_bindgen::Error> {
+        to_js(self)
+    }
+}
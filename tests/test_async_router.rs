@@ -0,0 +1,107 @@
+//! The `AsyncRouter`, driven with `block_on` instead of a runtime.
+#![cfg(feature = "futures")]
+
+extern crate futures;
+extern crate jrpc;
+extern crate serde_json;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::{self, FutureExt};
+use jrpc::router::AsyncRouter;
+use jrpc::*;
+
+fn error_code(reply: &str) -> ErrorCode {
+    let error: Error<Value> = serde_json::from_str(reply).unwrap();
+    error.error.code
+}
+
+#[test]
+fn test_async_error_classes() {
+    let mut router = AsyncRouter::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| future::ready(Ok(a - b)));
+    router.register("fail", |_: &(), (): ()| {
+        future::ready(Err::<(), _>(ErrorObject {
+            code: ErrorCode::ServerError(-32000),
+            message: "failed".into(),
+            data: None,
+        }))
+    });
+
+    let handle = |json: &str| block_on(router.handle(&(), json)).unwrap();
+    assert_eq!(error_code(&handle("{")), ErrorCode::ParseError);
+    assert_eq!(error_code(&handle(r#"{"jsonrpc": "2.0", "id": 1}"#)), ErrorCode::InvalidRequest);
+    assert_eq!(
+        error_code(&handle(r#"{"jsonrpc": "2.0", "method": "foo", "id": 1}"#)),
+        ErrorCode::MethodNotFound,
+    );
+    assert_eq!(
+        error_code(&handle(r#"{"jsonrpc": "2.0", "method": "subtract", "params": ["a"], "id": 1}"#)),
+        ErrorCode::InvalidParams,
+    );
+    assert_eq!(
+        error_code(&handle(r#"{"jsonrpc": "2.0", "method": "fail", "id": 1}"#)),
+        ErrorCode::ServerError(-32000),
+    );
+}
+
+#[test]
+fn test_async_notification_is_executed() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut router = AsyncRouter::new();
+    router.register("count", |calls: &Arc<AtomicUsize>, (): ()| {
+        let calls = calls.clone();
+        future::lazy(move |_| Ok(calls.fetch_add(1, Ordering::SeqCst)))
+    });
+
+    let reply = block_on(router.handle(&calls, r#"{"jsonrpc": "2.0", "method": "count"}"#));
+    assert_eq!(reply, None);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_async_batch_is_concurrent() {
+    // `wait` only completes once `signal` was called, so the batch deadlocks unless its elements
+    // are driven concurrently.
+    let (sender, receiver) = oneshot::channel::<i64>();
+    let sender = std::sync::Mutex::new(Some(sender));
+    let receiver = std::sync::Mutex::new(Some(receiver));
+
+    let mut router = AsyncRouter::new();
+    router.register("wait", move |_: &(), (): ()| {
+        let receiver = receiver.lock().unwrap().take().unwrap();
+        receiver.map(|value| Ok(value.unwrap()))
+    });
+    router.register("signal", move |_: &(), (value,): (i64,)| {
+        sender.lock().unwrap().take().unwrap().send(value).unwrap();
+        future::ready(Ok(value))
+    });
+
+    let json = r#"[
+        {"jsonrpc": "2.0", "method": "wait", "id": 1},
+        {"jsonrpc": "2.0", "method": "signal", "params": [7]},
+        {"jsonrpc": "2.0", "method": "foo", "id": 2}
+    ]"#;
+    let reply = block_on(router.handle_batch(&(), json)).unwrap();
+    let responses: Vec<Value> = serde_json::from_str(&reply).unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0], serde_json::json!({"jsonrpc": "2.0", "result": 7, "id": 1}));
+    assert_eq!(responses[1]["error"]["code"], Value::from(-32601));
+}
+
+#[test]
+fn test_async_batch_edge_cases() {
+    let mut router = AsyncRouter::new();
+    router.register("noop", |_: &(), (): ()| future::ready(Ok(())));
+
+    let handle_batch = |json: &str| block_on(router.handle_batch(&(), json));
+    assert_eq!(error_code(&handle_batch("[]").unwrap()), ErrorCode::InvalidRequest);
+    assert_eq!(handle_batch(r#"[{"jsonrpc": "2.0", "method": "noop"}]"#), None);
+    jrpc::testing::assert_json_eq(
+        &handle_batch(r#"{"jsonrpc": "2.0", "method": "noop", "id": 3}"#).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": null, "id": 3}"#,
+    );
+}
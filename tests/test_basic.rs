@@ -1,6 +1,10 @@
 extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
 extern crate serde_json;
 
+use std::collections::HashMap;
+
 use jrpc::*;
 
 #[test]
@@ -14,3 +18,1184 @@ fn test_id() {
     let id: Id = serde_json::from_str("null").unwrap();
     assert_eq!(id, Id::Null);
 }
+
+#[test]
+fn test_id_req_accessors() {
+    let ids = [
+        IdReq::String("a".into()),
+        IdReq::Int(1),
+        IdReq::Null,
+        IdReq::Notification,
+    ];
+    let refs = [Some(IdRef::String("a")), Some(IdRef::Int(1)), Some(IdRef::Null), None];
+    let owned = [Some(Id::from("a")), Some(Id::Int(1)), Some(Id::Null), None];
+    for ((id, id_ref), owned) in ids.iter().zip(refs.iter()).zip(owned.iter()) {
+        assert_eq!(id.id_ref(), *id_ref);
+        assert_eq!(id.as_id(), *owned);
+        assert_eq!(id.clone().into_id(), *owned);
+        assert_eq!(id.is_notification(), *id == IdReq::Notification);
+        assert_eq!(id.is_null(), *id == IdReq::Null);
+    }
+}
+
+#[test]
+fn test_id_accessors() {
+    let id = Id::from("a");
+    assert_eq!(id.as_str(), Some("a"));
+    assert_eq!(id.as_int(), None);
+    assert!(!id.is_null());
+    let req = IdReq::from(id.clone());
+    assert_eq!((req.as_str(), req.as_int(), req.is_null()), (Some("a"), None, false));
+
+    // the String is borrowed, not copied
+    match (&id, &req) {
+        (Id::String(s), IdReq::String(r)) => {
+            assert_eq!(id.as_str().map(str::as_ptr), Some(s.as_ptr()));
+            assert_eq!(req.as_str().map(str::as_ptr), Some(r.as_ptr()));
+        }
+        _ => unreachable!(),
+    }
+
+    for &(ref id, int, null) in &[(Id::Int(-3), Some(-3), false), (Id::Null, None, true)] {
+        assert_eq!((id.as_int(), id.as_str(), id.is_null()), (int, None, null));
+        let req = IdReq::from(id.clone());
+        assert_eq!((req.as_int(), req.as_str(), req.is_null()), (int, None, null));
+    }
+    let req = IdReq::Notification;
+    assert_eq!((req.as_int(), req.as_str(), req.is_null()), (None, None, false));
+}
+
+#[test]
+fn test_id_value_conversions() {
+    use std::convert::TryFrom;
+
+    for (id, value) in &[
+        (Id::from("a"), Value::from("a")),
+        (Id::from(""), Value::from("")),
+        (Id::Int(4), Value::from(4)),
+        (Id::Int(i64::MIN), Value::from(i64::MIN)),
+        (Id::Null, Value::Null),
+    ] {
+        assert_eq!(Value::from(id), *value);
+        assert_eq!(Value::from(id.clone()), serde_json::to_value(id).unwrap());
+        assert_eq!(Id::try_from(value.clone()), Ok(id.clone()));
+    }
+
+    for (value, got) in [
+        (Value::from(true), "a boolean"),
+        (Value::from(1.5), "a float"),
+        (Value::from(1.0), "a float"),
+        (Value::from(u64::MAX), "an integer out of the range of i64"),
+        (Value::from(vec![1]), "an array"),
+        (serde_json::from_str(r#"{"id": 1}"#).unwrap(), "an object"),
+    ] {
+        let error = Id::try_from(value).unwrap_err();
+        assert_eq!(error, InvalidId { got });
+        let message = format!("id must be a string, integer, or null; got {}", got);
+        assert_eq!(error.to_string(), message);
+        let error: Box<dyn std::error::Error> = Box::new(error);
+        assert!(error.source().is_none());
+    }
+}
+
+#[test]
+fn test_error_code_buffered() {
+    // untagged enums buffer positive numbers as u64
+    let response: Response<Value> = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","error":{"code":100,"message":"pets busy"},"id":1}"#,
+    ).unwrap();
+    match response {
+        Response::Err(err) => assert_eq!(err.error.code, ErrorCode::ServerError(100)),
+        Response::Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn test_response_deserialize_errors() {
+    let cases = vec![
+        // the result is not a T
+        (
+            r#"{"jsonrpc": "2.0", "result": "a", "id": 1}"#,
+            r#"invalid type: string "a", expected u32 at line 1 column 32"#,
+        ),
+        // a malformed error object
+        (
+            r#"{"jsonrpc": "2.0", "error": {"code": 1}, "id": 1}"#,
+            "missing field `message` at line 1 column 39",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "error": {"code": "a", "message": "m"}, "id": 1}"#,
+            r#"invalid type: string "a", expected A valid json-rpc error code at line 1 column 40"#,
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "result": 1, "error": null, "id": 1}"#,
+            "a Response has either a result or an error, not both at line 1 column 39",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "id": 1}"#,
+            "a Response must have a result or an error member at line 1 column 27",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "result": 1}"#,
+            "missing field `id` at line 1 column 31",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "result": 1, "id": 1, "x": 2}"#,
+            "unknown field `x`, expected one of `jsonrpc`, `result`, `error`, `id` \
+             at line 1 column 44",
+        ),
+    ];
+    for (json, message) in cases {
+        let error = serde_json::from_str::<Response<u32>>(json).unwrap_err();
+        assert_eq!(error.to_string(), message);
+    }
+
+    let json = r#"{"id": 1, "result": 4, "jsonrpc": "2.0"}"#;
+    match serde_json::from_str::<Response<u32>>(json).unwrap() {
+        Response::Ok(success) => assert_eq!((success.result, success.id), (4, Id::from(1))),
+        Response::Err(error) => panic!("{}", error.to_string()),
+    }
+}
+
+#[test]
+fn test_assert_json_eq_spaces_in_strings() {
+    let request = Request::with_params(
+        Id::from("an id"),
+        "Create Foo".to_string(),
+        vec!["a b".to_string()],
+    );
+    testing::assert_json_eq(
+        &request.to_string(),
+        r#"{"id": "an id", "params": ["a b"], "method": "Create Foo", "jsonrpc": "2.0"}"#,
+    );
+    testing::assert_json_bytes_eq(
+        &request.to_string(),
+        r#"{"jsonrpc": "2.0", "method": "Create Foo", "params": ["a b"], "id": "an id"}"#,
+    );
+}
+
+#[test]
+fn test_json_diff() {
+    let actual: Value = serde_json::from_str(r#"{"a": [1, {"b": 2}], "c": 3}"#).unwrap();
+    let expected: Value = serde_json::from_str(r#"{"c": 3, "a": [1, {"b": 2}]}"#).unwrap();
+    assert_eq!(testing::json_diff(&actual, &expected), None);
+
+    let expected: Value = serde_json::from_str(r#"{"a": [1, {"b": 3}], "c": 3}"#).unwrap();
+    assert_eq!(
+        testing::json_diff(&actual, &expected).unwrap(),
+        "at $.a[1].b: expected 3, got 2"
+    );
+
+    let expected: Value = serde_json::from_str(r#"{"a": [1], "c": 3}"#).unwrap();
+    assert_eq!(
+        testing::json_diff(&actual, &expected).unwrap(),
+        "at $.a: expected 1 elements, got 2"
+    );
+
+    let expected: Value = serde_json::from_str(r#"{"a": [1, {"b": 2}]}"#).unwrap();
+    assert_eq!(
+        testing::json_diff(&actual, &expected).unwrap(),
+        "at $.c: unexpected member"
+    );
+
+    let expected: Value = serde_json::from_str(r#"{"a": [1, {"b": 2}], "c": 3, "d": 4}"#).unwrap();
+    assert_eq!(
+        testing::json_diff(&actual, &expected).unwrap(),
+        "at $.d: missing, expected 4"
+    );
+}
+
+#[test]
+#[should_panic(expected = "json differs: at $.id: expected 5, got 4")]
+fn test_assert_json_eq_panics() {
+    testing::assert_json_eq(r#"{"id": 4}"#, r#"{"id": 5}"#);
+}
+
+#[test]
+#[should_panic(expected = "json bytes differ at offset 2")]
+fn test_assert_json_bytes_eq_panics() {
+    testing::assert_json_bytes_eq(r#"{"a":1,"b":2}"#, r#"{"b": 2, "a": 1}"#);
+}
+
+#[test]
+fn test_namespaced_method() {
+    let method: NamespacedMethod = serde_json::from_str(r#""chain.subscribe.new""#).unwrap();
+    assert_eq!(method.namespace(), "chain");
+    assert_eq!(method.name(), "subscribe.new");
+    assert!(method.matches_namespace("chain"));
+    assert!(!method.matches_namespace("chain.subscribe"));
+    assert_eq!(method.to_string(), "chain.subscribe.new");
+    assert_eq!(
+        serde_json::to_string(&method).unwrap(),
+        r#""chain.subscribe.new""#
+    );
+
+    // no separator
+    let err = serde_json::from_str::<NamespacedMethod>(r#""subscribe""#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("expected a method of the form `namespace.name`"),
+        "{}",
+        err
+    );
+    let json = r#"{"jsonrpc": "2.0", "method": "subscribe", "id": 1}"#;
+    let error = parse_request::<NamespacedMethod>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.id, Id::from(1));
+
+    // custom separator
+    let method = NamespacedMethod::<Underscore>::new("eth", "getBalance");
+    let request = Request::with_params(Id::from(1), method, vec!["0x0".to_string()]);
+    let json = serde_json::to_string(&request).unwrap();
+    testing::assert_json_eq(
+        &json,
+        r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0"], "id": 1}"#,
+    );
+    let request = parse_request::<NamespacedMethod<Underscore>>(&json).unwrap();
+    assert_eq!(request.method.namespace(), "eth");
+    assert!(NamespacedMethod::<Underscore>::parse("eth.call").is_none());
+}
+
+#[test]
+fn test_reserved_methods() {
+    assert!(is_reserved_method("rpc.foo"));
+    assert!(is_reserved_method("rpc."));
+    assert!(!is_reserved_method("rpcx.foo"));
+    assert!(!is_reserved_method("rpc"));
+
+    let request = |method: &str| {
+        format!(
+            r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#,
+            method
+        )
+    };
+
+    for method in &["rpc.foo", "rpc."] {
+        let error = parse_request_strict::<String>(&request(method)).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+        assert_eq!(error.id, Id::from(1));
+        assert_eq!(
+            error.error.message,
+            format!("method `{}` is reserved for system extensions", method)
+        );
+
+        // not checked by default
+        assert!(parse_request::<String>(&request(method)).is_ok());
+    }
+    assert!(parse_request_strict::<String>(&request("rpcx.foo")).is_ok());
+
+    let options = ParseOptions::strict().allow_extension("rpc.discover");
+    let parsed = parse_request_with::<String>(&request("rpc.discover"), &options).unwrap();
+    assert_eq!(parsed.method, "rpc.discover");
+    assert!(parse_request_with::<String>(&request("rpc.foo"), &options).is_err());
+
+    let discover = Request::new(Id::from(1), "rpc.discover".to_string());
+    assert_eq!(
+        discover.validate().unwrap_err().message,
+        "method `rpc.discover` is reserved for system extensions"
+    );
+    assert!(discover.validate_with(&options).is_ok());
+    assert!(Request::new(Id::from(1), "rpcx.foo".to_string())
+        .validate()
+        .is_ok());
+}
+
+#[test]
+fn test_parse_aliases() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Method {
+        ChainGetBlock,
+        ChainGetHeader,
+    }
+
+    let options = ParseOptions::default()
+        .alias("getBlock", "chain_get_block")
+        .alias("getHeader", "chain_get_header");
+    let request =
+        |method: &str| format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#, method);
+
+    let (parsed, alias) = parse_request_aliased::<Method>(&request("getBlock"), &options).unwrap();
+    assert_eq!(parsed.method, Method::ChainGetBlock);
+    assert_eq!(alias, Some("getBlock".to_string()));
+    // a String knows every name, so the alias is kept
+    let (parsed, alias) =
+        parse_request_aliased::<String>(&request("getHeader"), &options).unwrap();
+    assert_eq!(parsed.method, "getHeader");
+    assert_eq!(alias, None);
+
+    // the canonical name still works
+    let (parsed, alias) =
+        parse_request_aliased::<Method>(&request("chain_get_block"), &options).unwrap();
+    assert_eq!(parsed.method, Method::ChainGetBlock);
+    assert_eq!(alias, None);
+
+    // aliases are not used by default
+    assert!(parse_request::<Method>(&request("getBlock")).is_err());
+
+    // only the canonical names are listed
+    let error = parse_request_with::<Method>(&request("getblock"), &options).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.id, Id::from(1));
+    assert_eq!(
+        error.error.message,
+        "unknown variant `getblock`, expected `chain_get_block` or `chain_get_header`"
+    );
+}
+
+#[test]
+fn test_parse_alias_is_a_method() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Method {
+        GetBlock,
+        ChainGetBlock,
+    }
+
+    // `get_block` is still a method of its own, but the old clients meant `chain_get_block`
+    // by `getBlock`
+    let options = ParseOptions::default()
+        .alias("get_block", "chain_get_block")
+        .alias("getBlock", "chain_get_block")
+        .alias("getHeader", "chain_get_header");
+    let request =
+        |method: &str| format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#, method);
+
+    let (parsed, alias) = parse_request_aliased::<Method>(&request("get_block"), &options).unwrap();
+    assert_eq!(parsed.method, Method::GetBlock);
+    assert_eq!(alias, None);
+
+    let (parsed, alias) = parse_request_aliased::<Method>(&request("getBlock"), &options).unwrap();
+    assert_eq!(parsed.method, Method::ChainGetBlock);
+    assert_eq!(alias, Some("getBlock".to_string()));
+
+    // the canonical name is not a method either: the error is about the name which was sent
+    let error = parse_request_with::<Method>(&request("getHeader"), &options).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(
+        error.error.message,
+        "unknown variant `getHeader`, expected `get_block` or `chain_get_block`"
+    );
+}
+
+#[test]
+fn test_parse_bom() {
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    for input in &[
+        format!("\u{feff}{}", json),
+        format!("\u{feff} \n\t{}\r\n ", json),
+        format!("  {}\n", json),
+    ] {
+        let request = parse_request::<String>(input).unwrap();
+        assert_eq!(request.method, "subtract");
+        assert!(parse_request_strict::<String>(input).is_ok());
+    }
+
+    let batch = format!("\u{feff}[{}]", json);
+    let reply = router::handle_batch(
+        |value| parse_request_value::<String>(value).err().map(Response::Err),
+        &batch,
+    );
+    assert_eq!(reply, None, "the batch element parsed");
+
+    for input in &[
+        "\u{feff}".to_string(),
+        "\u{feff}\u{feff}".to_string(),
+        "{\"jsonrpc\": \"2.0\",\u{feff} \"method\": \"foo\", \"id\": 1}".to_string(),
+        format!(" \u{feff}{}", json),
+    ] {
+        let error = parse_request::<String>(input).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError, "{:?}", input);
+    }
+}
+
+#[test]
+fn test_parse_empty() {
+    for input in &["", " ", "\n", " \r\n\t \n", "\u{feff}", "\u{feff}\n"] {
+        let error = parse_request::<String>(input).unwrap_err();
+        jrpc::testing::assert_json_eq(
+            &error.to_string(),
+            r#"{
+                "jsonrpc": "2.0",
+                "error": {"code": -32700, "message": "empty request body", "data": {"kind": "empty"}},
+                "id": null
+            }"#,
+        );
+        assert_eq!(router::handle_batch(|_| None, input), Some(error.to_string()));
+    }
+
+    let error = parse_request::<String>("{").unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_ne!(error.error.message, "empty request body");
+    assert_eq!(error.error.data, None);
+}
+
+#[test]
+fn test_parse_exact_trailing_data() {
+    let request = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let response = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+    let trailing = |json: &str| {
+        let error = parse_request_exact::<String>(json).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError, "{}", json);
+        assert_eq!(error.id, Id::Null);
+        let offset = error.error.data.as_ref().unwrap()["offset"].as_u64().unwrap() as usize;
+        assert_eq!(error.error.message, format!("trailing data at byte {}", offset));
+        assert_eq!(error.error.data.unwrap()["kind"], "trailing");
+        offset
+    };
+
+    assert!(parse_request_exact::<String>(request).is_ok());
+    assert!(parse_request_exact::<String>(&format!("\u{feff} {}\r\n", request)).is_ok());
+
+    // concatenated messages
+    assert_eq!(trailing(&format!("{}{}", request, request)), request.len());
+    assert_eq!(trailing(&format!("{}\n {}", request, request)), request.len() + 2);
+    // the offset counts the BOM (3 bytes)
+    assert_eq!(trailing(&format!("\u{feff}{}x", request)), request.len() + 3);
+    // trailing comma and garbage
+    assert_eq!(trailing(&format!("{},", request)), request.len());
+    assert_eq!(trailing(&format!("{} \0garbage", request)), request.len() + 1);
+
+    let parsed = parse_response_exact::<i64>(response).unwrap();
+    assert_eq!(parsed.id(), &Id::from(1));
+    let error = parse_response_exact::<i64>(&format!("{}{}", response, response)).unwrap_err();
+    assert_eq!(error.error.message, format!("trailing data at byte {}", response.len()));
+
+    // the errors of a single value are unchanged
+    let error = parse_request_exact::<String>(r#"{"jsonrpc": "2.0", "method": "a",}"#).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(error.error.data, None);
+    let error = parse_request_exact::<String>(r#"{"jsonrpc": "2.0", "method": 1, "id": 1} "#).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    assert_eq!(parse_request_exact::<String>("  ").unwrap_err().error.message, "empty request body");
+}
+
+#[test]
+fn test_parse_max_depth() {
+    let nested = |depth: usize| {
+        format!(
+            r#"{{"jsonrpc": "2.0", "method": "sum", "params": {}1{}, "id": 1}}"#,
+            "[".repeat(depth),
+            "]".repeat(depth)
+        )
+    };
+    let check = |error: Error<Value>| {
+        assert_eq!(error.error.code, ErrorCode::ParseError);
+        assert_eq!(error.error.message, "maximum nesting depth exceeded");
+        assert_eq!(error.error.data.unwrap()["kind"], "depth");
+    };
+
+    let deep = nested(1000);
+    check(parse_request::<String>(&deep).unwrap_err());
+    check(parse_request_exact::<String>(&deep).unwrap_err());
+    let response = format!(r#"{{"jsonrpc": "2.0", "result": {}, "id": 1}}"#, "[".repeat(1000));
+    check(parse_response_exact::<Value>(&response).unwrap_err());
+
+    // the object of the Request is one level
+    assert!(parse_request::<String>(&nested(DEFAULT_MAX_DEPTH - 1)).is_ok());
+    check(parse_request::<String>(&nested(DEFAULT_MAX_DEPTH)).unwrap_err());
+
+    let options = ParseOptions::default().max_depth(3);
+    assert!(parse_request_with::<String>(&nested(2), &options).is_ok());
+    let error = parse_request_with::<String>(&nested(3), &options).unwrap_err();
+    assert_eq!(error.error.data.as_ref().unwrap()["max_depth"], 3);
+    check(error);
+
+    // brackets in strings are not counted
+    let json = r#"{"jsonrpc": "2.0", "method": "[[[[", "params": ["\"{{{{"], "id": 1}"#;
+    assert!(parse_request_with::<String>(json, &options).is_ok());
+}
+
+#[test]
+fn test_parse_field_limits() {
+    let long = "x".repeat(100);
+    let request = |method: &str, id: &str| {
+        format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": "{}"}}"#, method, id)
+    };
+    let check = |error: Error<Value>, id: Id, field: &str, limit: usize| {
+        assert_eq!(error.id, id);
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.error.message,
+            format!("{} is 100 bytes, longer than the limit of {}", field, limit)
+        );
+        assert_eq!(
+            error.error.data.unwrap(),
+            serde_json::json!({"field": field, "limit": limit, "actual": 100})
+        );
+    };
+
+    // unlimited by default
+    assert!(parse_request::<String>(&request(&long, &long)).is_ok());
+
+    let options = ParseOptions::default().max_method_len(10);
+    assert!(parse_request_with::<String>(&request("sum", &long), &options).is_ok());
+    let error = parse_request_with::<String>(&request(&long, "a"), &options).unwrap_err();
+    check(error, Id::from("a"), "method", 10);
+
+    let options = ParseOptions::default().max_id_len(20);
+    assert!(parse_request_with::<String>(&request(&long, "a"), &options).is_ok());
+    let error = parse_request_with::<String>(&request("sum", &long), &options).unwrap_err();
+    check(error, Id::Null, "id", 20);
+
+    // the limits are inclusive
+    let options = ParseOptions::default().max_method_len(100).max_id_len(100);
+    assert!(parse_request_with::<String>(&request(&long, &long), &options).is_ok());
+}
+
+#[test]
+fn test_parse_not_an_object() {
+    for &(json, message) in &[
+        (
+            "[1, 2, 3]",
+            "expected a JSON object for a single request \
+             (arrays are batches — use router::handle_batch)",
+        ),
+        ("42", "expected a JSON object, got a number"),
+        (r#""subtract""#, "expected a JSON object, got a string"),
+        ("true", "expected a JSON object, got a boolean"),
+        ("null", "expected a JSON object, got null"),
+    ] {
+        let error = parse_request::<String>(json).unwrap_err();
+        assert_eq!(error.id, Id::Null);
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+        assert_eq!(error.error.message, message);
+    }
+}
+
+#[test]
+fn test_parse_wrong_version() {
+    for &(json, ref id, message) in &[
+        (
+            r#"{"method": "subtract", "id": 1}"#,
+            Id::Int(1),
+            "missing required jsonrpc member (is this a 1.0 request?)",
+        ),
+        (
+            r#"{"jsonrpc": 2.0, "method": "subtract", "id": "a"}"#,
+            Id::from("a"),
+            r#"jsonrpc member must be the string "2.0", got the number 2.0"#,
+        ),
+        (
+            r#"{"jsonrpc": "2.1", "method": "subtract", "id": 1}"#,
+            Id::Int(1),
+            r#"jsonrpc member must be the string "2.0", got the string "2.1""#,
+        ),
+        (
+            r#"{"jsonrpc": null, "method": "subtract", "id": 1}"#,
+            Id::Int(1),
+            r#"jsonrpc member must be the string "2.0", got null"#,
+        ),
+        // the id is not recoverable
+        (
+            r#"{"jsonrpc": "1.0", "method": "subtract", "id": [1]}"#,
+            Id::Null,
+            r#"jsonrpc member must be the string "2.0", got the string "1.0""#,
+        ),
+    ] {
+        let error = parse_request::<String>(json).unwrap_err();
+        assert_eq!(&error.id, id);
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+        assert_eq!(error.error.message, message);
+    }
+}
+
+#[test]
+fn test_parse_invalid_id_type() {
+    for &(id, got) in &[
+        ("true", "a boolean"),
+        ("[1]", "an array"),
+        (r#"{"x": 1}"#, "an object"),
+        ("1.5", "a float"),
+    ] {
+        let json = format!(r#"{{"jsonrpc": "2.0", "method": "subtract", "id": {}}}"#, id);
+        let error = parse_request::<String>(&json).unwrap_err();
+        assert_eq!(error.id, Id::Null);
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.error.message,
+            format!("id must be a string, integer, or null; got {}", got)
+        );
+    }
+}
+
+#[test]
+fn test_parse_member_wrong_type() {
+    let strict = ParseOptions::strict();
+    for &(json, member, ref id, message) in &[
+        (
+            r#"{"jsonrpc": 2, "method": "subtract", "id": 1}"#,
+            "jsonrpc",
+            Id::Int(1),
+            r#"jsonrpc member must be the string "2.0", got the number 2"#,
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "method": 5, "id": 1}"#,
+            "method",
+            Id::Int(1),
+            "invalid type for member `method`: expected a string, found a number",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "method": "subtract", "params": "str", "id": "a"}"#,
+            "params",
+            Id::from("a"),
+            "invalid type for member `params`: expected an array or an object, found a string",
+        ),
+        (
+            r#"{"jsonrpc": "2.0", "method": "subtract", "id": [1]}"#,
+            "id",
+            Id::Null,
+            "id must be a string, integer, or null; got an array",
+        ),
+    ] {
+        let error = parse_request_with::<String>(json, &strict).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest, "{}", json);
+        assert_eq!(&error.id, id, "{}", json);
+        assert_eq!(error.error.message, message);
+        assert!(error.error.message.contains(member), "{}", json);
+        assert_eq!(error.error.data, Some(serde_json::json!({ "member": member })));
+    }
+
+    // the params are only checked in strict mode
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": "str", "id": 1}"#;
+    assert_eq!(parse_request::<String>(json).unwrap().params, Some(serde_json::json!("str")));
+}
+
+#[test]
+fn test_parse_method_not_a_string() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Method {
+        Subtract,
+    }
+
+    for &(method, found) in &[
+        ("123", "a number"),
+        ("null", "null"),
+        (r#"{"name": "subtract"}"#, "an object"),
+        (r#"["subtract"]"#, "an array"),
+    ] {
+        let json = format!(r#"{{"jsonrpc": "2.0", "method": {}, "id": 3}}"#, method);
+        let message = format!(
+            "invalid type for member `method`: expected a string, found {}",
+            found
+        );
+        for error in [
+            parse_request::<String>(&json).unwrap_err(),
+            parse_request::<Value>(&json).unwrap_err(),
+            parse_request::<Method>(&json).unwrap_err(),
+            parse_request_strict::<Method>(&json).unwrap_err(),
+        ] {
+            assert_eq!(error.error.code, ErrorCode::InvalidRequest, "{}", json);
+            assert_eq!(error.id, Id::from(3), "{}", json);
+            assert_eq!(error.error.message, message);
+        }
+    }
+
+    // an unknown String method is still a MethodNotFound
+    let json = r#"{"jsonrpc": "2.0", "method": "sum", "id": 3}"#;
+    let error = parse_request::<Method>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.id, Id::from(3));
+}
+
+#[test]
+fn test_parse_request_typed() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Subtract {
+        minuend: i64,
+        subtrahend: i64,
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract",
+        "params": {"minuend": 42, "subtrahend": 23}, "id": "a"}"#;
+    let request = parse_request_typed::<String, Subtract>(json).unwrap();
+    assert_eq!(request.params, Some(Subtract { minuend: 42, subtrahend: 23 }));
+    assert_eq!(request.id, IdReq::from(Id::from("a")));
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "id": 1}"#;
+    assert_eq!(parse_request_typed::<String, Subtract>(json).unwrap().params, None);
+
+    // garbage params of a valid Request
+    for params in &["\"garbage\"", "[1]", r#"{"minuend": "42"}"#, "true"] {
+        let json = format!(
+            r#"{{"jsonrpc": "2.0", "method": "subtract", "params": {}, "id": 7}}"#,
+            params
+        );
+        let error = parse_request_typed::<String, Subtract>(&json).unwrap_err();
+        assert_eq!(error.error.code.code(), -32602, "{}", json);
+        assert_eq!(error.id, Id::from(7), "{}", json);
+    }
+
+    // the envelope is checked first
+    let json = r#"{"jsonrpc": "1.0", "method": "subtract", "params": "garbage", "id": 7}"#;
+    let error = parse_request_typed::<String, Subtract>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    assert_eq!(error.id, Id::from(7));
+    let json = r#"{"jsonrpc": "2.0", "method": 1, "params": "garbage", "id": 7}"#;
+    let error = parse_request_typed::<String, Subtract>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+}
+
+#[test]
+fn test_parse_method_types_share_errors() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Method {
+        Subtract,
+    }
+
+    let options = ParseOptions::strict().max_id_len(4);
+    for json in &[
+        "{",
+        "[1, 2]",
+        r#"{"method": "subtract", "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": "subtract", "id": 1.5}"#,
+        r#"{"jsonrpc": "2.0", "method": "subtract", "id": "abcde"}"#,
+        r#"{"jsonrpc": "2.0", "method": "rpc.discover", "id": 1}"#,
+    ] {
+        let string = parse_request_with::<String>(json, &options).unwrap_err();
+        let method = parse_request_with::<Method>(json, &options).unwrap_err();
+        assert_eq!(string.id, method.id, "{}", json);
+        assert_eq!(string.error.code, method.error.code, "{}", json);
+        assert_eq!(string.error.message, method.error.message, "{}", json);
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [2, 1], "id": "a"}"#;
+    let string = parse_request_with::<String>(json, &options).unwrap();
+    let method = parse_request_with::<Method>(json, &options).unwrap();
+    assert_eq!(string.method, "subtract");
+    assert_eq!(method.method, Method::Subtract);
+    assert_eq!((string.params, string.id), (method.params, method.id));
+
+    // only deserializing the method depends on its type
+    let json = r#"{"jsonrpc": "2.0", "method": "sum", "id": 1}"#;
+    let error = parse_request_with::<Method>(json, &options).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.error.message, "unknown variant `sum`, expected `subtract`");
+}
+
+#[test]
+fn test_error_data_as() {
+    let error = parse_request::<String>("").unwrap_err();
+    let data: HashMap<String, String> = error.data_as().unwrap().unwrap();
+    assert_eq!(data["kind"], "empty");
+    assert!(error.data_as::<Vec<u32>>().unwrap().is_err());
+    assert!(error.error.data_as::<Vec<u32>>().unwrap().is_err());
+
+    let error = parse_request::<String>("{").unwrap_err();
+    assert!(error.data_as::<Value>().is_none());
+}
+
+#[test]
+fn test_parse_response_lenient() {
+    let json = r#"{"jsonrpc": "2.0", "result": [1, 2], "id": 1, "time_ms": 5, "server": "a"}"#;
+    match parse_response_lenient::<Vec<u32>>(json).unwrap() {
+        Response::Ok(success) => {
+            assert_eq!(success.result, vec![1, 2]);
+            assert_eq!(success.id, Id::from(1));
+        }
+        Response::Err(error) => panic!("{}", error.to_string()),
+    }
+    assert!(serde_json::from_str::<Response<Vec<u32>>>(json).is_err());
+
+    let json = r#"{
+        "jsonrpc": "2.0",
+        "error": {"code": -32000, "message": "busy", "retry_after": 3},
+        "id": "a",
+        "time_ms": 5
+    }"#;
+    match parse_response_lenient::<Vec<u32>>(json).unwrap() {
+        Response::Err(error) => {
+            assert_eq!(error.error.code, ErrorCode::ServerError(-32000));
+            assert_eq!(error.error.data, None);
+            assert_eq!(error.id, Id::from("a"));
+        }
+        Response::Ok(_) => panic!("expected an error"),
+    }
+
+    // the result and error are still exclusive
+    let json = r#"{
+        "jsonrpc": "2.0",
+        "result": 1,
+        "error": {"code": -32000, "message": "busy"},
+        "id": 1,
+        "time_ms": 5
+    }"#;
+    let error = parse_response_lenient::<u32>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(error.id, Id::from(1));
+
+    let error = parse_response_lenient::<u32>(r#"{"jsonrpc": "2.0", "id": 1}"#).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+}
+
+#[test]
+fn test_parse_response_lenient_null_members() {
+    let json = r#"{"jsonrpc": "2.0", "result": 42, "error": null, "id": 1}"#;
+    assert!(serde_json::from_str::<Response<u32>>(json).is_err());
+    match parse_response_lenient::<u32>(json).unwrap() {
+        Response::Ok(success) => assert_eq!(success.result, 42),
+        Response::Err(error) => panic!("{}", error.to_string()),
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "result": null, "error": {"code": -32601, "message": "no"}, "id": 1}"#;
+    assert!(serde_json::from_str::<Response<u32>>(json).is_err());
+    match parse_response_lenient::<u32>(json).unwrap() {
+        Response::Err(error) => assert_eq!(error.error.code, ErrorCode::MethodNotFound),
+        Response::Ok(_) => panic!("expected an error"),
+    }
+
+    // a null result without an error is still a result
+    let json = r#"{"jsonrpc": "2.0", "result": null, "error": null, "id": 1}"#;
+    match parse_response_lenient::<Option<u32>>(json).unwrap() {
+        Response::Ok(success) => assert_eq!(success.result, None),
+        Response::Err(error) => panic!("{}", error.to_string()),
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "result": 42, "error": {"code": -32601, "message": "no"}, "id": 1}"#;
+    let error = parse_response_lenient::<u32>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+}
+
+#[test]
+fn test_parse_success_and_error() {
+    let success = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+    let error = r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "no"}, "id": "a"}"#;
+    assert_eq!(parse_success::<u32>(success).unwrap().result, 19);
+    assert_eq!(parse_error(error).unwrap().error.code, ErrorCode::MethodNotFound);
+
+    // the crossed cases
+    let crossed = parse_success::<u32>(error).unwrap_err();
+    assert_eq!(crossed.id, Id::from("a"));
+    assert_eq!(crossed.error.code, ErrorCode::ParseError);
+    assert_eq!(
+        crossed.error.message,
+        "expected a result member but found error (code -32601)"
+    );
+    let crossed = parse_error(success).unwrap_err();
+    assert_eq!(crossed.id, Id::from(1));
+    assert_eq!(crossed.error.code, ErrorCode::ParseError);
+    assert_eq!(crossed.error.message, "expected an error member but found result");
+
+    let cases = vec![
+        (r#"{"jsonrpc": "2.0", "id": 1}"#, Id::from(1), "expected a result member"),
+        (
+            r#"{"jsonrpc": "2.0", "error": 4, "id": 1}"#,
+            Id::from(1),
+            "expected a result member but found error",
+        ),
+        ("[]", Id::Null, "invalid response: expected a JSON object, got an array"),
+        (
+            r#"{"result": 1, "id": 1}"#,
+            Id::from(1),
+            "invalid response: missing required jsonrpc member",
+        ),
+        (
+            r#"{"jsonrpc": "1.0", "result": 1, "id": 1}"#,
+            Id::from(1),
+            r#"invalid response: jsonrpc member must be the string "2.0", got the string "1.0""#,
+        ),
+        (r#"{"jsonrpc": "2.0", "result": 1}"#, Id::Null, "invalid response: missing id member"),
+        (
+            r#"{"jsonrpc": "2.0", "result": 1, "id": true}"#,
+            Id::Null,
+            "invalid response: id must be a string, integer, or null; got a boolean",
+        ),
+    ];
+    for (json, id, message) in cases {
+        let error = parse_success::<u32>(json).unwrap_err();
+        assert_eq!(error.id, id, "{}", json);
+        assert_eq!(error.error.code, ErrorCode::ParseError);
+        assert_eq!(error.error.message, message);
+    }
+    let error = parse_error(r#"{"jsonrpc": "2.0", "id": null}"#).unwrap_err();
+    assert_eq!(error.error.message, "expected an error member");
+
+    // the envelope is checked the same way for any Response
+    let json = r#"{"jsonrpc": "2.0", "result": 1, "id": [1]}"#;
+    let message = "invalid response: id must be a string, integer, or null; got an array";
+    assert_eq!(parse_success::<u32>(json).unwrap_err().error.message, message);
+    assert_eq!(parse_error(json).unwrap_err().error.message, message);
+    assert_eq!(parse_response_exact::<u32>(json).unwrap_err().error.message, message);
+
+    // trailing data is rejected as by parse_response_exact
+    let json = r#"{"jsonrpc": "2.0", "result": 1, "id": 1} {}"#;
+    let data = serde_json::json!({"kind": "trailing", "offset": 41});
+    assert_eq!(parse_success::<u32>(json).unwrap_err().error.data, Some(data.clone()));
+    assert_eq!(parse_error(json).unwrap_err().error.data, Some(data.clone()));
+    assert_eq!(parse_response_exact::<u32>(json).unwrap_err().error.data, Some(data));
+
+    // a result of the wrong type
+    let error = parse_success::<u32>(r#"{"jsonrpc": "2.0", "result": "a", "id": 1}"#);
+    let error = error.unwrap_err();
+    assert_eq!(error.id, Id::from(1));
+    assert!(error.error.message.starts_with("invalid response: "), "{}", error.error.message);
+}
+
+#[test]
+fn test_parse_response_typed() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Retry {
+        after_ms: u64,
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#;
+    let success = parse_response_typed::<u32, Retry>(json).unwrap().unwrap();
+    assert_eq!(success.result, 19);
+
+    let response = |data: &str| {
+        format!(
+            r#"{{"jsonrpc": "2.0", "error": {{"code": -32000, "message": "busy"{}}}, "id": 1}}"#,
+            data
+        )
+    };
+    let error = parse_response_typed::<u32, Retry>(&response(r#", "data": {"after_ms": 5}"#));
+    let error = error.unwrap().unwrap_err();
+    assert_eq!(error.id, Id::from(1));
+    assert_eq!(error.error.code, ErrorCode::ServerError(-32000));
+    assert_eq!(error.error.message, "busy");
+    assert_eq!(error.error.data, Some(Retry { after_ms: 5 }));
+
+    for data in &["", r#", "data": null"#] {
+        let error = parse_response_typed::<u32, Retry>(&response(data)).unwrap();
+        assert_eq!(error.unwrap_err().error.data, None);
+    }
+
+    // the same as parse_response_exact with Value data
+    let json = response(r#", "data": [1, "a"]"#);
+    let typed = parse_response_typed::<u32, Value>(&json).unwrap().unwrap_err();
+    match parse_response_exact::<u32>(&json).unwrap() {
+        Response::Err(error) => assert_eq!(typed.error.data, error.error.data),
+        Response::Ok(_) => panic!("expected an error"),
+    }
+
+    // data which is not an E
+    let json = response(r#", "data": "later""#);
+    let error = parse_response_typed::<u32, Retry>(&json).unwrap_err();
+    assert_eq!(error.id, Id::from(1));
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(
+        error.error.message,
+        concat!(
+            "invalid response: error.data is invalid: ",
+            r#"invalid type: string "later", expected struct Retry"#,
+        )
+    );
+    let error = parse_response_typed::<u32, Retry>(&response(r#", "data": {"after_ms": -1}"#));
+    assert_eq!(
+        error.unwrap_err().error.message,
+        "invalid response: error.data is invalid: invalid value: integer `-1`, expected u64"
+    );
+}
+
+#[test]
+fn test_parse_response_error_object_hints() {
+    let response = |error: &str| format!(r#"{{"jsonrpc": "2.0", "error": {}, "id": 1}}"#, error);
+    let cases = vec![
+        (r#"{"code": "oops", "message": "m"}"#, r#"error.code must be an integer, got "oops""#),
+        (r#"{"code": 1.5, "message": "m"}"#, "error.code must be an integer, got 1.5"),
+        (r#"{"message": "m"}"#, "error.code is missing"),
+        (r#"{"code": -32000}"#, "error.message is missing"),
+        (r#"{"code": -32000, "message": 4}"#, "error.message must be a string, got 4"),
+        ("4", "error is not an object, got the number 4"),
+        (r#""oops""#, r#"error is not an object, got the string "oops""#),
+        (
+            r#"{"code": -32000, "stack": "at main", "type": "Err"}"#,
+            "error.message is missing (error also has the unusual members `stack`, `type`, \
+             which are ignored)",
+        ),
+    ];
+    for (error, hint) in cases {
+        let json = response(error);
+        let message = format!("invalid response: {}", hint);
+        let error = parse_response_exact::<Value>(&json).unwrap_err();
+        assert_eq!(error.id, Id::from(1));
+        assert_eq!(error.error.code, ErrorCode::ParseError);
+        assert_eq!(error.error.message, message);
+        assert_eq!(parse_response_lenient::<Value>(&json).unwrap_err().error.message, message);
+        assert_eq!(parse_error(&json).unwrap_err().error.message, message);
+    }
+
+    // the unusual members of a valid error object are ignored
+    let json = response(r#"{"code": -32000, "message": "m", "stack": "at main"}"#);
+    let error = parse_error(&json).unwrap();
+    assert_eq!(error.error.message, "m");
+
+    // the hints are the same with typed data
+    let json = response(r#"{"code": "oops", "message": "m"}"#);
+    let error = parse_response_typed::<Value, HashMap<String, u32>>(&json).unwrap_err();
+    assert_eq!(
+        error.error.message,
+        r#"invalid response: error.code must be an integer, got "oops""#
+    );
+
+    // the hints are only about the error member
+    let json = r#"{"jsonrpc": "2.0", "result": 1, "error": {"code": 1, "message": "m"}, "id": 1}"#;
+    let error = parse_response_exact::<Value>(json).unwrap_err();
+    assert_eq!(
+        error.error.message,
+        "invalid response: a Response has either a result or an error, not both"
+    );
+}
+
+#[test]
+fn test_params_round_trip() {
+    let request = Request::with_params(Id::from(1), "sum".to_string(), vec![1, 2]);
+    let json = request.to_string();
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"sum","params":[1,2],"id":1}"#);
+    let parsed: Request<String, Vec<u32>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.params, Some(vec![1, 2]));
+
+    let request = Request::new(Id::from(1), "ping".to_string());
+    let json = request.to_string();
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"ping","id":1}"#);
+    let parsed = parse_request::<String>(&json).unwrap();
+    assert_eq!(parsed.params, None);
+    assert_eq!(parsed.to_string(), json);
+
+    // an explicit null is parsed as absent, and then omitted
+    let parsed = parse_request::<String>(r#"{"jsonrpc":"2.0","method":"ping","params":null,"id":1}"#)
+        .unwrap();
+    assert_eq!(parsed.params, None);
+    assert_eq!(parsed.to_string(), json);
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Method {
+    Subtract,
+}
+
+#[test]
+fn test_parse_long_error_message() {
+    let long = "x".repeat(1 << 20);
+    let json = format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#, long);
+
+    let error = parse_request::<Method>(&json).unwrap_err();
+    assert_eq!(error.id, Id::Int(1));
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert!(error.error.message.starts_with("unknown variant `xxx"));
+    assert!(error.error.message.ends_with("..."));
+    assert_eq!(error.error.message.len(), DEFAULT_MAX_MESSAGE_LEN + 3);
+    assert!(error.to_string().len() < 512);
+
+    let options = ParseOptions::default().max_message_len(Some(10));
+    let error = parse_request_with::<Method>(&json, &options).unwrap_err();
+    assert_eq!(error.error.message, "unknown va...");
+    assert_eq!(error.error.data, None);
+
+    // the full message is only kept when asked for
+    let error = parse_request_with::<Method>(&json, &options.full_message_in_data(true))
+        .unwrap_err();
+    let full = error.error.data.unwrap()["full_message"].as_str().unwrap().to_string();
+    assert!(full.len() > long.len());
+
+    let options = ParseOptions::default().max_message_len(None);
+    let error = parse_request_with::<Method>(&json, &options).unwrap_err();
+    assert_eq!(error.error.message, full);
+
+    // cut at a character boundary
+    let json = format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#, "é".repeat(200));
+    let options = ParseOptions::default().max_message_len(Some(19));
+    let error = parse_request_with::<Method>(&json, &options).unwrap_err();
+    assert_eq!(error.error.message, "unknown variant `é...");
+    let error = parse_request_with::<Method>(&json, &options.max_message_len(Some(18)))
+        .unwrap_err();
+    assert_eq!(error.error.message, "unknown variant `...");
+}
+
+#[test]
+fn test_success_accessors() {
+    let success = Success::new(Id::from(4), vec![1, 2, 3]);
+    // borrowed, not copied
+    assert!(std::ptr::eq(success.as_result(), &success.result));
+    let buffer = success.result.as_ptr();
+
+    let (id, result) = success.into_parts();
+    assert_eq!(id, Id::from(4));
+    assert_eq!(result, vec![1, 2, 3]);
+    assert_eq!(result.as_ptr(), buffer);
+
+    let success = Success::new(Id::from("a"), result);
+    let result = success.into_result();
+    assert_eq!(result.as_ptr(), buffer);
+
+    let response: Response<Vec<u32>> = Response::success(Id::from(1), result);
+    let result = response.into_result().unwrap();
+    assert_eq!(result.as_ptr(), buffer);
+}
+
+#[test]
+fn test_error_into_parts() {
+    let error: Error<Value> =
+        Error::new(Id::from(4), ErrorCode::InvalidParams, "bad", Some(Value::from("x")));
+    let (id, object) = error.into_parts();
+    assert_eq!(id, Id::from(4));
+    assert_eq!(object.code, ErrorCode::InvalidParams);
+    assert_eq!(object.message, "bad");
+    assert_eq!(object.data, Some(Value::from("x")));
+
+    let response: Response<u32> =
+        Response::error(Id::from(5), ErrorCode::InternalError, "oops", None);
+    let error = response.into_result().unwrap_err();
+    assert_eq!(error.id, Id::from(5));
+    assert_eq!(error.error.message, "oops");
+}
+
+fn failed() -> Response<i64> {
+    let data = Some(Value::from("minuend"));
+    Response::error(Id::from("a"), ErrorCode::InvalidParams, "not a number", data)
+}
+
+#[test]
+fn test_response_unwrap() {
+    assert_eq!(Response::success(Id::from(1), 19).unwrap_result(), 19);
+    assert_eq!(Response::success(Id::from(1), 19).expect("subtract failed"), 19);
+    assert_eq!(failed().unwrap_err().error.message, "not a number");
+}
+
+#[test]
+#[should_panic(expected = "called `Response::unwrap_result()` on an Error: \
+                           id=\"a\" Invalid params (-32602): not a number; data: \"minuend\"")]
+fn test_response_unwrap_result_panics() {
+    failed().unwrap_result();
+}
+
+#[test]
+#[should_panic(expected = "subtract failed: id=\"a\" Invalid params (-32602): not a number")]
+fn test_response_expect_panics() {
+    failed().expect("subtract failed");
+}
+
+#[test]
+#[should_panic(expected = "called `Response::unwrap_err()` on a Success: id=1 result=[19]")]
+fn test_response_unwrap_err_panics() {
+    Response::success(Id::from(1), vec![19]).unwrap_err();
+}
+
+#[test]
+fn test_try_to_string() {
+    // keys which are not strings can't be serialized
+    let mut params = std::collections::BTreeMap::new();
+    params.insert((1, 2), 3);
+
+    let request = Request::with_params(Id::from(1), "sum".to_string(), params.clone());
+    assert_eq!(request.try_to_string().unwrap_err().to_string(), "key must be a string");
+    let request = Request::with_params(Id::from(1), "sum".to_string(), vec![1, 2]);
+    assert_eq!(request.try_to_string().unwrap(), request.to_string());
+
+    let success = Success::new(Id::from(1), params.clone());
+    assert!(success.try_to_string().is_err());
+    let response = Response::Ok(success);
+    assert!(response.try_to_string().is_err());
+    let error = Error::new(Id::from(1), ErrorCode::InternalError, "failed", Some(params));
+    assert!(error.try_to_string().is_err());
+}
+
+#[test]
+#[should_panic(expected = "the Response failed to serialize: key must be a string")]
+fn test_to_string_panics() {
+    let mut result = std::collections::BTreeMap::new();
+    result.insert((1, 2), 3);
+    Response::success(Id::from(1), result).to_string();
+}
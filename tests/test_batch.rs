@@ -0,0 +1,138 @@
+//! The batch examples of section 7 of the spec, through `handle_batch`.
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::router::Router;
+use jrpc::*;
+
+fn spec_router() -> Router<()> {
+    let mut router = Router::new();
+    router
+        .register("sum", |_: &(), values: Vec<i64>| {
+            Ok(Value::from(values.iter().sum::<i64>()))
+        })
+        .register("subtract", |_: &(), (a, b): (i64, i64)| Ok(Value::from(a - b)))
+        .register("notify_hello", |_: &(), _: Vec<i64>| Ok(Value::Null))
+        .register("notify_sum", |_: &(), _: Vec<i64>| Ok(Value::Null))
+        .register_fn("get_data", |(): ()| Ok(("hello", 5)));
+    router
+}
+
+/// Remove the error messages, which are more detailed than the spec's, and the `"data": null`
+/// which this crate emits for absent data.
+fn strip_messages(value: &mut Value) {
+    match *value {
+        Value::Array(ref mut values) => values.iter_mut().for_each(strip_messages),
+        Value::Object(ref mut map) => {
+            if let Some(error) = map.get_mut("error").and_then(Value::as_object_mut) {
+                error.remove("message");
+                if error.get("data") == Some(&Value::Null) {
+                    error.remove("data");
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn assert_reply(request: &str, expected: Option<&str>) {
+    let reply = spec_router().handle_batch(&(), request);
+    match (reply, expected) {
+        (None, None) => {}
+        (Some(reply), Some(expected)) => {
+            let mut reply: Value = serde_json::from_str(&reply).unwrap();
+            let mut expected: Value = serde_json::from_str(expected).unwrap();
+            strip_messages(&mut reply);
+            strip_messages(&mut expected);
+            testing::assert_json_eq(&reply.to_string(), &expected.to_string());
+        }
+        (reply, expected) => panic!("expected {:?}, got {:?}", expected, reply),
+    }
+}
+
+#[test]
+fn test_batch_invalid_json() {
+    assert_reply(
+        r#"[
+          {"jsonrpc": "2.0", "method": "sum", "params": [1,2,4], "id": "1"},
+          {"jsonrpc": "2.0", "method"
+        ]"#,
+        Some(r#"{"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null}"#),
+    );
+}
+
+#[test]
+fn test_batch_empty() {
+    assert_reply(
+        "[]",
+        Some(r#"{"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}"#),
+    );
+}
+
+#[test]
+fn test_batch_invalid_one() {
+    assert_reply(
+        "[1]",
+        Some(r#"[
+          {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}
+        ]"#),
+    );
+}
+
+#[test]
+fn test_batch_invalid_many() {
+    assert_reply(
+        "[1,2,3]",
+        Some(r#"[
+          {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+          {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+          {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}
+        ]"#),
+    );
+}
+
+#[test]
+fn test_batch() {
+    assert_reply(
+        r#"[
+            {"jsonrpc": "2.0", "method": "sum", "params": [1,2,4], "id": "1"},
+            {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]},
+            {"jsonrpc": "2.0", "method": "subtract", "params": [42,23], "id": "2"},
+            {"foo": "boo"},
+            {"jsonrpc": "2.0", "method": "foo.get", "params": {"name": "myself"}, "id": "5"},
+            {"jsonrpc": "2.0", "method": "get_data", "id": "9"}
+        ]"#,
+        Some(r#"[
+            {"jsonrpc": "2.0", "result": 7, "id": "1"},
+            {"jsonrpc": "2.0", "result": 19, "id": "2"},
+            {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null},
+            {"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "5"},
+            {"jsonrpc": "2.0", "result": ["hello", 5], "id": "9"}
+        ]"#),
+    );
+}
+
+#[test]
+fn test_batch_notifications() {
+    assert_reply(
+        r#"[
+            {"jsonrpc": "2.0", "method": "notify_sum", "params": [1,2,4]},
+            {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]}
+        ]"#,
+        None,
+    );
+}
+
+#[test]
+fn test_single_request() {
+    // a single Request is not wrapped in an Array
+    assert_reply(
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+        Some(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#),
+    );
+    assert_reply(r#"{"jsonrpc": "2.0", "method": "notify_hello", "params": [7]}"#, None);
+    assert_reply(
+        "1",
+        Some(r#"{"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}"#),
+    );
+}
@@ -0,0 +1,279 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+
+use jrpc::*;
+
+#[test]
+fn test_build_request() {
+    let request = Request::builder()
+        .method("subtract")
+        .positional_params([42, 23])
+        .id(1)
+        .build()
+        .unwrap();
+    assert_eq!(
+        request.to_string(),
+        r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#
+    );
+
+    let request = Request::builder()
+        .id("a")
+        .method("subtract")
+        .named_params(vec![("minuend", 42), ("subtrahend", 23)])
+        .build()
+        .unwrap();
+    testing::assert_json_eq(
+        &request.to_string(),
+        r#"{"jsonrpc": "2.0", "method": "subtract",
+            "params": {"minuend": 42, "subtrahend": 23}, "id": "a"}"#,
+    );
+
+    // a call without params
+    let request = Request::builder().method("get_data").id(Id::Null).build().unwrap();
+    assert_eq!(request.to_string(), r#"{"jsonrpc":"2.0","method":"get_data","id":null}"#);
+
+    let mut params = BTreeMap::new();
+    params.insert("values", vec![1, 2, 3]);
+    let request = Request::builder().method("update").params(params).notification().build();
+    assert_eq!(
+        request.unwrap().to_string(),
+        r#"{"jsonrpc":"2.0","method":"update","params":{"values":[1,2,3]}}"#
+    );
+
+    let request = Request::builder()
+        .method("rpc.discover")
+        .allow_reserved()
+        .id(2)
+        .build()
+        .unwrap();
+    assert_eq!(request.method, "rpc.discover");
+}
+
+#[test]
+fn test_build_request_errors() {
+    let error = Request::builder().id(1).build().unwrap_err();
+    assert_eq!(error, BuildError::MissingMethod);
+    assert_eq!(error.to_string(), "the Request has no method");
+
+    let error = Request::builder().method("rpc.discover").id(1).build().unwrap_err();
+    assert_eq!(error, BuildError::ReservedMethod("rpc.discover".into()));
+
+    let error = Request::builder().method("subtract").build().unwrap_err();
+    assert_eq!(error, BuildError::MissingId);
+
+    let error = Request::builder().method("subtract").id(1).notification().build();
+    assert_eq!(error.unwrap_err(), BuildError::IdAndNotification);
+
+    let cases = vec![
+        (json!(null), "null"),
+        (json!(true), "a boolean"),
+        (json!(4), "a number"),
+        (json!("4"), "a string"),
+    ];
+    for (params, got) in cases {
+        let error = Request::builder().method("subtract").params(params).id(1).build();
+        let error = error.unwrap_err();
+        assert_eq!(error, BuildError::ParamsNotStructured(got));
+        assert_eq!(error.to_string(), format!("params must be an array or an object, got {}", got));
+    }
+
+    // keys which are not strings can't be serialized
+    let mut params = BTreeMap::new();
+    params.insert(vec![1], 2);
+    let error = Request::builder().method("subtract").params(params).id(1).build();
+    match error.unwrap_err() {
+        BuildError::Params(message) => assert_eq!(message, "key must be a string"),
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn test_build_request_unchecked() {
+    let request = Request::builder().method("rpc.foo").params(4).build_unchecked();
+    assert_eq!(request.to_string(), r#"{"jsonrpc":"2.0","method":"rpc.foo","params":4}"#);
+
+    let request = Request::builder().id(1).notification().build_unchecked();
+    assert_eq!(request.to_string(), r#"{"jsonrpc":"2.0","method":"","id":1}"#);
+}
+
+#[test]
+fn test_build_response() {
+    let response = Response::builder().id(1).result(vec!["a", "b"]).build().unwrap();
+    assert_eq!(response.to_string(), r#"{"jsonrpc":"2.0","result":["a","b"],"id":1}"#);
+
+    let response = Response::builder()
+        .error(ErrorCode::InvalidParams, "minuend must be a number")
+        .id("a")
+        .build()
+        .unwrap();
+    testing::assert_json_eq(
+        &response.to_string(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32602, "message": "minuend must be a number",
+            "data": null}, "id": "a"}"#,
+    );
+
+    // codes outside of the reserved range are the application's
+    let response = Response::builder()
+        .id(Id::Null)
+        .error(42, "application error")
+        .data(json!({"field": "minuend"}))
+        .build()
+        .unwrap();
+    match response {
+        Response::Err(error) => {
+            assert_eq!(error.error.code, ErrorCode::ServerError(42));
+            assert_eq!(error.error.data, Some(json!({"field": "minuend"})));
+        }
+        Response::Ok(_) => panic!("expected an error"),
+    }
+}
+
+#[test]
+fn test_build_response_errors() {
+    let error = Response::builder().result(1).build().unwrap_err();
+    assert_eq!(error, BuildError::MissingId);
+
+    let error = Response::builder().id(1).result(1).error(ErrorCode::InternalError, "oops");
+    let error = error.build().unwrap_err();
+    assert_eq!(error, BuildError::ResultAndError);
+    assert_eq!(error.to_string(), "a Response has either a result or an error, not both");
+
+    let error = Response::builder().id(1).build().unwrap_err();
+    assert_eq!(error, BuildError::NoResultOrError);
+    let error = Response::builder().id(1).data(1).build().unwrap_err();
+    assert_eq!(error, BuildError::NoResultOrError);
+
+    let error = Response::builder().id(1).result(1).data("why").build().unwrap_err();
+    assert_eq!(error, BuildError::DataWithoutError);
+
+    let error = Response::builder().id(1).error(-32100, "reserved").build().unwrap_err();
+    assert_eq!(error, BuildError::UndefinedReservedCode(-32100));
+    assert_eq!(error.to_string(), "the error code -32100 is reserved but not defined");
+
+    let mut data = BTreeMap::new();
+    data.insert(vec![1], 2);
+    let error = Response::builder().id(1).error(-32000, "bad").data(data).build();
+    assert_eq!(error.unwrap_err(), BuildError::Data("key must be a string".into()));
+}
+
+#[test]
+fn test_build_error_object() {
+    // the message defaults to the name of the error
+    let cases = vec![
+        (ErrorCode::ParseError, "Parse error"),
+        (ErrorCode::InvalidRequest, "Invalid Request"),
+        (ErrorCode::MethodNotFound, "Method not found"),
+        (ErrorCode::InvalidParams, "Invalid params"),
+        (ErrorCode::InternalError, "Internal error"),
+        (ErrorCode::ServerError(-32001), "Server error"),
+    ];
+    for (code, message) in cases {
+        let error = ErrorObject::builder(code).build().unwrap();
+        assert_eq!(error.code, code);
+        assert_eq!(error.message, message);
+        assert_eq!(error.data, None);
+    }
+    let error = ErrorObject::builder(-32000).message("busy").data([1, 2]).build().unwrap();
+    assert_eq!(error.message, "busy");
+    assert_eq!(error.data, Some(json!([1, 2])));
+}
+
+#[test]
+fn test_build_error_object_details() {
+    let error = ErrorObject::builder(ErrorCode::InvalidParams)
+        .detail("field", "minuend")
+        .detail("hint", "a number")
+        .detail("field", "subtrahend")
+        .build()
+        .unwrap();
+    assert_eq!(error.data, Some(json!({"field": "subtrahend", "hint": "a number"})));
+    // the members are sorted by key, unless `preserve_order` is enabled
+    let error = ErrorObject::builder(ErrorCode::InvalidParams)
+        .detail("hint", "a number")
+        .detail("field", "minuend")
+        .build()
+        .unwrap();
+    #[cfg(not(feature = "preserve_order"))]
+    assert_eq!(error.data.unwrap().to_string(), r#"{"field":"minuend","hint":"a number"}"#);
+    #[cfg(feature = "preserve_order")]
+    assert_eq!(error.data.unwrap().to_string(), r#"{"hint":"a number","field":"minuend"}"#);
+
+    // details are added to the data, data replaces the details
+    let error = ErrorObject::builder(ErrorCode::InvalidParams)
+        .data(json!({"field": "minuend"}))
+        .detail("hint", "a number")
+        .build()
+        .unwrap();
+    assert_eq!(error.data, Some(json!({"field": "minuend", "hint": "a number"})));
+    let error = ErrorObject::builder(ErrorCode::InvalidParams)
+        .detail("hint", "a number")
+        .data("minuend")
+        .build()
+        .unwrap();
+    assert_eq!(error.data, Some(json!("minuend")));
+
+    let error = ErrorObject::builder(ErrorCode::InvalidParams).data(4).detail("hint", "x").build();
+    assert_eq!(error.unwrap_err(), BuildError::DetailWithoutObject);
+
+    let mut value = BTreeMap::new();
+    value.insert(vec![1], 2);
+    let error = ErrorObject::builder(ErrorCode::InvalidParams).detail("x", value).build();
+    assert_eq!(error.unwrap_err(), BuildError::Data("key must be a string".into()));
+}
+
+/// An error caused by another one.
+#[derive(Debug)]
+struct Failed(serde_json::Error);
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to load the config")
+    }
+}
+
+impl error::Error for Failed {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn test_build_error_object_cause() {
+    let err = Failed(serde_json::from_str::<Vec<u32>>("[1, -2]").unwrap_err());
+    let error = ErrorObject::builder(ErrorCode::InternalError).cause(&err).build().unwrap();
+    assert_eq!(
+        error.data,
+        Some(json!({"causes": [
+            "failed to load the config",
+            "invalid value: integer `-2`, expected u32 at line 1 column 6",
+        ]}))
+    );
+}
+
+#[test]
+fn test_build_error_object_codes() {
+    for &code in &[-32000, -32099, -32700, -32600] {
+        assert!(ErrorObject::builder(code).build().is_ok());
+    }
+    for &code in &[-31999, 1, -40000] {
+        let error = ErrorObject::builder(code).build().unwrap_err();
+        assert_eq!(error, BuildError::ApplicationCode(code));
+        let error = ErrorObject::builder(code).application_code().build().unwrap();
+        assert_eq!(error.code, ErrorCode::ServerError(code));
+    }
+    for &code in &[-32100, -32768, -32604] {
+        let error = ErrorObject::builder(code).application_code().build().unwrap_err();
+        assert_eq!(error, BuildError::UndefinedReservedCode(code));
+    }
+    let error = ErrorObject::builder(42).build().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "the error code 42 is not a server error (-32099 to -32000), use application_code to \
+         allow it"
+    );
+}
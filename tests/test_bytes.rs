@@ -0,0 +1,72 @@
+#![cfg(feature = "bytes")]
+extern crate bytes;
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+
+use bytes::{Bytes, BytesMut};
+use jrpc::*;
+
+#[test]
+fn test_to_bytes() {
+    let request = Request::with_params(Id::from(1), "subtract".to_string(), json!([42, 23]));
+    let success = Success::new(Id::from("a"), 19);
+    let error: Error<Value> = Error::new(Id::Null, ErrorCode::ParseError, "bad json", None);
+    let response: Response<i64> = Response::success(Id::from(2), 7);
+
+    let mut buf = BytesMut::new();
+    request.to_bytes(&mut buf).unwrap();
+    assert_eq!(&buf[..], request.to_string().as_bytes());
+
+    // the buffer is appended to
+    let mut buf = BytesMut::from(&b"prefix\n"[..]);
+    success.to_bytes(&mut buf).unwrap();
+    assert_eq!(&buf[..], format!("prefix\n{}", success.to_string()).as_bytes());
+    error.to_bytes(&mut buf).unwrap();
+    response.to_bytes(&mut buf).unwrap();
+    let expected = format!(
+        "prefix\n{}{}{}",
+        success.to_string(),
+        error.to_string(),
+        response.to_string()
+    );
+    assert_eq!(&buf[..], expected.as_bytes());
+}
+
+#[test]
+fn test_to_bytes_fails() {
+    // keys which are not strings can't be serialized
+    let mut params = BTreeMap::new();
+    params.insert(vec![1], 2);
+    let request = Request::with_params(Id::from(1), "subtract".to_string(), params);
+    let mut buf = BytesMut::from(&b"prefix"[..]);
+    assert!(request.to_bytes(&mut buf).is_err());
+    assert_eq!(&buf[..], b"prefix");
+}
+
+#[test]
+fn test_parse_request_bytes() {
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let request = parse_request_bytes::<String>(&Bytes::from(json)).unwrap();
+    assert_eq!(request.method, "subtract");
+    assert_eq!(request.params, Some(json!([42, 23])));
+
+    let error = parse_request_bytes::<String>(&Bytes::from_static(b"{\"a\": \"\xff\"}"));
+    let error = error.unwrap_err();
+    assert_eq!(error.id, Id::Null);
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(
+        error.error.message,
+        "request is not UTF-8: invalid utf-8 sequence of 1 bytes from index 7"
+    );
+
+    // the same errors as parse_request
+    for json in &["{", "[]", r#"{"jsonrpc": "2.0", "method": 1, "id": 1}"#] {
+        let error = parse_request_bytes::<String>(&Bytes::from(*json)).unwrap_err();
+        let expected = parse_request::<String>(json).unwrap_err();
+        assert_eq!(error.error.code, expected.error.code);
+        assert_eq!(error.error.message, expected.error.message);
+    }
+}
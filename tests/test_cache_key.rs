@@ -0,0 +1,112 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+fn parse(json: &str) -> Request<String, Value> {
+    parse_request(json).unwrap()
+}
+
+#[test]
+fn test_cache_key_ignores_id() {
+    let keys: Vec<u64> = [
+        r#"{"jsonrpc": "2.0", "method": "get", "params": [1, 2], "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": [1, 2], "id": "a"}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": [1, 2], "id": null}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": [1, 2]}"#,
+    ]
+    .iter()
+    .map(|json| parse(json).cache_key())
+    .collect();
+    assert!(keys.iter().all(|&key| key == keys[0]));
+}
+
+#[test]
+fn test_cache_key_ignores_member_order() {
+    let a = parse(
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": {"c": [3], "d": 4}}}"#,
+    );
+    let b = parse(
+        r#"{"params": {"b": {"d": 4, "c": [3]}, "a": 1}, "method": "get", "jsonrpc": "2.0"}"#,
+    );
+    assert_eq!(a.cache_key(), b.cache_key());
+
+    let mut map = serde_json::Map::new();
+    map.insert("b".into(), Value::from(2));
+    map.insert("a".into(), Value::from(1));
+    let c = Request::with_params(Id::from(9), "get".to_string(), Value::Object(map));
+    let d = parse(r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 2}}"#);
+    assert_eq!(c.cache_key(), d.cache_key());
+}
+
+#[test]
+fn test_cache_key_differs() {
+    let keys = [
+        parse(r#"{"jsonrpc": "2.0", "method": "get", "params": [1, 2]}"#).cache_key(),
+        parse(r#"{"jsonrpc": "2.0", "method": "set", "params": [1, 2]}"#).cache_key(),
+        parse(r#"{"jsonrpc": "2.0", "method": "get", "params": [2, 1]}"#).cache_key(),
+        parse(r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 2}}"#).cache_key(),
+        parse(r#"{"jsonrpc": "2.0", "method": "get"}"#).cache_key(),
+        parse(r#"{"jsonrpc": "2.0", "method": "get", "params": []}"#).cache_key(),
+    ];
+    for (i, a) in keys.iter().enumerate() {
+        for b in &keys[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_cache_key_stable() {
+    // FNV-1a of `"get",[1,{"a":null,"b":2}]`
+    let request =
+        parse(r#"{"jsonrpc": "2.0", "method": "get", "params": [1, {"b": 2, "a": null}]}"#);
+    assert_eq!(request.cache_key(), 0x0a1b_b237_1f8b_b4e4);
+}
+
+#[test]
+fn test_dedup_by_content() {
+    let mut requests: Vec<_> = [
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 2}, "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 3}, "id": 2}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"b": 2, "a": 1}, "id": 3}"#,
+        r#"{"jsonrpc": "2.0", "method": "put", "params": {"a": 1, "b": 2}, "id": 4}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "id": 5}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"a": 1, "b": 2}}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "id": 6}"#,
+    ]
+    .iter()
+    .map(|json| parse(json))
+    .collect();
+    assert_eq!(requests[0].content_hash(), requests[2].content_hash());
+    assert_ne!(requests[0].content_hash(), requests[1].content_hash());
+
+    dedup_by_content(&mut requests);
+    let ids: Vec<_> = requests.iter().map(|request| request.id.clone()).collect();
+    assert_eq!(
+        ids,
+        vec![IdReq::Int(1), IdReq::Int(2), IdReq::Int(4), IdReq::Int(5)]
+    );
+}
+
+#[test]
+fn test_request_hash() {
+    use std::collections::HashSet;
+
+    let request = |id: i64, params: Vec<i64>| {
+        Request::with_params(Id::from(id), "sum".to_string(), params)
+    };
+    let mut set = HashSet::new();
+    assert!(set.insert(hash(&request(1, vec![1, 2]))));
+    assert!(set.insert(hash(&request(2, vec![1, 2]))));
+    assert!(set.insert(hash(&request(1, vec![1, 3]))));
+    assert!(!set.insert(hash(&request(1, vec![1, 2]))));
+}
+
+fn hash<H: std::hash::Hash>(value: &H) -> u64 {
+    use std::hash::Hasher;
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,115 @@
+//! Cancellation of pending Requests with `$/cancelRequest`.
+extern crate jrpc;
+extern crate serde_json;
+
+use std::sync::Mutex;
+
+use jrpc::cancel::{self, CancelParams, CancellationSet};
+use jrpc::router::{Middleware, Router};
+use jrpc::*;
+
+/// Reply with LSP's `RequestCancelled` to the Requests cancelled before their handler ran.
+struct HonorCancellation;
+
+impl Middleware<Mutex<CancellationSet>> for HonorCancellation {
+    fn on_request(
+        &self,
+        cancelled: &Mutex<CancellationSet>,
+        request: &Request<String, Value>,
+    ) -> Result<(), ErrorObject<Value>> {
+        let id = match request.id.as_id() {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        if cancelled.lock().unwrap().take(&id) {
+            return Err(ErrorObject {
+                code: ErrorCode::from(-32800),
+                message: "the request was cancelled".into(),
+                data: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_cancel_before_handler() {
+    let mut router = Router::new().with_middleware(HonorCancellation);
+    router
+        .register_notification(
+            cancel::METHOD,
+            |cancelled: &Mutex<CancellationSet>, p: CancelParams| {
+                cancelled.lock().unwrap().cancel(p.id);
+            },
+        )
+        .register("slow", |_: &Mutex<CancellationSet>, (): ()| Ok("done"));
+    let cancelled = Mutex::new(CancellationSet::new());
+
+    let cancel_4 = serde_json::to_string(&cancel::notification(Id::from(4))).unwrap();
+    assert_eq!(router.handle(&cancelled, &cancel_4), None);
+    assert!(cancelled.lock().unwrap().is_cancelled(&Id::from(4)));
+
+    let reply = router.handle(
+        &cancelled,
+        r#"{"jsonrpc": "2.0", "method": "slow", "id": 4}"#,
+    );
+    jrpc::testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{
+            "jsonrpc": "2.0",
+            "error": {"code": -32800, "message": "the request was cancelled", "data": null},
+            "id": 4
+        }"#,
+    );
+    assert!(
+        cancelled.lock().unwrap().is_empty(),
+        "the cancellation was consumed"
+    );
+
+    // Other Requests are not affected.
+    let reply = router.handle(
+        &cancelled,
+        r#"{"jsonrpc": "2.0", "method": "slow", "id": 5}"#,
+    );
+    jrpc::testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "result": "done", "id": 5}"#,
+    );
+}
+
+#[test]
+fn test_cancel_decode() {
+    let json = r#"{"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": "abc"}}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert_eq!(
+        cancel::decode(&request, cancel::METHOD).unwrap().unwrap(),
+        Id::from("abc")
+    );
+    assert!(cancel::decode(&request, "cancel").is_none());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"ids": [1]}}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert!(cancel::decode(&request, cancel::METHOD).unwrap().is_err());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "$/cancelRequest", "params": {"id": 1}, "id": 2}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert!(
+        cancel::decode(&request, cancel::METHOD).is_none(),
+        "not a Notification"
+    );
+}
+
+#[test]
+fn test_cancellation_set() {
+    let mut set = CancellationSet::new();
+    assert!(set.cancel(Id::from(1)));
+    assert!(!set.cancel(Id::from(1)), "already cancelled");
+    assert!(set.cancel(Id::from("1")));
+    assert_eq!(set.len(), 2);
+
+    assert!(!set.take(&Id::from(2)));
+    assert!(set.take(&Id::from(1)));
+    assert!(!set.take(&Id::from(1)));
+    set.clear();
+    assert!(set.is_empty());
+}
@@ -0,0 +1,82 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Method {
+    CreateFooBar,
+    Get,
+    HTTPGet,
+    V2Update,
+}
+
+const METHODS: &[Method] = &[Method::CreateFooBar, Method::Get, Method::HTTPGet, Method::V2Update];
+
+#[test]
+fn test_conventions() {
+    assert_eq!(SnakeCase::from_variant("CreateFooBar"), "create_foo_bar");
+    assert_eq!(SnakeCase::to_variant("create_foo_bar"), "CreateFooBar");
+    assert_eq!(SnakeCase::from_variant("HTTPGet"), "h_t_t_p_get");
+    assert_eq!(SnakeCase::from_variant("V2Update"), "v2_update");
+    assert_eq!(CamelCase::from_variant("CreateFooBar"), "createFooBar");
+    assert_eq!(CamelCase::to_variant("createFooBar"), "CreateFooBar");
+    assert_eq!(CamelCase::from_variant(""), "");
+}
+
+#[test]
+fn test_round_trip() {
+    for &method in METHODS {
+        let snake = serde_json::to_string(&Cased::<_, SnakeCase>::new(method)).unwrap();
+        let cased: Cased<Method, SnakeCase> = serde_json::from_str(&snake).unwrap();
+        assert_eq!(cased.into_inner(), method);
+
+        let camel = serde_json::to_string(&Cased::<_, CamelCase>::new(method)).unwrap();
+        let cased: Cased<Method, CamelCase> = serde_json::from_str(&camel).unwrap();
+        assert_eq!(cased.into_inner(), method);
+    }
+    let json = serde_json::to_string(&Cased::<_, CamelCase>::from(Method::CreateFooBar)).unwrap();
+    assert_eq!(json, r#""createFooBar""#);
+}
+
+#[test]
+fn test_parse_request() {
+    for &method in METHODS {
+        let request = Request::with_params(
+            Id::from(1),
+            Cased::<_, SnakeCase>::new(method),
+            Value::from(vec![1, 2]),
+        );
+        let json = request.to_string();
+        let request = parse_request::<Cased<Method, SnakeCase>>(&json).unwrap();
+        assert_eq!(*request.method, method);
+        assert_eq!(request.params, Some(Value::from(vec![1, 2])));
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "method": "create_foo_bar", "id": 1}"#;
+    let request = parse_request::<Cased<Method>>(json).unwrap();
+    assert_eq!(*request.method, Method::CreateFooBar);
+}
+
+#[test]
+fn test_parse_request_wrong_case() {
+    let cases = vec![
+        ("CreateFooBar", "method `CreateFooBar` is not snake_case"),
+        ("createFooBar", "method `createFooBar` is not snake_case"),
+        ("create_foo_baz", "unknown method `create_foo_baz`"),
+        ("", "unknown method ``"),
+    ];
+    for (method, message) in cases {
+        let json = format!(r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#, method);
+        let error = parse_request::<Cased<Method, SnakeCase>>(&json).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+        assert_eq!(error.id, Id::from(1));
+        assert!(error.error.message.starts_with(message), "{}", error.error.message);
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "method": "create_foo_bar", "id": 1}"#;
+    let error = parse_request::<Cased<Method, CamelCase>>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+}
@@ -0,0 +1,536 @@
+//! The Client side state: correlating Responses with pending Requests.
+extern crate jrpc;
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+use jrpc::client::{
+    self, BatchBuilder, BatchError, ClientError, ClientState, IdGenerator, InboundEvent,
+    NotificationBatch, PendingRequests, ResponseSet,
+};
+use jrpc::*;
+
+#[test]
+fn test_pending_duplicate_id() {
+    let mut pending = PendingRequests::new();
+    assert_eq!(pending.register(Id::from(1), "first"), Ok(()));
+    assert_eq!(pending.register(Id::from(1), "second"), Err("second"));
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.complete(&Id::from(1)), Some("first"));
+
+    // The id can be reused once completed.
+    assert_eq!(pending.register(Id::from(1), "third"), Ok(()));
+}
+
+#[test]
+fn test_pending_unknown_id() {
+    let mut pending = PendingRequests::new();
+    pending.register(Id::from(1), ()).unwrap();
+    assert_eq!(pending.complete(&Id::from(2)), None);
+    assert_eq!(pending.complete(&Id::Null), None);
+    assert_eq!(pending.complete(&Id::from(1)), Some(()));
+    assert_eq!(pending.complete(&Id::from(1)), None, "already completed");
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn test_pending_string_int_distinct() {
+    let mut pending = PendingRequests::new();
+    pending.register(Id::from(1), "int").unwrap();
+    pending.register(Id::from("1".to_string()), "string").unwrap();
+    assert_eq!(pending.len(), 2);
+    assert!(pending.contains(&Id::from(1)));
+
+    assert_eq!(pending.complete(&Id::from("1".to_string())), Some("string"));
+    assert!(pending.contains(&Id::from(1)));
+    assert_eq!(pending.complete(&Id::from(1)), Some("int"));
+}
+
+#[test]
+fn test_pending_drain_on_disconnect() {
+    let mut pending = PendingRequests::new();
+    for i in 0..5 {
+        pending.register(Id::from(i), i * 10).unwrap();
+    }
+    pending.complete(&Id::from(3)).unwrap();
+
+    let mut outstanding: Vec<_> = pending.iter().map(|(id, meta)| (id.clone(), *meta)).collect();
+    outstanding.sort_by_key(|&(_, meta)| meta);
+    let mut drained: Vec<_> = pending.drain().collect();
+    drained.sort_by_key(|&(_, meta)| meta);
+    assert_eq!(drained, outstanding);
+    assert_eq!(
+        drained,
+        vec![(Id::from(0), 0), (Id::from(1), 10), (Id::from(2), 20), (Id::from(4), 40)],
+    );
+
+    assert!(pending.is_empty());
+    assert_eq!(pending.complete(&Id::from(1)), None, "a late Response matches nothing");
+}
+
+#[test]
+fn test_client_call_cycle() {
+    let mut client = ClientState::new();
+    let (first, json) = client.start_call("subtract", Some((42, 23)), "subtract").unwrap();
+    jrpc::testing::assert_json_eq(
+        &json,
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+    );
+    let (second, _) = client.start_call("get_data", None::<()>, "get_data").unwrap();
+    assert_eq!(second, Id::from(2));
+    assert_eq!(client.pending().len(), 2);
+
+    // The Responses arrive out of order.
+    match client.on_message(r#"{"jsonrpc": "2.0", "result": ["hello", 5], "id": 2}"#) {
+        InboundEvent::Response { meta, response } => {
+            assert_eq!(meta, "get_data");
+            assert_eq!(response.id(), &second);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    let reply = r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "nope"}, "id": 1}"#;
+    match client.on_message(reply) {
+        InboundEvent::Response {
+            meta,
+            response: Response::Err(error),
+        } => {
+            assert_eq!(meta, "subtract");
+            assert_eq!(error.id, first);
+            assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    assert!(client.pending().is_empty());
+
+    // A duplicated Response no longer matches anything.
+    match client.on_message(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#) {
+        InboundEvent::Unmatched(response) => assert_eq!(response.id(), &first),
+        event => panic!("unexpected {:?}", event),
+    }
+}
+
+#[test]
+fn test_client_unsolicited_notification() {
+    let mut client: ClientState<()> = ClientState::new();
+    client.start_call("subscribe", Some(["news"]), ()).unwrap();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "news", "params": {"title": "hello"}}"#;
+    match client.on_message(json) {
+        InboundEvent::Request(request) => {
+            assert_eq!(request.method, "news");
+            assert_eq!(request.id, IdReq::Notification);
+            assert_eq!(request.params, Some(serde_json::json!({"title": "hello"})));
+        }
+        event => panic!("unexpected {:?}", event),
+    }
+    assert_eq!(client.pending().len(), 1, "the pending call is untouched");
+}
+
+#[test]
+fn test_client_invalid_messages() {
+    let mut client: ClientState<()> = ClientState::new();
+    for json in &["{", "[]", r#"{"jsonrpc": "2.0", "id": 1}"#, r#"{"jsonrpc": "2.0", "method": 1}"#] {
+        match client.on_message(json) {
+            InboundEvent::Invalid(_) => {}
+            event => panic!("unexpected {:?} for {}", event, json),
+        }
+    }
+}
+
+#[test]
+fn test_client_skips_pending_ids() {
+    let mut client = ClientState::with_ids(IdGenerator::starting_at(i64::MAX));
+    let (max, _) = client.start_call("a", None::<()>, 0).unwrap();
+    assert_eq!(max, Id::from(i64::MAX));
+    let (min, _) = client.start_call("b", None::<()>, 1).unwrap();
+    assert_eq!(min, Id::from(i64::MIN));
+
+    client.pending_mut().register(Id::from(i64::MIN + 1), 2).unwrap();
+    let (id, _) = client.start_call("c", None::<()>, 3).unwrap();
+    assert_eq!(id, Id::from(i64::MIN + 2));
+}
+
+#[test]
+fn test_complete_typed_success() {
+    let mut client = ClientState::new();
+    client.start_call("get_data", None::<()>, "get_data").unwrap();
+    let reply = r#"{"jsonrpc": "2.0", "result": ["hello", 5], "id": 1}"#;
+    let (meta, result) = client.complete_typed::<(String, u32)>(reply).unwrap();
+    assert_eq!(meta, "get_data");
+    assert_eq!(result.unwrap(), ("hello".to_string(), 5));
+    assert!(client.pending().is_empty());
+}
+
+#[test]
+fn test_complete_typed_server_error() {
+    let mut client = ClientState::new();
+    client.start_call("foobar", None::<()>, "foobar").unwrap();
+    let reply = r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "nope"}, "id": 1}"#;
+    let (meta, result) = client.complete_typed::<i64>(reply).unwrap();
+    assert_eq!(meta, "foobar");
+    let error = result.unwrap_err();
+    assert_eq!(error.id, Id::from(1));
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.error.message, "nope");
+}
+
+#[test]
+fn test_complete_typed_unknown_id() {
+    let mut client = ClientState::new();
+    client.start_call("subtract", Some((42, 23)), ()).unwrap();
+    let reply = r#"{"jsonrpc": "2.0", "result": 19, "id": "1"}"#;
+    match client.complete_typed::<i64>(reply) {
+        Err(ClientError::UnknownId(id)) => assert_eq!(id, Id::from("1".to_string())),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(client.pending().len(), 1, "the pending call is untouched");
+
+    match client.complete_typed::<i64>(r#"{"jsonrpc": "2.0", "id": 1}"#) {
+        Err(ClientError::InvalidResponse(_)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_complete_typed_wrong_shape() {
+    let mut client = ClientState::new();
+    client.start_call("subtract", Some((42, 23)), "subtract").unwrap();
+    let reply = r#"{"jsonrpc": "2.0", "result": {"value": 19}, "id": 1}"#;
+    let err = client.complete_typed::<i64>(reply).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the result has the wrong type: invalid type: map, expected i64",
+    );
+    match err {
+        ClientError::ResultTypeMismatch { meta, result, .. } => {
+            assert_eq!(meta, "subtract");
+            assert_eq!(result, serde_json::json!({"value": 19}));
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(client.pending().is_empty());
+}
+
+#[test]
+fn test_request_re_id() {
+    let ids = vec![
+        IdReq::Int(1),
+        IdReq::String("a".into()),
+        IdReq::Null,
+        IdReq::Notification,
+    ];
+    let mut generator = IdGenerator::starting_at(7);
+    for (i, old) in ids.into_iter().enumerate() {
+        let mut request = Request::with_params(old.clone(), "subtract".to_string(), [42, 23]);
+        assert_eq!(request.re_id_with(&mut generator), old);
+        assert_eq!(request.id, IdReq::Int(7 + i as i64));
+        assert_eq!(request.method, "subtract");
+        assert_eq!(request.params, Some([42, 23]));
+
+        let request = request.re_id(old.clone());
+        assert_eq!(request.id, old);
+        assert_eq!(request.method, "subtract");
+        assert_eq!(request.params, Some([42, 23]));
+    }
+}
+
+#[test]
+fn test_encode_call() {
+    let mut ids = IdGenerator::starting_at(3);
+    let (id, json) = client::encode_call(&mut ids, "subtract", Some([42, 23])).unwrap();
+    assert_eq!(id, Id::from(3));
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":3}"#);
+
+    let mut params = std::collections::BTreeMap::new();
+    params.insert("minuend", 42);
+    params.insert("subtrahend", 23);
+    let (id, json) = client::encode_call(&mut ids, "subtract", Some(params)).unwrap();
+    assert_eq!(id, Id::from(4));
+    assert_eq!(
+        json,
+        r#"{"jsonrpc":"2.0","method":"subtract","params":{"minuend":42,"subtrahend":23},"id":4}"#
+    );
+
+    let (id, json) = client::encode_call(&mut ids, "get_data", None::<()>).unwrap();
+    assert_eq!(id, Id::from(5));
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"get_data","id":5}"#);
+
+    // the Server reads what was encoded
+    let request = parse_request::<String>(&json).unwrap();
+    assert_eq!(request.id.into_id(), Some(id));
+
+    let mut key = std::collections::BTreeMap::new();
+    key.insert(vec![1], 2);
+    assert!(client::encode_call(&mut ids, "subtract", Some(key)).is_err());
+}
+
+#[test]
+fn test_encode_notification() {
+    let json = client::encode_notification("update", Some([1, 2, 3])).unwrap();
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"update","params":[1,2,3]}"#);
+    let json = client::encode_notification("heartbeat", None::<()>).unwrap();
+    assert_eq!(json, r#"{"jsonrpc":"2.0","method":"heartbeat"}"#);
+    assert!(parse_request::<String>(&json).unwrap().id.is_notification());
+}
+
+#[test]
+fn test_request_strip_id() {
+    let request = Request::with_params(Id::from(1), "update".to_string(), [1, 2]).strip_id();
+    assert_eq!(request.id, IdReq::Notification);
+    assert_eq!(request.params, Some([1, 2]));
+    assert_eq!(
+        request.to_string(),
+        r#"{"jsonrpc":"2.0","method":"update","params":[1,2]}"#
+    );
+}
+
+#[test]
+fn test_batch_builder_ids() {
+    let mut batch = BatchBuilder::with_ids(IdGenerator::starting_at(5));
+    let mut ids = Vec::new();
+    for i in 0..4 {
+        ids.push(batch.call("sum".to_string(), Some([i])));
+        batch.notify("progress".to_string(), Some([i]));
+    }
+    assert_eq!(ids, vec![Id::from(5), Id::from(6), Id::from(7), Id::from(8)]);
+    assert_eq!(batch.len(), 8);
+
+    let requests = batch.build().unwrap();
+    for (i, pair) in requests.chunks(2).enumerate() {
+        assert_eq!(pair[0].id, IdReq::from(ids[i].clone()));
+        assert_eq!(pair[0].method, "sum");
+        assert_eq!(pair[1].id, IdReq::Notification);
+        assert_eq!(pair[1].params, Some(Value::from(vec![i])));
+    }
+}
+
+#[test]
+fn test_batch_builder_string() {
+    let mut batch = BatchBuilder::new();
+    batch.notify("first", None::<()>);
+    batch.call("second", Some(serde_json::json!({"a": 1})));
+    batch.call("third", None::<()>);
+    testing::assert_json_eq(
+        &batch.build_string().unwrap(),
+        r#"[
+            {"jsonrpc": "2.0", "method": "first"},
+            {"jsonrpc": "2.0", "method": "second", "params": {"a": 1}, "id": 1},
+            {"jsonrpc": "2.0", "method": "third", "id": 2}
+        ]"#,
+    );
+}
+
+#[test]
+fn test_batch_builder_limits() {
+    let batch: BatchBuilder<String> = BatchBuilder::new();
+    assert_eq!(batch.build().unwrap_err(), BatchError::Empty);
+
+    let mut batch = BatchBuilder::new().max_len(2);
+    batch.call("a", None::<()>);
+    batch.call("b", None::<()>);
+    assert_eq!(batch.build().unwrap().len(), 2);
+
+    let mut batch = BatchBuilder::new().max_len(2);
+    batch.call("a", None::<()>);
+    batch.call("b", None::<()>);
+    batch.notify("c", None::<()>);
+    let err = batch.build_string().unwrap_err();
+    assert_eq!(err, BatchError::TooLong { len: 3, max: 2 });
+    assert_eq!(
+        err.to_string(),
+        "the batch has 3 requests, more than the maximum of 2"
+    );
+}
+
+#[test]
+fn test_notification_batch() {
+    let mut batch = NotificationBatch::new();
+    assert!(batch.is_empty());
+    batch.notify("notify_sum".to_string(), Some(Value::from(vec![1, 2, 4])));
+    batch.notify("notify_hello".to_string(), None);
+    let request = Request::with_params(IdReq::Null, "sum".to_string(), Value::from(vec![1]));
+    assert_eq!(batch.push(request).unwrap_err().id, IdReq::Null);
+    batch
+        .push(Request::with_params(IdReq::Notification, "notify_bye".to_string(), Value::from(7)))
+        .unwrap();
+    assert_eq!(batch.len(), 3);
+
+    let json = serde_json::to_string(&batch).unwrap();
+    testing::assert_json_eq(
+        &json,
+        r#"[
+            {"jsonrpc": "2.0", "method": "notify_sum", "params": [1, 2, 4]},
+            {"jsonrpc": "2.0", "method": "notify_hello"},
+            {"jsonrpc": "2.0", "method": "notify_bye", "params": 7}
+        ]"#,
+    );
+
+    // the Server replies nothing
+    let mut handled = Vec::new();
+    let reply = router::handle_batch(
+        |value| {
+            let request = parse_request_value::<String>(value).unwrap();
+            handled.push(request.method);
+            request.id.into_id().map(|id| Response::success(id, Value::Null))
+        },
+        &json,
+    );
+    assert_eq!(reply, None);
+    assert_eq!(handled, ["notify_sum", "notify_hello", "notify_bye"]);
+
+    let requests: Vec<Request<String, Value>> = serde_json::from_str(&json).unwrap();
+    assert!(router::is_all_notifications(&requests));
+    let batch = NotificationBatch::try_from(requests).unwrap();
+    assert_eq!(batch.into_vec().len(), 3);
+
+    let requests: Vec<Request<String, ()>> = vec![
+        Request::new(IdReq::Notification, "notify_hello".to_string()),
+        Request::new(Id::from(1), "get_data".to_string()),
+    ];
+    assert!(!router::is_all_notifications(&requests));
+    let requests = NotificationBatch::try_from(requests).unwrap_err();
+    assert_eq!(requests.len(), 2);
+}
+
+/// A map with keys which are not strings, which can't be serialized as json.
+fn unserializable() -> std::collections::BTreeMap<(u8, u8), u8> {
+    let mut map = std::collections::BTreeMap::new();
+    map.insert((1, 2), 3);
+    map
+}
+
+#[test]
+fn test_batch_builder_serialize_errors() {
+    let mut batch = BatchBuilder::new();
+    batch.call("a", None::<()>);
+    batch.notify("b", Some(unserializable()));
+    batch.call("c", Some(unserializable()));
+    let err = batch.build().unwrap_err();
+    assert_eq!(
+        err,
+        BatchError::Serialize {
+            index: Some(1),
+            reason: "key must be a string".into(),
+        }
+    );
+    assert_eq!(err.to_string(), "request 1 of the batch failed to serialize: key must be a string");
+
+    let mut batch = BatchBuilder::new();
+    batch.call(unserializable(), None::<()>);
+    let err = batch.build_string().unwrap_err();
+    assert_eq!(err.to_string(), "the batch failed to serialize: key must be a string");
+}
+
+#[test]
+fn test_start_call_serialize_error() {
+    let mut client = ClientState::new();
+    let err = client.start_call("subtract", Some(unserializable()), ()).unwrap_err();
+    assert_eq!(err.to_string(), "key must be a string");
+    assert!(client.pending().is_empty());
+
+    let (id, _) = client.start_call("subtract", Some((42, 23)), ()).unwrap();
+    assert!(client.pending().contains(&id));
+}
+
+fn batch_responses(json: &str) -> Vec<Response<Value>> {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_response_set() {
+    let responses = batch_responses(
+        r#"[
+            {"jsonrpc": "2.0", "result": 7, "id": 1},
+            {"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null},
+            {"jsonrpc": "2.0", "error": {"code": -32601, "message": "Method not found"}, "id": "1"},
+            {"jsonrpc": "2.0", "error": {"code": -32600, "message": "Invalid Request"}, "id": null}
+        ]"#,
+    );
+    let mut responses = ResponseSet::new(responses).unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses.orphans().len(), 2);
+    assert!(responses
+        .orphans()
+        .iter()
+        .all(|response| *response.id() == Id::Null));
+
+    // the String "1" and the integer 1 are different ids
+    match responses.get(&Id::from("1")) {
+        Some(Response::Err(error)) => assert_eq!(error.error.code, ErrorCode::MethodNotFound),
+        response => panic!("unexpected {:?}", response),
+    }
+    assert!(responses.remove(&Id::from(1)).is_some());
+    assert!(responses.remove(&Id::from(1)).is_none());
+    assert!(responses.get(&Id::Null).is_none());
+    assert_eq!(responses.len(), 1);
+}
+
+#[test]
+fn test_response_set_duplicates() {
+    let json = r#"[
+        {"jsonrpc": "2.0", "result": 1, "id": 2},
+        {"jsonrpc": "2.0", "result": 2, "id": 3},
+        {"jsonrpc": "2.0", "error": {"code": -32700, "message": "Parse error"}, "id": null},
+        {"jsonrpc": "2.0", "result": 3, "id": 2},
+        {"jsonrpc": "2.0", "result": 4, "id": 2}
+    ]"#;
+    let err = ResponseSet::new(batch_responses(json)).unwrap_err();
+    assert_eq!(err.ids, vec![Id::from(2)]);
+    assert_eq!(err.to_string(), "several responses have the id 2");
+    let ids: Vec<_> = err.responses.iter().map(|r| r.id().clone()).collect();
+    assert_eq!(
+        ids,
+        vec![Id::from(2), Id::from(3), Id::Null, Id::from(2), Id::from(2)]
+    );
+}
+
+#[test]
+fn test_id_eq_loose() {
+    assert!(Id::from(7).eq_loose(&Id::from("7")));
+    assert!(Id::from("7").eq_loose(&Id::from(7)));
+    assert!(!Id::from(7).eq_loose(&Id::from("07")));
+    assert!(!Id::from(7).eq_loose(&Id::from(" 7")));
+    assert!(!Id::from(7).eq_loose(&Id::from("+7")));
+    assert!(Id::from(-1).eq_loose(&Id::from("-1")));
+    assert!(!Id::from(0).eq_loose(&Id::from("-0")));
+    assert!(!Id::Null.eq_loose(&Id::from("null")));
+    assert!(Id::Null.eq_loose(&Id::Null));
+    assert!(!Id::from(7).eq_loose(&Id::from(8)));
+    assert!(Id::from(i64::MIN).eq_loose(&Id::from(i64::MIN.to_string())));
+
+    // strict equality is unchanged
+    assert_ne!(Id::from(7), Id::from("7"));
+}
+
+#[test]
+fn test_pending_loose_ids() {
+    let mut pending = PendingRequests::new();
+    pending.register(Id::from(7), "int").unwrap();
+    pending.register(Id::from("-1"), "string").unwrap();
+    assert_eq!(pending.complete(&Id::from("7")), None);
+    assert_eq!(pending.complete(&Id::from(-1)), None);
+
+    pending.set_loose_ids(true);
+    assert_eq!(pending.complete(&Id::from("07")), None);
+    assert_eq!(pending.complete(&Id::from("7")), Some("int"));
+    assert_eq!(pending.complete(&Id::from(-1)), Some("string"));
+    assert!(pending.is_empty());
+
+    // an exact match is preferred
+    pending.register(Id::from(1), "int").unwrap();
+    pending.register(Id::from("1"), "string").unwrap();
+    assert_eq!(pending.complete(&Id::from("1")), Some("string"));
+    assert_eq!(pending.complete(&Id::from("1")), Some("int"));
+}
+
+#[test]
+fn test_client_loose_ids() {
+    let mut client = ClientState::new();
+    let (id, _) = client.start_call("subtract", Some([42, 23]), "caller").unwrap();
+    assert_eq!(id, Id::from(1));
+    client.pending_mut().set_loose_ids(true);
+    match client.on_message(r#"{"jsonrpc": "2.0", "result": 19, "id": "1"}"#) {
+        InboundEvent::Response { meta, .. } => assert_eq!(meta, "caller"),
+        event => panic!("unexpected {:?}", event),
+    }
+}
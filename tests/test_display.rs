@@ -0,0 +1,118 @@
+//! The one-line summaries of `Display`, which log parsers rely on.
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Method {
+    #[serde(rename = "get_data")]
+    GetData,
+}
+
+#[test]
+fn test_display_request() {
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"minuend": 42, "subtrahend": 23}, "id": 4}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert_eq!(format!("{}", request), "--> subtract id=4 params=30B");
+
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": [1, 2, 3, 4, 5]}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert_eq!(format!("{}", request), "--> update id=notif params=11B");
+
+    let json = r#"{"jsonrpc": "2.0", "method": "get_data", "id": "a\"b"}"#;
+    let request = parse_request::<Method>(json).unwrap();
+    assert_eq!(format!("{}", request), r#"--> get_data id="a\"b""#);
+
+    let json = r#"{"jsonrpc": "2.0", "method": "get_data", "params": [], "id": null}"#;
+    let request = parse_request::<Method>(json).unwrap();
+    assert_eq!(format!("{}", request), "--> get_data id=null params=2B");
+}
+
+#[test]
+fn test_display_response() {
+    let response = Response::success(Id::from(4), Value::from(19));
+    assert_eq!(format!("{}", response), "<-- id=4 ok result=2B");
+
+    let response = Response::success(Id::from("abc"), serde_json::json!(["hello", 5]));
+    assert_eq!(format!("{}", response), r#"<-- id="abc" ok result=11B"#);
+
+    let response: Response<Value> = Response::error(
+        Id::Null,
+        ErrorCode::ParseError,
+        "secret details",
+        Some(Value::from("more secret details")),
+    );
+    assert_eq!(format!("{}", response), "<-- id=null err code=-32700");
+}
+
+#[test]
+fn test_display_never_contains_params() {
+    let request = Request::with_params(
+        Id::from(1),
+        "login".to_string(),
+        vec!["hunter2".to_string()],
+    );
+    assert_eq!(format!("{}", request), "--> login id=1 params=11B");
+    assert_eq!(
+        request.to_string().len(),
+        serde_json::to_string(&request).unwrap().len(),
+        "to_string is still the json"
+    );
+}
+
+fn error_object(code: ErrorCode, data: Option<Value>) -> ErrorObject<Value> {
+    ErrorObject {
+        code,
+        message: "failed".into(),
+        data,
+    }
+}
+
+#[test]
+fn test_display_error_object() {
+    let expected = [
+        (ErrorCode::ParseError, "Parse error (-32700): failed"),
+        (ErrorCode::InvalidRequest, "Invalid Request (-32600): failed"),
+        (ErrorCode::MethodNotFound, "Method not found (-32601): failed"),
+        (ErrorCode::InvalidParams, "Invalid params (-32602): failed"),
+        (ErrorCode::InternalError, "Internal error (-32603): failed"),
+        (ErrorCode::ServerError(-32000), "Server error (-32000): failed"),
+        (ErrorCode::ServerError(42), "Server error (42): failed"),
+    ];
+    for &(code, summary) in &expected {
+        assert_eq!(error_object(code, None).to_string(), summary);
+    }
+
+    let error = error_object(ErrorCode::InternalError, Some(Value::from(vec![1, 2])));
+    assert_eq!(error.to_string(), "Internal error (-32603): failed; data: [1,2]");
+    let error = error_object(ErrorCode::InternalError, Some(Value::Null));
+    assert_eq!(error.to_string(), "Internal error (-32603): failed; data: null");
+}
+
+#[test]
+fn test_display_error_object_long_data() {
+    let data = Value::from("a".repeat(200));
+    let summary = error_object(ErrorCode::InternalError, Some(data)).to_string();
+    assert_eq!(
+        summary,
+        format!("Internal error (-32603): failed; data: \"{}...", "a".repeat(99))
+    );
+
+    // the data is truncated on a char boundary
+    let data = Value::from("é".repeat(100));
+    let summary = error_object(ErrorCode::InternalError, Some(data)).to_string();
+    assert!(summary.ends_with(&format!("; data: \"{}...", "é".repeat(49))));
+}
+
+#[test]
+fn test_display_error() {
+    let error: Error<Value> = Error::new(Id::from("a"), ErrorCode::InvalidParams, "bad", None);
+    assert_eq!(format!("{}", error), r#"id="a" Invalid params (-32602): bad"#);
+    let error: Error<Value> = Error::new(Id::Null, ErrorCode::ParseError, "bad", None);
+    assert_eq!(format!("{}", error), "id=null Parse error (-32700): bad");
+    // `to_string` is the json
+    assert!(error.to_string().starts_with("{"));
+}
@@ -0,0 +1,74 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+const DEFINED: &[(i64, ErrorCode)] = &[
+    (ErrorCode::PARSE_ERROR_CODE, ErrorCode::ParseError),
+    (ErrorCode::INVALID_REQUEST_CODE, ErrorCode::InvalidRequest),
+    (ErrorCode::METHOD_NOT_FOUND_CODE, ErrorCode::MethodNotFound),
+    (ErrorCode::INVALID_PARAMS_CODE, ErrorCode::InvalidParams),
+    (ErrorCode::INTERNAL_ERROR_CODE, ErrorCode::InternalError),
+];
+
+#[test]
+fn test_error_code_consts() {
+    assert_eq!(ErrorCode::PARSE_ERROR_CODE, -32700);
+    assert_eq!(ErrorCode::INVALID_REQUEST_CODE, -32600);
+    assert_eq!(ErrorCode::METHOD_NOT_FOUND_CODE, -32601);
+    assert_eq!(ErrorCode::INVALID_PARAMS_CODE, -32602);
+    assert_eq!(ErrorCode::INTERNAL_ERROR_CODE, -32603);
+    assert_eq!(
+        (ErrorCode::SERVER_ERROR_MIN, ErrorCode::SERVER_ERROR_MAX),
+        (-32099, -32000)
+    );
+    assert_eq!(
+        (ErrorCode::RESERVED_MIN, ErrorCode::RESERVED_MAX),
+        (-32768, -32000)
+    );
+
+    for &(code, error) in DEFINED {
+        assert_eq!(ErrorCode::from(code), error);
+        assert_eq!(error.code(), code);
+        assert_eq!(serde_json::to_string(&error).unwrap(), code.to_string());
+    }
+}
+
+#[test]
+fn test_try_from_code_reserved_range() {
+    for code in ErrorCode::RESERVED_MIN - 1..=ErrorCode::RESERVED_MAX + 1 {
+        let expected = match DEFINED.iter().find(|&&(c, _)| c == code) {
+            Some(&(_, error)) => Ok(error),
+            None if !(ErrorCode::RESERVED_MIN..=ErrorCode::RESERVED_MAX).contains(&code) => {
+                Ok(ErrorCode::ServerError(code))
+            }
+            None if code >= ErrorCode::SERVER_ERROR_MIN => Ok(ErrorCode::ServerError(code)),
+            None => Err(UnknownReservedCode(code)),
+        };
+        assert_eq!(ErrorCode::try_from_code(code), expected, "{}", code);
+    }
+}
+
+#[test]
+fn test_try_from_code_boundaries() {
+    let refused = [-32768, -32701, -32699, -32604, -32599, -32100];
+    for &code in &refused {
+        assert_eq!(
+            ErrorCode::try_from_code(code),
+            Err(UnknownReservedCode(code))
+        );
+        // the lossy conversion is unchanged
+        assert_eq!(ErrorCode::from(code), ErrorCode::ServerError(code));
+    }
+    let accepted = [-32769, -32099, -32000, -31999, 0, i64::MIN, i64::MAX];
+    for &code in &accepted {
+        assert_eq!(
+            ErrorCode::try_from_code(code),
+            Ok(ErrorCode::ServerError(code))
+        );
+    }
+    assert_eq!(
+        UnknownReservedCode(-32100).to_string(),
+        "the error code -32100 is reserved but not defined"
+    );
+}
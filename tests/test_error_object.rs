@@ -0,0 +1,174 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use std::borrow::Cow;
+use std::error::Error as StdError;
+
+use jrpc::*;
+
+fn handler(params: Value) -> Result<Value, ErrorObject<Value>> {
+    let (a, b): (i64, i64) = serde_json::from_value(params)?;
+    a.checked_sub(b)
+        .map(Value::from)
+        .ok_or_else(|| ErrorObject::internal("overflow"))
+}
+
+fn boxed(params: Value) -> Result<Value, Box<dyn StdError + Send + Sync>> {
+    Ok(handler(params)?)
+}
+
+#[test]
+fn test_error_object_boxed() {
+    assert_eq!(boxed(Value::from(vec![42, 23])).unwrap(), 19);
+
+    let error = boxed(Value::from(vec![i64::MIN, 1])).unwrap_err();
+    assert_eq!(error.to_string(), "Internal error (-32603): overflow");
+    let error = error.downcast::<ErrorObject<Value>>().unwrap();
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn test_error_object_constructors() {
+    let error: ErrorObject<Value> = ErrorObject::internal("db down");
+    assert_eq!(error.code, ErrorCode::InternalError);
+    assert_eq!(error.message, "db down");
+    assert_eq!(error.data, None);
+
+    let error: ErrorObject<()> = ErrorObject::invalid_params(String::from("need two numbers"));
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.message, "need two numbers");
+}
+
+#[test]
+fn test_error_object_from_serde_json() {
+    let error = handler(Value::from("42 - 23")).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+    assert_eq!(
+        error.message,
+        "invalid type: string \"42 - 23\", expected a tuple of size 2"
+    );
+
+    let err = serde_json::from_str::<Value>("{").unwrap_err();
+    let error = ErrorObject::from(err);
+    assert_eq!(error.code, ErrorCode::InternalError);
+}
+
+#[test]
+fn test_error_object_with_id() {
+    let error: Error<Value> = ErrorObject::internal("db down").with_id(Id::from("a"));
+    testing::assert_json_eq(
+        &serde_json::to_string(&error).unwrap(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32603, "message": "db down", "data": null}, "id": "a"}"#,
+    );
+
+    let (id, object) = error.into_parts();
+    assert_eq!(id, Id::from("a"));
+    assert_eq!(object.code, ErrorCode::InternalError);
+    assert_eq!(object.data, None);
+}
+
+#[test]
+fn test_error_object_with_id_data() {
+    let object = ErrorObject {
+        code: ErrorCode::InvalidParams,
+        message: "need two numbers".into(),
+        data: Some(Value::from(vec![42])),
+    };
+    let error = object.with_id(Id::from(4));
+    let expected = Error::new(
+        Id::from(4),
+        ErrorCode::InvalidParams,
+        "need two numbers",
+        Some(Value::from(vec![42])),
+    );
+    assert_eq!(error.to_string(), expected.to_string());
+
+    let (id, object) = error.into_parts();
+    assert_eq!(id, Id::from(4));
+    assert_eq!(object.data, Some(Value::from(vec![42])));
+}
+
+fn is_borrowed<T>(object: &ErrorObject<T>) -> bool {
+    match object.message {
+        Cow::Borrowed(_) => true,
+        Cow::Owned(_) => false,
+    }
+}
+
+#[test]
+fn test_error_object_message_cow() {
+    // `&'static str`, `String` and `format!` are all accepted, only constants are borrowed
+    let error: Error<Value> = Error::new(Id::from(1), ErrorCode::InvalidParams, "bad", None);
+    assert!(is_borrowed(&error.error));
+    let error: Error<Value> =
+        Error::new(Id::from(1), ErrorCode::InvalidParams, String::from("bad"), None);
+    assert!(!is_borrowed(&error.error));
+    let name = "minuend";
+    let error: Error<Value> =
+        Error::new(Id::from(1), ErrorCode::InvalidParams, format!("bad {}", name), None);
+    assert_eq!(error.error.message, "bad minuend");
+
+    let response: Response<Value> = Response::error(Id::Null, -32000, "busy", None);
+    match response {
+        Response::Err(error) => assert!(is_borrowed(&error.error)),
+        Response::Ok(_) => panic!("expected an error"),
+    }
+    let object: ErrorObject<Value> = ErrorObject::internal(format!("{} failed", "query"));
+    assert_eq!(object.message, "query failed");
+    assert!(is_borrowed(&ErrorObject::<Value>::invalid_params("need two numbers")));
+
+    // the standard messages are borrowed
+    let object = ErrorObject::builder(ErrorCode::MethodNotFound).build().unwrap();
+    assert_eq!(object.message, "Method not found");
+    assert!(is_borrowed(&object));
+
+    // deserialized messages are owned and the json is the same
+    let json = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"bad","data":null},"id":1}"#;
+    let parsed: Error<Value> = serde_json::from_str(json).unwrap();
+    assert!(!is_borrowed(&parsed.error));
+    assert_eq!(parsed.error.message, "bad");
+    let error: Error<Value> = Error::new(Id::from(1), ErrorCode::InvalidParams, "bad", None);
+    assert_eq!(error.to_string(), json);
+}
+
+const NULL_ID: Id = Id::Null;
+const FIRST_ID: Id = Id::Int(1);
+const NOTIFICATION: IdReq = IdReq::Notification;
+const BUSY_CODE: ErrorCode = ErrorCode::from_code_const(-32001);
+const BUSY_CODE_NUMBER: i64 = BUSY_CODE.code();
+const PARSE_ERROR_MESSAGE: &str = ErrorCode::ParseError.standard_message();
+
+static PARSE_ERROR: ErrorObject<Value> = ErrorObject::standard(ErrorCode::ParseError);
+static BUSY: ErrorObject<Value> = ErrorObject::new_static(BUSY_CODE, "the server is busy");
+
+#[test]
+fn test_error_object_static() {
+    assert_eq!(FIRST_ID, Id::from(1));
+    assert!(NOTIFICATION.is_notification());
+    assert_eq!(BUSY_CODE_NUMBER, -32001);
+    assert!(BUSY_CODE.is_valid());
+    assert_eq!(ErrorCode::from_code_const(-32700), ErrorCode::ParseError);
+    assert_eq!(ErrorCode::from_code_const(42), ErrorCode::from(42));
+    assert_eq!(PARSE_ERROR_MESSAGE, "Parse error");
+
+    assert_eq!(PARSE_ERROR.code, ErrorCode::ParseError);
+    assert_eq!(PARSE_ERROR.message, "Parse error");
+    assert_eq!(PARSE_ERROR.data, None);
+    assert!(is_borrowed(&PARSE_ERROR));
+    assert_eq!(BUSY.code, ErrorCode::ServerError(-32001));
+    assert_eq!(BUSY.message, "the server is busy");
+
+    // the same as the builder's default message
+    for &code in &[ErrorCode::InvalidRequest, ErrorCode::InternalError, BUSY_CODE] {
+        let built = ErrorObject::builder(code).build().unwrap();
+        let standard = ErrorObject::<Value>::standard(code);
+        assert_eq!((built.code, built.message), (standard.code, standard.message));
+    }
+
+    let error = ErrorObject::<Value>::standard(ErrorCode::ParseError).with_id(NULL_ID);
+    assert_eq!(
+        error.to_string(),
+        r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error","data":null},"id":null}"#
+    );
+}
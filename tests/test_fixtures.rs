@@ -0,0 +1,108 @@
+#![cfg(feature = "test-fixtures")]
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::fixtures::{self, Expected, Fixture, SpecMethod};
+use jrpc::*;
+
+/// `(id, Some(code))` for each entry of a response (`None` for a success).
+fn response_entries(json: &str) -> Vec<(Id, Option<ErrorCode>)> {
+    let value: Value = serde_json::from_str(json).unwrap();
+    let values = match value {
+        Value::Array(values) => values,
+        v => vec![v],
+    };
+    values
+        .into_iter()
+        .map(|v| match serde_json::from_value::<Response<Value>>(v).unwrap() {
+            Response::Ok(success) => (success.id, None),
+            Response::Err(error) => (error.id, Some(error.error.code)),
+        })
+        .collect()
+}
+
+fn check_request(fixture: &Fixture) {
+    let request = parse_request::<SpecMethod>(fixture.request).unwrap();
+    let expected = (fixture.parsed.expect("parsed"))();
+    assert_eq!(expected.method, request.method, "{}", fixture.name);
+    assert_eq!(expected.id, request.id, "{}", fixture.name);
+    assert_eq!(expected.params, request.params, "{}", fixture.name);
+
+    match request.id.into_id() {
+        Some(id) => assert_eq!(
+            response_entries(fixture.response.unwrap()),
+            vec![(id, None)],
+            "{}",
+            fixture.name
+        ),
+        None => assert!(fixture.response.is_none(), "{}", fixture.name),
+    }
+}
+
+fn check_error(fixture: &Fixture, code: ErrorCode) -> Option<ErrorCode> {
+    let error = parse_request::<SpecMethod>(fixture.request).unwrap_err();
+    if error.error.code != code {
+        return Some(error.error.code);
+    }
+    assert!(!error.error.message.is_empty(), "{}", fixture.name);
+    if let Some(response) = fixture.response {
+        assert_eq!(
+            response_entries(response),
+            vec![(error.id, Some(code))],
+            "{}",
+            fixture.name
+        );
+    }
+    None
+}
+
+fn check_batch(fixture: &Fixture) {
+    let values: Vec<Value> = serde_json::from_str(fixture.request).unwrap();
+    let mut entries = Vec::new();
+    for value in values {
+        let json = serde_json::to_string(&value).unwrap();
+        match parse_request::<SpecMethod>(&json) {
+            Ok(request) => if let Some(id) = request.id.into_id() {
+                entries.push((id, None));
+            },
+            Err(error) => entries.push((error.id, Some(error.error.code))),
+        }
+    }
+    match fixture.response {
+        Some(response) => assert_eq!(response_entries(response), entries, "{}", fixture.name),
+        None => assert!(entries.is_empty(), "{}", fixture.name),
+    }
+}
+
+#[test]
+fn test_fixtures() {
+    let mut deviations = Vec::new();
+    for fixture in fixtures::ALL {
+        match fixture.expected {
+            Expected::Request => check_request(fixture),
+            Expected::Error(code) => if let Some(got) = check_error(fixture, code) {
+                deviations.push((fixture.name, got));
+            },
+            Expected::Batch => check_batch(fixture),
+        }
+    }
+
+    assert!(deviations.is_empty(), "{:?}", deviations);
+}
+
+#[test]
+fn test_fixture_messages() {
+    let error = parse_request::<SpecMethod>(fixtures::METHOD_NOT_FOUND.request).unwrap_err();
+    assert!(error.error.message.contains("unknown variant `foobar`"));
+
+    let error = parse_request::<SpecMethod>(fixtures::INVALID_JSON.request).unwrap_err();
+    assert!(error.error.message.contains("line 1"));
+}
+
+#[test]
+fn test_fixture_names_unique() {
+    let mut names: Vec<_> = fixtures::ALL.iter().map(|f| f.name).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), fixtures::ALL.len());
+}
@@ -0,0 +1,114 @@
+//! Run the seed corpus of the fuzz targets (and the inputs of the crashes they found) through the
+//! same entry points, so that the no-panic guarantee is checked without `cargo fuzz`.
+
+extern crate jrpc;
+extern crate serde_json;
+
+use std::fs;
+use std::path::Path;
+
+use jrpc::client::ClientState;
+use jrpc::router::{self, Router};
+use jrpc::*;
+
+/// The inputs of `fuzz/corpus` to keep, see `tests/data/fuzz`.
+fn corpus() -> Vec<(String, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/fuzz");
+    let mut inputs: Vec<(String, String)> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let bytes = fs::read(&path).unwrap();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, String::from_utf8_lossy(&bytes).into_owned())
+        })
+        .collect();
+    inputs.sort();
+    assert!(inputs.len() >= 10, "the corpus is missing");
+    inputs
+}
+
+/// `fuzz/fuzz_targets/parse_request.rs`.
+fn parse_request_target(json: &str) {
+    let _ = parse_request::<String>(json);
+    let _ = parse_request::<Value>(json);
+    let _ = parse_request_exact::<String>(json);
+    let options = ParseOptions::strict()
+        .max_depth(8)
+        .max_method_len(16)
+        .max_id_len(16)
+        .max_message_len(Some(16))
+        .full_message_in_data(true);
+    if let Ok(request) = parse_request_with::<String>(json, &options) {
+        let _ = request.to_string();
+    }
+}
+
+/// `fuzz/fuzz_targets/parse_batch.rs`.
+fn parse_batch_target(json: &str) {
+    let echo = |value: Value| match parse_request_value::<String>(value) {
+        Ok(request) => {
+            let id = request.id.into_id()?;
+            Some(Response::success(id, request.params.unwrap_or(Value::Null)))
+        }
+        Err(error) => Some(Response::Err(error)),
+    };
+    let _ = router::handle_batch(echo, json);
+
+    let mut router = Router::new();
+    router.register_fn("subtract", |(a, b): (i64, i64)| Ok(a.wrapping_sub(b)));
+    let _ = router.handle_batch(&(), json);
+}
+
+/// `fuzz/fuzz_targets/parse_response.rs`.
+fn parse_response_target(json: &str) {
+    let _ = parse_response_exact::<Value>(json);
+    let _ = parse_response_lenient::<Value>(json);
+    if let Ok(response) = parse_response_exact::<Value>(json) {
+        let _ = response.to_string();
+    }
+
+    let mut client: ClientState<()> = ClientState::new();
+    let _ = client.pending_mut().register(Id::from(1), ());
+    let _ = client.on_message(json);
+}
+
+#[test]
+fn test_corpus_never_panics() {
+    for (name, json) in corpus() {
+        println!("{}", name);
+        parse_request_target(&json);
+        parse_batch_target(&json);
+        parse_response_target(&json);
+
+        // every prefix, i.e. the truncated messages of a broken connection
+        for end in (0..json.len()).filter(|&i| json.is_char_boundary(i)).take(512) {
+            parse_request_target(&json[..end]);
+            parse_response_target(&json[..end]);
+        }
+    }
+}
+
+#[test]
+fn test_corpus_errors() {
+    let corpus = corpus();
+    let input = |name: &str| &corpus.iter().find(|&(n, _)| n == name).unwrap().1;
+
+    for name in &["deep_arrays", "deep_objects"] {
+        let error = parse_request::<String>(input(name)).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError);
+        assert_eq!(error.error.message, "maximum nesting depth exceeded");
+        let reply = router::handle_batch(|_| None, input(name)).unwrap();
+        assert!(reply.contains("-32700"), "{}", reply);
+        assert!(parse_response_exact::<Value>(input(name)).is_err());
+    }
+    assert!(parse_request::<String>(input("deep_params")).is_ok());
+
+    // ids which are not integers
+    for name in &["float_id", "huge_id"] {
+        let error = parse_request::<String>(input(name)).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+        assert_eq!(error.id, Id::Null);
+    }
+    assert!(parse_response_exact::<Value>(input("huge_float_id")).is_err());
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "http")]
+extern crate http;
+extern crate jrpc;
+extern crate serde_json;
+
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+use jrpc::router::Router;
+use jrpc::*;
+
+fn router() -> Router<()> {
+    let mut router = Router::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+    router
+}
+
+fn headers(content_type: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers
+}
+
+fn error_code(body: &[u8]) -> ErrorCode {
+    let error: Error<Value> = serde_json::from_slice(body).unwrap();
+    error.error.code
+}
+
+#[test]
+fn test_content_type() {
+    assert!(jrpc::http::is_json(&headers("application/json")));
+    assert!(jrpc::http::is_json(&headers("Application/JSON; charset=utf-8")));
+    assert!(!jrpc::http::is_json(&headers("text/plain")));
+    assert!(!jrpc::http::is_json(&headers("application/jsonp")));
+    assert!(!jrpc::http::is_json(&HeaderMap::new()));
+
+    let body = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    for headers in &[headers("text/plain"), HeaderMap::new()] {
+        let response = jrpc::http::handle(&router(), &(), headers, body);
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response.body(), b"expected a Content-Type of application/json");
+    }
+}
+
+#[test]
+fn test_handle() {
+    let json = headers("application/json");
+    let handle = |body: &[u8]| jrpc::http::handle(&router(), &(), &json, body);
+
+    let body = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let response = handle(body);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    assert_eq!(response.body(), br#"{"jsonrpc":"2.0","result":19,"id":1}"#);
+
+    let response = handle(br#"{"jsonrpc": "2.0", "method": "foobar", "id": 1}"#);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(error_code(response.body()), ErrorCode::MethodNotFound);
+
+    let response = handle(br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23]}"#);
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+    assert!(response.body().is_empty());
+
+    // failures to parse are jsonrpc errors, not HTTP ones
+    let response = handle(br#"{"jsonrpc": "2.0", "method"#);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(error_code(response.body()), ErrorCode::ParseError);
+    let response = handle(b"{\"jsonrpc\": \"2.0\", \"method\": \"\xff\", \"id\": 1}");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(error_code(response.body()), ErrorCode::ParseError);
+}
+
+#[test]
+fn test_handle_batch() {
+    let json = headers("application/json");
+    let handle = |body: &[u8]| jrpc::http::handle(&router(), &(), &json, body);
+
+    let response = handle(
+        br#"[
+            {"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1},
+            {"jsonrpc": "2.0", "method": "subtract", "params": [1, 2]}
+        ]"#,
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), br#"[{"jsonrpc":"2.0","result":19,"id":1}]"#);
+
+    // a batch of only notifications has nothing to reply
+    let response = handle(
+        br#"[
+            {"jsonrpc": "2.0", "method": "subtract", "params": [42, 23]},
+            {"jsonrpc": "2.0", "method": "foobar"}
+        ]"#,
+    );
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.body().is_empty());
+
+    let response = handle(b"[]");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(error_code(response.body()), ErrorCode::InvalidRequest);
+}
+
+#[test]
+fn test_parse_request_body() {
+    let json = headers("application/json");
+    let body = br#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let request = jrpc::http::parse_request_body::<String>(&json, body).unwrap();
+    assert_eq!(request.method, "subtract");
+
+    let response = jrpc::http::parse_request_body::<String>(&json, b"[]").unwrap_err();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(error_code(response.body()), ErrorCode::InvalidRequest);
+
+    let response = jrpc::http::parse_request_body::<String>(&HeaderMap::new(), body).unwrap_err();
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
@@ -0,0 +1,71 @@
+extern crate jrpc;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use jrpc::router::Router;
+use jrpc::*;
+
+/// Counts the allocations of the current thread, since the tests run in parallel.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<R>(f: impl FnOnce() -> R) -> (usize, R) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (ALLOCATIONS.with(Cell::get) - before, result)
+}
+
+const UUID: &str = r#""67e55044-10b1-426f-9247-bb680e5fe0c8""#;
+
+fn request(id: &str) -> String {
+    format!(
+        r#"{{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": {}}}"#,
+        id
+    )
+}
+
+fn parse_and_respond(json: &str) -> String {
+    let request = parse_request::<String>(json).unwrap();
+    Response::success(request.id.into_id().unwrap(), 19).to_string()
+}
+
+#[test]
+fn test_string_id_allocated_once() {
+    // the String id is allocated when parsed and then moved into the Response
+    let (int_request, uuid_request) = (request("1"), request(UUID));
+    let (int, reply) = allocations(|| parse_and_respond(&int_request));
+    assert_eq!(reply, r#"{"jsonrpc":"2.0","result":19,"id":1}"#);
+    let (uuid, reply) = allocations(|| parse_and_respond(&uuid_request));
+    assert!(reply.ends_with(&format!("\"id\":{}}}", UUID)));
+    assert_eq!(uuid, int + 1);
+}
+
+#[test]
+fn test_router_string_id_allocated_once() {
+    let mut router = Router::new();
+    router.register_fn("subtract", |(a, b): (i64, i64)| Ok(a - b));
+
+    let (int_request, uuid_request) = (request("1"), request(UUID));
+    let (int, _) = allocations(|| router.handle(&(), &int_request));
+    let (uuid, reply) = allocations(|| router.handle(&(), &uuid_request));
+    assert!(reply.unwrap().contains(UUID));
+    assert_eq!(uuid, int + 1);
+}
@@ -0,0 +1,139 @@
+extern crate jrpc;
+extern crate serde;
+extern crate serde_json;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashSet;
+
+use jrpc::*;
+
+/// Counts the allocations of the current thread, since the tests run in parallel.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<R>(f: impl FnOnce() -> R) -> (usize, R) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (ALLOCATIONS.with(Cell::get) - before, result)
+}
+
+#[test]
+fn test_interned_no_method_allocations() {
+    let interner = MethodInterner::new();
+    let known = interner.get_or_intern("subtract");
+
+    let (count, found) = allocations(|| interner.get("subtract"));
+    assert_eq!(count, 0);
+    assert!(MethodName::ptr_eq(&found.unwrap(), &known));
+
+    // a known method is shared instead of allocating a new name
+    for json in &[
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": "subtract", "id": "a"}"#,
+    ] {
+        let (count, request) = allocations(|| parse_request_interned(json, &interner).unwrap());
+        assert!(MethodName::ptr_eq(&request.method, &known));
+        let (owned, _) = allocations(|| parse_request::<MethodName>(json).unwrap());
+        assert!(owned > count);
+    }
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_interned_unknown_methods() {
+    let interner = MethodInterner::new();
+    interner.get_or_intern("subtract");
+    for i in 0..100 {
+        let json = format!(r#"{{"jsonrpc": "2.0", "method": "m{}", "id": {}}}"#, i, i);
+        let request = parse_request_interned(&json, &interner).unwrap();
+        assert_eq!(request.method, format!("m{}", i).as_str());
+        assert!(!MethodName::ptr_eq(
+            &request.method,
+            &parse_request_interned(&json, &interner).unwrap().method
+        ));
+    }
+    assert!(interner.get("m0").is_none());
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_interned_same_as_parse_request() {
+    let interner = MethodInterner::new();
+    let inputs = &[
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": "a"}"#,
+        r#"{"jsonrpc": "2.0", "method": "sub\ttract"}"#,
+        "\u{feff}{\"jsonrpc\": \"2.0\", \"method\": \"subtract\", \"id\": null}",
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": null}"#,
+        r#"{"jsonrpc": "2.0", "method": "subtract", "method": "sum", "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": 1, "id": 1}"#,
+        r#"{"jsonrpc": "1.0", "method": "subtract", "id": 1}"#,
+        r#"{"method": "subtract"}"#,
+        r#"{"jsonrpc": "2.0", "method": "subtract""#,
+        "  ",
+    ];
+    let deep = format!(
+        r#"{{"jsonrpc": "2.0", "method": "subtract", "params": {}{}, "id": 1}}"#,
+        "[".repeat(200),
+        "]".repeat(200)
+    );
+    let mut inputs = inputs.to_vec();
+    inputs.push(&deep);
+    interner.get_or_intern("subtract");
+    interner.get_or_intern("sub\ttract");
+    for input in &inputs {
+        let expected = parse_request::<String>(input).map(|r| r.to_string());
+        let got = parse_request_interned(input, &interner).map(|r| r.to_string());
+        match (expected, got) {
+            (Ok(expected), Ok(got)) => assert_eq!(expected, got, "{}", input),
+            (Err(expected), Err(got)) => assert_eq!(expected.to_string(), got.to_string()),
+            (expected, got) => panic!("{}: {:?} != {:?}", input, expected, got),
+        }
+    }
+    let error = parse_request_interned(&deep, &interner).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    // the escaped name is known too
+    assert!(MethodName::ptr_eq(
+        &interner.get("sub\ttract").unwrap(),
+        &parse_request_interned(inputs[1], &interner).unwrap().method
+    ));
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn test_method_name_eq() {
+    let interner = MethodInterner::new();
+    let interned = interner.get_or_intern("subtract");
+    let other = MethodName::from("subtract".to_string());
+    assert_eq!(interned, other);
+    assert!(!MethodName::ptr_eq(&interned, &other));
+    assert_ne!(interned, MethodName::from("sum"));
+    assert_eq!(interned, "subtract");
+    assert_eq!(&*interned, "subtract");
+    assert_eq!(
+        format!("{} {:?}", interned, interned),
+        r#"subtract "subtract""#
+    );
+
+    let mut names = HashSet::new();
+    names.insert(interned);
+    assert!(names.contains(&other));
+    assert!(names.contains("subtract"));
+}
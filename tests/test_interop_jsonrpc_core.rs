@@ -0,0 +1,108 @@
+#![cfg(feature = "interop-jsonrpc-core")]
+extern crate jrpc;
+extern crate jsonrpc_core;
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+use jrpc::*;
+use jsonrpc_core as rpc;
+
+const REQUESTS: &[&str] = &[
+    r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#,
+    r#"{"jsonrpc":"2.0","method":"subtract","params":{"minuend":42,"subtrahend":23},"id":"a"}"#,
+    r#"{"jsonrpc":"2.0","method":"get_data","id":null}"#,
+    r#"{"jsonrpc":"2.0","method":"update","params":[1,2,3]}"#,
+];
+
+const RESPONSES: &[&str] = &[
+    r#"{"jsonrpc":"2.0","result":19,"id":1}"#,
+    r#"{"jsonrpc":"2.0","result":["hello",5],"id":"9"}"#,
+    r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":"5"}"#,
+    r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"busy","data":[1]},"id":null}"#,
+];
+
+#[test]
+fn test_request_roundtrip() {
+    for json in REQUESTS {
+        let request: Request<String, Value> = serde_json::from_str(json).unwrap();
+        let call = rpc::Call::try_from(request).unwrap();
+
+        // jsonrpc-core agrees on the meaning of the message
+        let expected: rpc::Call = serde_json::from_str(json).unwrap();
+        assert_eq!(call, expected, "{}", json);
+
+        let original: Request<String, Value> = serde_json::from_str(json).unwrap();
+        let request = Request::<String, Value>::try_from(call).unwrap();
+        assert_eq!(request.to_string(), original.to_string());
+    }
+}
+
+#[test]
+fn test_response_roundtrip() {
+    for json in RESPONSES {
+        let response: Response<Value> = serde_json::from_str(json).unwrap();
+        let output = rpc::Output::try_from(response).unwrap();
+
+        let expected: rpc::Output = serde_json::from_str(json).unwrap();
+        assert_eq!(output, expected, "{}", json);
+
+        let original: Response<Value> = serde_json::from_str(json).unwrap();
+        let response = Response::<Value>::try_from(output).unwrap();
+        assert_eq!(response.to_string(), original.to_string());
+    }
+}
+
+#[test]
+fn test_error_code() {
+    let codes = [
+        ErrorCode::ParseError,
+        ErrorCode::InvalidRequest,
+        ErrorCode::MethodNotFound,
+        ErrorCode::InvalidParams,
+        ErrorCode::InternalError,
+        ErrorCode::ServerError(-32000),
+        ErrorCode::ServerError(42),
+    ];
+    for &code in codes.iter() {
+        let theirs = rpc::ErrorCode::from(code);
+        assert_eq!(ErrorCode::from(theirs), code);
+    }
+    assert_eq!(
+        rpc::ErrorCode::from(ErrorCode::MethodNotFound).code(),
+        -32601
+    );
+}
+
+#[test]
+fn test_id_out_of_range() {
+    let err = rpc::Id::try_from(Id::Int(-1)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "conversion failed: jsonrpc-core ids cannot be negative, got -1"
+    );
+    assert!(Id::try_from(rpc::Id::Num(u64::MAX)).is_err());
+    assert_eq!(
+        Id::try_from(rpc::Id::Num(i64::MAX as u64)).unwrap(),
+        Id::Int(i64::MAX)
+    );
+}
+
+#[test]
+fn test_lossy_requests() {
+    // primitive params can't be represented
+    let request = Request::with_params(Id::from(1), "foo".to_string(), Value::from(1));
+    assert!(rpc::Call::try_from(request).is_err());
+
+    // an invalid call has no equivalent
+    let invalid = rpc::Call::Invalid { id: rpc::Id::Num(1) };
+    assert!(Request::<String, Value>::try_from(invalid).is_err());
+
+    // version 1.0 calls become 2.0
+    let call: rpc::Call = serde_json::from_str(r#"{"method":"foo","params":[],"id":1}"#).unwrap();
+    let request = Request::<String, Value>::try_from(call).unwrap();
+    testing::assert_json_eq(
+        &request.to_string(),
+        r#"{"jsonrpc":"2.0","method":"foo","params":[],"id":1}"#,
+    );
+}
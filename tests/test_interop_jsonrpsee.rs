@@ -0,0 +1,122 @@
+#![cfg(feature = "interop-jsonrpsee")]
+extern crate jrpc;
+extern crate jsonrpsee_types;
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+use jrpc::*;
+use jsonrpsee_types as rpc;
+
+const REQUESTS: &[&str] = &[
+    r#"{"jsonrpc":"2.0","method":"subtract","params":[42,23],"id":1}"#,
+    r#"{"jsonrpc":"2.0","method":"subtract","params":{"minuend":42,"subtrahend":23},"id":"a"}"#,
+    r#"{"jsonrpc":"2.0","method":"get_data","id":null}"#,
+];
+
+const NOTIFICATIONS: &[&str] = &[
+    r#"{"jsonrpc":"2.0","method":"update","params":[1,2,3]}"#,
+    r#"{"jsonrpc":"2.0","method":"heartbeat"}"#,
+];
+
+const RESPONSES: &[&str] = &[
+    r#"{"jsonrpc":"2.0","result":19,"id":1}"#,
+    r#"{"jsonrpc":"2.0","result":["hello",5],"id":"9"}"#,
+    r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":"5"}"#,
+    r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"busy","data":[1]},"id":null}"#,
+];
+
+/// The members of the json, whatever their order.
+fn value(json: &str) -> Value {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_request_roundtrip() {
+    for json in REQUESTS {
+        let request: Request<String, Value> = serde_json::from_str(json).unwrap();
+        let call = rpc::Request::try_from(request).unwrap();
+
+        // jsonrpsee agrees on the meaning of the message
+        assert_eq!(serde_json::to_value(&call).unwrap(), value(json));
+        let theirs: rpc::Request = serde_json::from_str(json).unwrap();
+
+        let request = Request::<String, Value>::try_from(theirs).unwrap();
+        assert_eq!(request.to_string(), *json);
+        let request = Request::<String, Value>::try_from(call).unwrap();
+        assert_eq!(request.to_string(), *json);
+    }
+}
+
+#[test]
+fn test_notification_roundtrip() {
+    for json in NOTIFICATIONS {
+        let request: Request<String, Value> = serde_json::from_str(json).unwrap();
+        assert!(rpc::Request::try_from(request).is_err());
+        let request: Request<String, Value> = serde_json::from_str(json).unwrap();
+        let notification = rpc::Notification::<Option<Value>>::try_from(request).unwrap();
+
+        let theirs: rpc::Notification<Option<Value>> = serde_json::from_str(json).unwrap();
+        assert_eq!(theirs.method, notification.method);
+        assert_eq!(theirs.params, notification.params);
+
+        let request = Request::<String, Value>::from(notification);
+        assert_eq!(request.to_string(), *json);
+    }
+
+    let request: Request<String, Value> = serde_json::from_str(REQUESTS[0]).unwrap();
+    assert!(rpc::Notification::<Option<Value>>::try_from(request).is_err());
+}
+
+#[test]
+fn test_response_roundtrip() {
+    for json in RESPONSES {
+        let response: Response<Value> = serde_json::from_str(json).unwrap();
+        let theirs = rpc::Response::<Value>::try_from(response).unwrap();
+        assert_eq!(serde_json::to_value(&theirs).unwrap(), value(json));
+
+        // an absent `data` is serialized as `null` by us
+        let original: Response<Value> = serde_json::from_str(json).unwrap();
+        let parsed: rpc::Response<Value> = serde_json::from_str(json).unwrap();
+        let response = Response::<Value>::try_from(parsed).unwrap();
+        assert_eq!(response.to_string(), original.to_string());
+        let response = Response::<Value>::try_from(theirs).unwrap();
+        assert_eq!(response.to_string(), original.to_string());
+    }
+}
+
+#[test]
+fn test_error_object() {
+    let error = || ErrorObject {
+        code: ErrorCode::InvalidParams,
+        message: "bad".into(),
+        data: Some(Value::from("x")),
+    };
+    let theirs = rpc::ErrorObjectOwned::try_from(error()).unwrap();
+    assert_eq!(theirs.code(), -32602);
+    assert_eq!(theirs.message(), "bad");
+    assert_eq!(theirs.data().unwrap().get(), r#""x""#);
+    let ours = ErrorObject::try_from(theirs).unwrap();
+    assert_eq!(ours.to_string(), error().to_string());
+    assert_eq!(ours.data, Some(Value::from("x")));
+
+    let error = ErrorObject::<Value> {
+        code: ErrorCode::ServerError(1 << 40),
+        message: "too big".into(),
+        data: None,
+    };
+    let err = rpc::ErrorObjectOwned::try_from(error).unwrap_err();
+    assert_eq!(err.reason, "jsonrpsee error codes are i32, got 1099511627776");
+}
+
+#[test]
+fn test_id_out_of_range() {
+    let err = rpc::Id::try_from(Id::Int(-1)).unwrap_err();
+    assert_eq!(err.reason, "jsonrpsee ids cannot be negative, got -1");
+
+    let err = Id::try_from(rpc::Id::Number(u64::MAX)).unwrap_err();
+    assert_eq!(err.reason, "id 18446744073709551615 does not fit in an i64");
+
+    let id = rpc::Id::Str("a".into());
+    assert_eq!(Id::try_from(id).unwrap(), Id::from("a"));
+}
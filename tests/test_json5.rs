@@ -0,0 +1,76 @@
+//! Parsing the sloppy json of some clients with `parse_request_lenient_json`.
+#![cfg(feature = "json5")]
+
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+fn expected() -> Value {
+    serde_json::json!({"minuend": 42, "subtrahend": [23, "a'b"]})
+}
+
+#[test]
+fn test_json5_syntax() {
+    for input in &[
+        // trailing commas
+        r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"minuend": 42, "subtrahend": [23, "a'b",],}, "id": 1,}"#,
+        // single quotes
+        r#"{'jsonrpc': '2.0', 'method': 'subtract', 'params': {'minuend': 42, 'subtrahend': [23, "a'b"]}, 'id': 1}"#,
+        // comments and unquoted keys
+        "// the firmware\n{jsonrpc: '2.0', /* subtract */ method: 'subtract',\n params: {minuend: 42, subtrahend: [23, 'a\\'b']}, id: 1}",
+    ] {
+        let request = parse_request_lenient_json::<String>(input).unwrap();
+        assert_eq!(request.method, "subtract", "{}", input);
+        assert_eq!(request.params, Some(expected()), "{}", input);
+        assert_eq!(request.id, IdReq::Int(1), "{}", input);
+
+        let error = parse_request::<String>(input).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError, "strict parsing rejects {}", input);
+    }
+}
+
+#[test]
+fn test_json5_staging_unchanged() {
+    let error =
+        parse_request_lenient_json::<String>("{jsonrpc: '2.0', method: 'a', id: 1").unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+
+    let error =
+        parse_request_lenient_json::<String>("{jsonrpc: '1.0', method: 'a', id: 1,}").unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+
+    let error =
+        parse_request_lenient_json::<String>("{jsonrpc: '2.0', method: 1, id: 7,}").unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    assert_eq!(error.id, Id::from(7));
+
+    let error = parse_request_lenient_json::<String>(" \n").unwrap_err();
+    assert_eq!(error.error.message, "empty request body");
+}
+
+#[test]
+fn test_json5_max_depth() {
+    let deep = format!("{}{}", "[".repeat(200), "]".repeat(200));
+    for params in &[
+        deep.clone(),
+        // the quotes of the strings and comments don't hide the brackets
+        format!(r#"['"', {}]"#, deep),
+        format!("// it's\r{}", deep),
+        format!("/* it's */ {}", deep),
+    ] {
+        let input = format!("{{jsonrpc: '2.0', method: 'a', params: {}, id: 1}}", params);
+        let error = parse_request_lenient_json::<String>(&input).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError, "{}", params);
+        assert_eq!(error.error.message, "maximum nesting depth exceeded");
+    }
+
+    // brackets in the strings and comments are not counted
+    let input = format!(
+        "{{jsonrpc: '2.0', /* {} */ method: 'a', params: ['{}'], id: 1}}",
+        "[".repeat(200),
+        "[".repeat(200)
+    );
+    let request = parse_request_lenient_json::<String>(&input).unwrap();
+    assert_eq!(request.method, "a");
+}
@@ -0,0 +1,146 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::*;
+
+rpc_methods! {
+    enum Api {
+        GetFoo = "foo.get" => fn get_foo(GetFooParams {
+            #[serde(rename = "fooName")]
+            name: String,
+        }) -> Vec<u32>;
+        Ping = "rpc.ping" => fn ping(PingParams {}) -> String;
+        Update = "update" => notify update(UpdateParams { values: Vec<u32> });
+        Hello = "notify_hello" => notify notify_hello(HelloParams { n: u32, });
+    }
+}
+
+#[test]
+fn test_renames() {
+    assert_eq!(Api::GetFoo.name(), "foo.get");
+    assert_eq!(<GetFooParams as Method>::NAME, "foo.get");
+    assert_eq!(serde_json::to_string(&Api::Ping).unwrap(), r#""rpc.ping""#);
+    for &method in &[Api::GetFoo, Api::Ping, Api::Update, Api::Hello] {
+        let json = serde_json::to_string(&method).unwrap();
+        assert_eq!(json, format!("\"{}\"", method.name()));
+        assert_eq!(serde_json::from_str::<Api>(&json).unwrap(), method);
+    }
+
+    let request = Api::get_foo(Id::from(5), "myself".to_string());
+    testing::assert_json_eq(
+        &serde_json::to_string(&request).unwrap(),
+        r#"{"jsonrpc": "2.0", "method": "foo.get", "params": {"fooName": "myself"}, "id": 5}"#,
+    );
+    testing::assert_json_eq(
+        &serde_json::to_string(&Api::ping(Id::from("a"))).unwrap(),
+        r#"{"jsonrpc": "2.0", "method": "rpc.ping", "params": {}, "id": "a"}"#,
+    );
+}
+
+#[test]
+fn test_parse_request() {
+    let json = r#"{"jsonrpc": "2.0", "method": "foo.get", "params": {"fooName": "x"}, "id": 5}"#;
+    let request = parse_request::<Api>(json).unwrap();
+    assert_eq!(request.method, Api::GetFoo);
+    let params: GetFooParams = serde_json::from_value(request.params.unwrap()).unwrap();
+    assert_eq!(params.name, "x");
+
+    // the variant names are not the wire names
+    let json = r#"{"jsonrpc": "2.0", "method": "GetFoo", "id": 5}"#;
+    let error = parse_request::<Api>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.id, Id::from(5));
+}
+
+#[test]
+fn test_notification_methods() {
+    let request = Api::update(vec![1, 2]);
+    assert_eq!(request.id, IdReq::Notification);
+    testing::assert_json_eq(
+        &serde_json::to_string(&request).unwrap(),
+        r#"{"jsonrpc": "2.0", "method": "update", "params": {"values": [1, 2]}}"#,
+    );
+    assert_eq!(Api::notify_hello(3).params, Some(HelloParams { n: 3 }));
+
+    let request = parse_request::<Api>(&serde_json::to_string(&request).unwrap()).unwrap();
+    assert_eq!(request.method, Api::Update);
+    assert_eq!(request.id, IdReq::Notification);
+}
+
+#[test]
+fn test_decode_response() {
+    let json = r#"{"jsonrpc": "2.0", "result": [1, 2], "id": 5}"#;
+    assert_eq!(decode_response::<GetFooParams>(json).unwrap(), vec![1, 2]);
+    let error = decode_response::<PingParams>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+}
+
+#[test]
+fn test_parse_request_listed() {
+    assert_eq!(
+        Api::method_names(),
+        &["foo.get", "rpc.ping", "update", "notify_hello"]
+    );
+
+    let json = r#"{"jsonrpc": "2.0", "method": "foo.set", "id": 1}"#;
+    let error = parse_request_listed::<Api>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.error.message, r#"unknown method "foo.set""#);
+    assert_eq!(error.id, Id::from(1));
+    testing::assert_json_eq(
+        &error.error.data.unwrap().to_string(),
+        r#"{"method": "foo.set", "known": ["foo.get", "rpc.ping", "update", "notify_hello"]}"#,
+    );
+
+    // a method which is not a String
+    let json = r#"{"jsonrpc": "2.0", "method": 1}"#;
+    let error = parse_request_listed::<Api>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    assert_eq!(error.error.data.unwrap()["member"], "method");
+
+    let json = r#"{"jsonrpc": "2.0", "method": "rpc.ping", "params": {}, "id": 1}"#;
+    assert_eq!(parse_request_listed::<Api>(json).unwrap().method, Api::Ping);
+}
+
+#[test]
+fn test_error_data() {
+    let field = String::from("minuend");
+    let data = error_data! {
+        "field" => field,
+        "limits" => { "min" => 0, "max" => { "inclusive" => 100 } },
+        "hint" =>? Some("use a smaller number"),
+        "detail" =>? None::<String>,
+        format!("{}_len", "message") => 9
+    };
+    assert_eq!(
+        data,
+        Some(serde_json::json!({
+            "field": "minuend",
+            "limits": {"min": 0, "max": {"inclusive": 100}},
+            "hint": "use a smaller number",
+            "message_len": 9,
+        }))
+    );
+
+    let error = Error::<Value>::new(Id::from(1), ErrorCode::InvalidParams, "bad", error_data! {});
+    testing::assert_json_eq(
+        &error.to_string(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32602, "message": "bad", "data": {}}, "id": 1}"#,
+    );
+}
+
+#[test]
+fn test_error_data_serialize_failure() {
+    let mut by_pair = std::collections::BTreeMap::new();
+    by_pair.insert((1, 2), "a");
+    let data = error_data! { "field" => "minuend", "by_pair" => by_pair };
+    assert_eq!(
+        data,
+        Some(serde_json::json!({
+            "field": "minuend",
+            "by_pair": "failed to serialize: key must be a string",
+        }))
+    );
+}
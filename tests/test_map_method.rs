@@ -0,0 +1,63 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[derive(Debug, PartialEq)]
+enum Internal {
+    GetUser,
+    DeleteUser,
+}
+
+fn to_internal(method: String) -> Result<Internal, String> {
+    match method.as_str() {
+        "v2.getUser" => Ok(Internal::GetUser),
+        "v2.deleteUser" => Ok(Internal::DeleteUser),
+        _ => Err(method),
+    }
+}
+
+fn parse(json: &str) -> Request<String, Value> {
+    parse_request(json).unwrap()
+}
+
+#[test]
+fn test_try_map_method() {
+    let request = parse(r#"{"jsonrpc": "2.0", "method": "v2.getUser", "params": [1], "id": 3}"#);
+    let request = request.try_map_method(to_internal).unwrap();
+    assert_eq!(request.method, Internal::GetUser);
+    assert_eq!(request.params, Some(Value::from(vec![1])));
+    assert_eq!(request.id, IdReq::Int(3));
+}
+
+#[test]
+fn test_try_map_method_unknown() {
+    let request = parse(r#"{"jsonrpc": "2.0", "method": "v1.getUser", "id": "a"}"#);
+    let unknown = request.try_map_method(to_internal).unwrap_err();
+    assert_eq!(unknown.method, "v1.getUser");
+    assert_eq!(unknown.id, IdReq::String("a".into()));
+    assert_eq!(unknown.to_string(), "method `v1.getUser` not found");
+    testing::assert_json_eq(
+        &serde_json::to_string(&unknown.to_error().unwrap()).unwrap(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "method `v1.getUser` not found",
+        "data": null}, "id": "a"}"#,
+    );
+
+    // nothing to reply to a Notification
+    let request = parse(r#"{"jsonrpc": "2.0", "method": "v1.getUser"}"#);
+    let unknown = request.try_map_method(to_internal).unwrap_err();
+    assert!(unknown.to_error().is_none());
+}
+
+#[test]
+fn test_map_method_back() {
+    let request = parse(r#"{"jsonrpc": "2.0", "method": "v2.deleteUser", "id": 1}"#)
+        .try_map_method(to_internal)
+        .unwrap();
+    let request = request.map_method(|method| match method {
+        Internal::GetUser => "v2.getUser",
+        Internal::DeleteUser => "v2.deleteUser",
+    });
+    assert_eq!(request.method, "v2.deleteUser");
+    assert_eq!(request.id, IdReq::Int(1));
+}
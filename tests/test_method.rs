@@ -0,0 +1,157 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+struct UpdateParams {
+    values: Vec<u32>,
+}
+
+struct Update;
+
+impl Method for Update {
+    const NAME: &'static str = "update";
+    type Params = UpdateParams;
+}
+
+#[test]
+fn test_call() {
+    let request = call::<Update>(IdReq::Notification, UpdateParams { values: vec![1, 2] });
+    assert_eq!(request.method, Update::NAME);
+    testing::assert_json_eq(
+        &serde_json::to_string(&request).unwrap(),
+        r#"{"jsonrpc": "2.0", "method": "update", "params": {"values": [1, 2]}}"#,
+    );
+
+    let request = call::<Update>(Id::from("a"), UpdateParams::default());
+    let json = serde_json::to_string(&request).unwrap();
+    let parsed: Request<String, UpdateParams> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.method, Update::NAME);
+    assert_eq!(parsed.params, Some(UpdateParams::default()));
+    assert_eq!(parsed.id, IdReq::String("a".into()));
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Pet {
+    name: String,
+    age: u32,
+}
+
+struct GetPet;
+
+impl Method for GetPet {
+    const NAME: &'static str = "get_pet";
+    type Params = (u32,);
+}
+
+impl RpcMethod for GetPet {
+    type Output = Pet;
+}
+
+impl RpcMethod for Update {
+    type Output = ();
+}
+
+impl NotificationMethod for Update {}
+
+#[test]
+fn test_decode_response() {
+    let json = r#"{"jsonrpc": "2.0", "result": {"name": "fido", "age": 3}, "id": 7}"#;
+    assert_eq!(
+        decode_response::<GetPet>(json).unwrap(),
+        Pet {
+            name: "fido".into(),
+            age: 3,
+        }
+    );
+
+    // the server's error is returned as-is
+    let json = r#"{"jsonrpc": "2.0", "error": {"code": -32602, "message": "no such pet"}, "id": 7}"#;
+    let error = decode_response::<GetPet>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidParams);
+    assert_eq!(error.error.message, "no such pet");
+    assert_eq!(error.id, Id::from(7));
+}
+
+#[test]
+fn test_decode_response_mismatch() {
+    // the result of a different method
+    let json = r#"{"jsonrpc": "2.0", "result": {"name": "fido"}, "id": 7}"#;
+    let error = decode_response::<GetPet>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(error.id, Id::from(7));
+    assert_eq!(
+        error.error.message,
+        "invalid result for `get_pet`: missing field `age`"
+    );
+
+    let error = decode_response::<Update>(r#"{"jsonrpc": "2.0", "result": 1, "id": 7}"#).unwrap_err();
+    assert_eq!(
+        error.error.message,
+        "invalid result for `update`: invalid type: integer `1`, expected unit"
+    );
+
+    let error = decode_response::<GetPet>(r#"{"result": 1}"#).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(error.id, Id::Null);
+    assert!(error.error.message.starts_with("invalid response to `get_pet`: "));
+}
+
+#[test]
+fn test_notify() {
+    let request = notify::<Update>(UpdateParams { values: vec![3] });
+    assert_eq!(request.id, IdReq::Notification);
+    assert_eq!(request.method, "update");
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LenientMethod {
+    Subtract,
+    Sum,
+    #[serde(skip_deserializing)]
+    Other(String),
+}
+
+impl MethodFallback for LenientMethod {
+    fn unknown(name: String) -> LenientMethod {
+        LenientMethod::Other(name)
+    }
+}
+
+#[test]
+fn test_parse_request_lenient() {
+    let json = r#"{"jsonrpc": "2.0", "method": "sum", "params": [1, 2], "id": 1}"#;
+    let request = parse_request_lenient::<LenientMethod>(json).unwrap();
+    assert_eq!(request.method, LenientMethod::Sum);
+    assert_eq!(request.params, Some(Value::from(vec![1, 2])));
+
+    let json = r#"{"jsonrpc": "2.0", "method": "foo.get", "params": {"name": "myself"}}"#;
+    let request = parse_request_lenient::<LenientMethod>(json).unwrap();
+    assert_eq!(request.method, LenientMethod::Other("foo.get".into()));
+    assert_eq!(request.id, IdReq::Notification);
+
+    // the fallback can't be reached through the wire format
+    let json = r#"{"jsonrpc": "2.0", "method": {"other": "subtract"}, "id": 3}"#;
+    let error = parse_request_lenient::<LenientMethod>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::InvalidRequest);
+    assert_eq!(error.id, Id::from(3));
+
+    // strict parsing is unchanged
+    let json = r#"{"jsonrpc": "2.0", "method": "foo.get", "id": 4}"#;
+    let error = parse_request::<LenientMethod>(json).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+}
+
+#[test]
+fn test_decode_response_empty() {
+    for input in &["", "\n  "] {
+        let error = decode_response::<GetPet>(input).unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::ParseError);
+        assert_eq!(error.error.message, "empty response body");
+        assert_eq!(error.error.data, Some(serde_json::json!({"kind": "empty"})));
+    }
+}
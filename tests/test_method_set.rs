@@ -0,0 +1,76 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use jrpc::*;
+
+fn methods() -> MethodSet {
+    vec!["subtract", "sum", "get_data", "notify_hello", "update"].into_iter().collect()
+}
+
+#[test]
+fn test_membership() {
+    let mut methods = methods();
+    assert_eq!(methods.len(), 5);
+    assert!(methods.contains("sum"));
+    assert!(!methods.contains("Sum"));
+    assert!(!methods.insert("sum"));
+    assert!(methods.insert("multiply"));
+    assert!(methods.remove("update"));
+    assert_eq!(
+        methods.names().collect::<Vec<_>>(),
+        vec!["get_data", "multiply", "notify_hello", "subtract", "sum"]
+    );
+    assert!(MethodSet::new().is_empty());
+}
+
+#[test]
+fn test_suggestions() {
+    let methods = methods();
+    assert_eq!(methods.suggestions("subtract"), vec!["subtract"]);
+    assert_eq!(methods.suggestions("substract"), vec!["subtract"]);
+    assert_eq!(methods.suggestions("sub"), vec!["sum"]);
+    assert_eq!(methods.suggestions("getdata"), vec!["get_data"]);
+    assert_eq!(methods.suggestions("updat"), vec!["update"]);
+    // at most a third of the length is edited
+    assert!(methods.suggestions("divide").is_empty());
+    assert!(methods.suggestions("").is_empty());
+    assert_eq!(methods.suggestions("x"), Vec::<&str>::new());
+
+    // closest first, then by name
+    let methods: MethodSet = vec!["get_b", "get_ab", "get_a"].into_iter().collect();
+    assert_eq!(methods.suggestions("get_ab"), vec!["get_ab", "get_a", "get_b"]);
+    assert_eq!(methods.suggestions("get_c"), vec!["get_a", "get_b"]);
+}
+
+#[test]
+fn test_method_not_found() {
+    let error = methods().method_not_found(Id::from("a"), "substract");
+    assert_eq!(error.id, Id::from("a"));
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+    assert_eq!(error.error.message, "method `substract` not found, did you mean `subtract`?");
+    assert_eq!(
+        error.error.data,
+        Some(json!({
+            "methods": ["get_data", "notify_hello", "subtract", "sum", "update"],
+            "suggestions": ["subtract"],
+        }))
+    );
+
+    let error = methods().method_not_found(Id::Null, "divide");
+    assert_eq!(error.error.message, "method `divide` not found");
+    assert_eq!(
+        error.error.data,
+        Some(json!({"methods": ["get_data", "notify_hello", "subtract", "sum", "update"]}))
+    );
+
+    let error = methods().max_listed(2).suggest(false).method_not_found(Id::from(1), "sub");
+    assert_eq!(error.error.message, "method `sub` not found");
+    assert_eq!(
+        error.error.data,
+        Some(json!({"methods": ["get_data", "notify_hello"], "omitted": 3}))
+    );
+
+    let error = MethodSet::new().method_not_found(Id::from(1), "sub");
+    assert_eq!(error.error.data, Some(json!({"methods": []})));
+}
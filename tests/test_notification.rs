@@ -18,9 +18,8 @@ fn test_notification_id() {
       "params":[1,2,3]
     }
     "#;
-    let json = json.replace("\n", "").replace(" ", "");
     let result = serde_json::to_string(&request).unwrap();
-    assert_eq!(json, result);
+    testing::assert_json_bytes_eq(&result, json);
 }
 
 #[test]
@@ -39,7 +38,6 @@ fn test_request_id() {
       "id": 7
     }
     "#;
-    let json = json.replace("\n", "").replace(" ", "");
     let result = serde_json::to_string(&request).unwrap();
-    assert_eq!(json, result);
+    testing::assert_json_bytes_eq(&result, json);
 }
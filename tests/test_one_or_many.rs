@@ -0,0 +1,53 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_one_or_many_round_trip() {
+    let json = r#"{"jsonrpc":"2.0","method":"update","params":[1]}"#;
+    let one: OneOrMany<Request<String, Value>> = serde_json::from_str(json).unwrap();
+    match one {
+        OneOrMany::One(ref request) => assert_eq!(request.method, "update"),
+        OneOrMany::Many(_) => panic!("expected a single Request"),
+    }
+    assert_eq!(serde_json::to_string(&one).unwrap(), json);
+
+    let json = r#"[{"jsonrpc":"2.0","method":"update","params":[1]}]"#;
+    let many: OneOrMany<Request<String, Value>> = serde_json::from_str(json).unwrap();
+    assert_eq!(many.len(), 1);
+    assert_eq!(serde_json::to_string(&many).unwrap(), json);
+
+    let empty: OneOrMany<u32> = serde_json::from_str("[]").unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(serde_json::to_string(&empty).unwrap(), "[]");
+}
+
+#[test]
+fn test_one_or_many_error_data() {
+    let data: OneOrMany<String> = serde_json::from_str(r#""busy""#).unwrap();
+    assert_eq!(data, OneOrMany::One("busy".to_string()));
+    assert_eq!(data.iter().collect::<Vec<_>>(), vec!["busy"]);
+    assert_eq!(data.into_vec(), vec!["busy".to_string()]);
+
+    let data: OneOrMany<String> = serde_json::from_str(r#"["busy", "retry"]"#).unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(
+        data.into_vec(),
+        vec!["busy".to_string(), "retry".to_string()]
+    );
+
+    assert!(serde_json::from_str::<OneOrMany<String>>("1").is_err());
+    assert!(serde_json::from_str::<OneOrMany<String>>("[1]").is_err());
+}
+
+#[test]
+fn test_one_or_many_from_value() {
+    let value: Value = serde_json::from_str(r#"[{"a": 1}, 2]"#).unwrap();
+    assert_eq!(OneOrMany::from(value).len(), 2);
+    let value: Value = serde_json::from_str(r#"{"a": [1, 2]}"#).unwrap();
+    match OneOrMany::from(value) {
+        OneOrMany::One(value) => assert_eq!(value["a"][1], 2),
+        OneOrMany::Many(_) => panic!("expected a single value"),
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "openrpc")]
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::openrpc::*;
+use jrpc::*;
+
+const PETSTORE: &str = include_str!("data/openrpc_petstore.json");
+
+#[test]
+fn test_petstore_roundtrip() {
+    let document: OpenRpcDocument = serde_json::from_str(PETSTORE).unwrap();
+    assert_eq!(document.openrpc, "1.2.1");
+    assert_eq!(document.info.title, "Petstore");
+    assert_eq!(document.methods.len(), 3);
+
+    let list_pets = document.methods[0].as_item().unwrap();
+    assert_eq!(list_pets.name, "list_pets");
+    let errors = list_pets.errors.as_ref().unwrap();
+    assert_eq!(
+        errors[0].as_item().unwrap().code,
+        ErrorCode::ServerError(100)
+    );
+
+    let create_pet = document.methods[1].as_item().unwrap();
+    assert_eq!(
+        create_pet.result.as_ref().unwrap().as_reference(),
+        Some("#/components/contentDescriptors/PetId")
+    );
+
+    let expected: Value = serde_json::from_str(PETSTORE).unwrap();
+    let result = serde_json::to_value(&document).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn test_extensions_roundtrip() {
+    let json = r#"
+    {
+        "openrpc": "1.2.6",
+        "x-generated-by": "hand",
+        "info": {"title": "t", "version": "1", "x-internal": true},
+        "methods": [
+            {"name": "ping", "params": [], "paramStructure": "by-name", "x-rate-limit": 5}
+        ]
+    }
+    "#;
+    let document: OpenRpcDocument = serde_json::from_str(json).unwrap();
+    assert_eq!(document.extensions["x-generated-by"], Value::from("hand"));
+    assert_eq!(document.info.extensions["x-internal"], Value::from(true));
+    let ping = document.methods[0].as_item().unwrap();
+    assert_eq!(ping.param_structure, Some(ParamStructure::ByName));
+    assert_eq!(ping.result, None);
+
+    let expected: Value = serde_json::from_str(json).unwrap();
+    assert_eq!(expected, serde_json::to_value(&document).unwrap());
+}
+
+#[test]
+fn test_discover_request() {
+    let request = Request::new(Id::from(1), RPC_DISCOVER.to_string());
+    assert_eq!(request.method, "rpc.discover");
+}
@@ -0,0 +1,37 @@
+//! The prelude alone is enough for the example of the README.
+extern crate jrpc;
+
+use jrpc::prelude::*;
+
+#[test]
+fn test_prelude_readme_example() {
+    // Server
+    let mut router = Router::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+
+    // Client
+    let mut client = ClientState::new();
+    let (id, json) = client.start_call("subtract", Some((42, 23)), ()).unwrap();
+
+    let reply = router.handle(&(), &json).unwrap();
+    let ((), result) = client.complete_typed::<i64>(&reply).unwrap();
+    assert_eq!(result.unwrap(), 19);
+    assert_eq!(id, Id::from(1));
+}
+
+#[test]
+fn test_prelude_types() {
+    let request: Request<String, Value> =
+        parse_request(r#"{"jsonrpc": "2.0", "method": "foo", "id": null}"#).unwrap();
+    assert_eq!(request.id, IdReq::Null);
+
+    let error: Error<Value> = Error::new(Id::Null, ErrorCode::ParseError, "parse error", None);
+    let response: Response<Value> = Response::Err(error);
+    match response {
+        Response::Err(Error {
+            error: ErrorObject { code, .. },
+            ..
+        }) => assert_eq!(code, ErrorCode::ParseError),
+        Response::Ok(Success { .. }) => unreachable!(),
+    }
+}
@@ -0,0 +1,45 @@
+//! The members of `Value` params keep their order with the `preserve_order` feature.
+#![cfg(feature = "preserve_order")]
+
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_preserve_order_round_trip() {
+    for json in &[
+        r#"{"jsonrpc":"2.0","method":"subtract","params":{"subtrahend":23,"minuend":42},"id":1}"#,
+        r#"{"jsonrpc":"2.0","method":"update","params":{"z":1,"a":{"y":2,"b":3},"m":[{"c":4,"a":5}]}}"#,
+    ] {
+        let request: Request<String, Value> = parse_request(json).unwrap();
+        assert_eq!(request.to_string(), *json);
+    }
+}
+
+#[test]
+fn test_preserve_order_cache_key() {
+    // the cache key stays canonical whatever the order of the members
+    let a: Request<String, Value> =
+        parse_request(r#"{"jsonrpc":"2.0","method":"get","params":{"b":1,"a":2}}"#).unwrap();
+    let b: Request<String, Value> =
+        parse_request(r#"{"jsonrpc":"2.0","method":"get","params":{"a":2,"b":1}}"#).unwrap();
+    assert_ne!(a.to_string(), b.to_string());
+    assert_eq!(a.cache_key(), b.cache_key());
+}
+
+#[test]
+fn test_preserve_order_error_details() {
+    // the details are in the order they were first set
+    let error = ErrorObject::builder(ErrorCode::InvalidParams)
+        .detail("field", "minuend")
+        .detail("hint", "a number")
+        .detail("expected", "i64")
+        .detail("field", "subtrahend")
+        .build()
+        .unwrap();
+    assert_eq!(
+        error.data.unwrap().to_string(),
+        r#"{"field":"subtrahend","hint":"a number","expected":"i64"}"#
+    );
+}
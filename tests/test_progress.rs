@@ -0,0 +1,74 @@
+//! Progress Notifications reporting on long-running work.
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::progress::{self, ProgressParams, ProgressToken};
+use jrpc::*;
+
+fn round_trip(
+    notification: &Request<&'static str, ProgressParams<Value>>,
+) -> Request<String, Value> {
+    parse_request::<String>(&serde_json::to_string(notification).unwrap()).unwrap()
+}
+
+#[test]
+fn test_progress_int_token() {
+    let value = serde_json::json!({"kind": "begin", "title": "Indexing"});
+    let request = round_trip(&progress::notification(
+        ProgressToken::from(42),
+        value.clone(),
+    ));
+    assert_eq!(request.method, "$/progress");
+    assert_eq!(request.id, IdReq::Notification);
+
+    let params = progress::decode(&request, progress::METHOD)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        params,
+        ProgressParams {
+            token: ProgressToken::Int(42),
+            value,
+        }
+    );
+}
+
+#[test]
+fn test_progress_string_token() {
+    let value = serde_json::json!({"kind": "end"});
+    let notification =
+        progress::notification_of("window/progress", ProgressToken::from("abc"), value.clone());
+    let request = round_trip(&notification);
+    assert!(progress::decode(&request, progress::METHOD).is_none());
+
+    let params = progress::decode(&request, "window/progress")
+        .unwrap()
+        .unwrap();
+    assert_eq!(params.token, ProgressToken::String("abc".into()));
+    assert_eq!(params.value, value);
+}
+
+#[test]
+fn test_progress_ignores_other_methods() {
+    for json in &[
+        r#"{"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {"token": 1, "value": 2}}"#,
+        r#"{"jsonrpc": "2.0", "method": "$/progress", "params": {"token": 1, "value": 2}, "id": 1}"#,
+    ] {
+        let request = parse_request::<String>(json).unwrap();
+        assert!(
+            progress::decode(&request, progress::METHOD).is_none(),
+            "{}",
+            json
+        );
+    }
+
+    let json =
+        r#"{"jsonrpc": "2.0", "method": "$/progress", "params": {"token": 1.5, "value": 2}}"#;
+    let request = parse_request::<String>(json).unwrap();
+    assert!(
+        progress::decode(&request, progress::METHOD)
+            .unwrap()
+            .is_err(),
+        "not a token"
+    );
+}
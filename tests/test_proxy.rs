@@ -0,0 +1,116 @@
+//! Rewriting the ids of the Requests forwarded by a proxy.
+extern crate jrpc;
+extern crate serde_json;
+
+use std::collections::HashMap;
+
+use jrpc::proxy::IdMapper;
+use jrpc::*;
+
+fn request(json: &str) -> Request<String, Value> {
+    parse_request(json).unwrap()
+}
+
+fn response(json: &str) -> Response<Value> {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_proxy_two_clients_same_id() {
+    let mut mapper = IdMapper::new();
+    // The proxy remembers which Client each upstream id belongs to.
+    let mut clients = HashMap::new();
+
+    let from_a =
+        request(r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#);
+    let from_b =
+        request(r#"{"jsonrpc": "2.0", "method": "subtract", "params": [23, 42], "id": 1}"#);
+    let upstream_a = mapper.map_request(from_a);
+    let upstream_b = mapper.map_request(from_b);
+    assert_ne!(upstream_a.id, upstream_b.id);
+    clients.insert(upstream_a.id.as_id().unwrap(), "a");
+    clients.insert(upstream_b.id.as_id().unwrap(), "b");
+    assert_eq!(mapper.len(), 2);
+
+    // The upstream Server replies out of order.
+    let reply_b: Response<Value> =
+        Response::success(upstream_b.id.into_id().unwrap(), Value::from(-19));
+    let reply_a: Response<Value> =
+        Response::success(upstream_a.id.into_id().unwrap(), Value::from(19));
+    for (reply, (client, result)) in vec![reply_b, reply_a]
+        .into_iter()
+        .zip(vec![("b", -19), ("a", 19)])
+    {
+        assert_eq!(clients[reply.id()], client);
+        let reply = mapper.map_response(reply).unwrap();
+        assert_eq!(
+            reply.to_string(),
+            Response::success(Id::from(1), Value::from(result)).to_string()
+        );
+    }
+    assert!(mapper.is_empty());
+}
+
+#[test]
+fn test_proxy_notification_untouched() {
+    let mut mapper = IdMapper::new();
+    let notification = request(r#"{"jsonrpc": "2.0", "method": "update", "params": [1]}"#);
+    assert_eq!(mapper.map_request(notification).id, IdReq::Notification);
+    assert!(mapper.is_empty());
+}
+
+#[test]
+fn test_proxy_unknown_response() {
+    let mut mapper = IdMapper::new();
+    mapper.map_outbound(IdReq::from(Id::from("abc".to_string())));
+
+    let parse_error =
+        response(r#"{"jsonrpc": "2.0", "error": {"code": -32700, "message": "x"}, "id": null}"#);
+    assert!(mapper.map_response(parse_error).is_none());
+    assert!(mapper
+        .map_response(response(r#"{"jsonrpc": "2.0", "result": 1, "id": 2}"#))
+        .is_none());
+
+    let error =
+        response(r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "x"}, "id": 1}"#);
+    match mapper.map_response(error) {
+        Some(Response::Err(error)) => assert_eq!(error.id, Id::from("abc".to_string())),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_proxy_batches() {
+    let mut mapper = IdMapper::new();
+    let batch: Vec<Request<String, Value>> = serde_json::from_str(
+        r#"[
+            {"jsonrpc": "2.0", "method": "sum", "params": [1, 2], "id": "1"},
+            {"jsonrpc": "2.0", "method": "notify_hello", "params": [7]},
+            {"jsonrpc": "2.0", "method": "sum", "params": [3, 4], "id": 1}
+        ]"#,
+    )
+    .unwrap();
+    let upstream = mapper.map_requests(batch);
+    let ids: Vec<IdReq> = upstream.iter().map(|r| r.id.clone()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            IdReq::from(Id::from(1)),
+            IdReq::Notification,
+            IdReq::from(Id::from(2))
+        ],
+    );
+
+    let replies = vec![
+        response(r#"{"jsonrpc": "2.0", "result": 7, "id": 2}"#),
+        response(r#"{"jsonrpc": "2.0", "result": 0, "id": 3}"#),
+        response(r#"{"jsonrpc": "2.0", "result": 3, "id": 1}"#),
+    ];
+    let originals: Vec<Id> = mapper
+        .map_responses(replies)
+        .iter()
+        .map(|r| r.id().clone())
+        .collect();
+    assert_eq!(originals, vec![Id::from(1), Id::from("1".to_string())]);
+    assert!(mapper.is_empty());
+}
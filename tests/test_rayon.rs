@@ -0,0 +1,106 @@
+//! Handle the elements of a batch in parallel with `handle_batch_parallel`.
+#![cfg(feature = "rayon")]
+
+extern crate jrpc;
+extern crate serde_json;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use jrpc::router::{BatchOrder, Router};
+use jrpc::*;
+
+/// A batch of 100 Requests to `slow`, every 10th of which is a Notification.
+fn slow_batch() -> String {
+    let batch: Vec<Value> = (0..100)
+        .map(|i| {
+            let mut request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "slow",
+                "params": [i],
+            });
+            if i % 10 != 0 {
+                request["id"] = Value::from(i);
+            }
+            request
+        })
+        .collect();
+    serde_json::to_string(&batch).unwrap()
+}
+
+/// A Router whose `slow` method sleeps longer for the earlier Requests, so that they complete
+/// roughly in reverse order.
+fn slow_router() -> Router<AtomicUsize> {
+    let mut router = Router::new();
+    router.register("slow", |calls: &AtomicUsize, (i,): (u64,)| {
+        thread::sleep(Duration::from_millis((100 - i) / 5));
+        calls.fetch_add(1, Ordering::SeqCst);
+        Ok(i)
+    });
+    router
+}
+
+fn response_ids(json: &str) -> Vec<u64> {
+    let responses: Vec<Response<Value>> = serde_json::from_str(json).unwrap();
+    responses
+        .iter()
+        .map(|response| match *response {
+            Response::Ok(ref success) => {
+                assert_eq!(serde_json::to_value(&success.id).unwrap(), success.result);
+                match success.id {
+                    Id::Int(i) => i as u64,
+                    ref id => panic!("unexpected id {:?}", id),
+                }
+            }
+            Response::Err(ref error) => panic!("unexpected error {:?}", error),
+        })
+        .collect()
+}
+
+fn expected_ids() -> Vec<u64> {
+    (0..100).filter(|i| i % 10 != 0).collect()
+}
+
+#[test]
+fn test_parallel_request_order() {
+    let calls = AtomicUsize::new(0);
+    let json = slow_router()
+        .handle_batch_parallel(&calls, &slow_batch(), BatchOrder::Request)
+        .unwrap();
+    assert_eq!(response_ids(&json), expected_ids());
+    assert_eq!(calls.load(Ordering::SeqCst), 100, "notifications are executed");
+}
+
+#[test]
+fn test_parallel_completion_order() {
+    let calls = AtomicUsize::new(0);
+    let json = slow_router()
+        .handle_batch_parallel(&calls, &slow_batch(), BatchOrder::Completion)
+        .unwrap();
+    let mut ids = response_ids(&json);
+    ids.sort();
+    assert_eq!(ids, expected_ids());
+    assert_eq!(calls.load(Ordering::SeqCst), 100, "notifications are executed");
+}
+
+#[test]
+fn test_parallel_same_as_sequential() {
+    let router = slow_router();
+    let calls = AtomicUsize::new(0);
+    for json in &[
+        "[]",
+        "[1]",
+        r#"{"jsonrpc": "2.0", "method": "slow", "params": [99], "id": 1}"#,
+        r#"[{"jsonrpc": "2.0", "method": "slow", "params": [99]}]"#,
+        r#"[{"jsonrpc": "2.0", "method": "foo", "id": 1}, 1]"#,
+        "[",
+    ] {
+        assert_eq!(
+            router.handle_batch_parallel(&calls, json, BatchOrder::Request),
+            router.handle_batch(&calls, json),
+            "{}",
+            json,
+        );
+    }
+}
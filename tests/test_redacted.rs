@@ -0,0 +1,59 @@
+//! Hiding sensitive params from `Debug` output with `Redacted`.
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::*;
+
+const SECRET: &str = "s3cr3t-t0k3n";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AuthParams {
+    user: String,
+    token: Redacted<String>,
+    scopes: Vec<Redacted<u64>>,
+}
+
+fn params() -> AuthParams {
+    AuthParams {
+        user: "alice".into(),
+        token: Redacted(SECRET.into()),
+        scopes: vec![Redacted(42)],
+    }
+}
+
+#[test]
+fn test_redacted_formatting() {
+    let request = Request::with_params(Id::from(1), "auth".to_string(), params());
+    for formatted in &[
+        format!("{:?}", request),
+        format!("{:#?}", request),
+        format!("{:?}", request.params),
+        format!("{}", request.params.as_ref().unwrap().token),
+    ] {
+        assert!(!formatted.contains(SECRET), "{}", formatted);
+        assert!(!formatted.contains("42"), "{}", formatted);
+    }
+    assert!(format!("{:?}", request).contains(r#"token: <redacted>, scopes: [<redacted>]"#));
+}
+
+#[test]
+fn test_redacted_serializes_transparently() {
+    let request = Request::with_params(Id::from(1), "auth".to_string(), params());
+    let json = serde_json::to_string(&request).unwrap();
+    jrpc::testing::assert_json_eq(
+        &json,
+        r#"{
+            "jsonrpc": "2.0",
+            "method": "auth",
+            "params": {"user": "alice", "token": "s3cr3t-t0k3n", "scopes": [42]},
+            "id": 1
+        }"#,
+    );
+
+    let request = parse_request::<String>(&json).unwrap();
+    let params: AuthParams = serde_json::from_value(request.params.unwrap()).unwrap();
+    assert_eq!(params.token.into_inner(), SECRET);
+    assert_eq!(params.scopes[0].0, 42);
+}
@@ -0,0 +1,30 @@
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_static_responses() {
+    let cases = vec![
+        (responses::PARSE_ERROR_JSON, responses::parse_error(), ErrorCode::ParseError),
+        (responses::INVALID_REQUEST_JSON, responses::invalid_request(), ErrorCode::InvalidRequest),
+    ];
+    for (json, error, code) in cases {
+        assert_eq!(error.id, Id::Null);
+        assert_eq!(error.error.code, code);
+        assert_eq!(error.error.message, code.standard_message());
+        assert_eq!(error.error.data, None);
+
+        // the constant is exactly the serialized error and parses back into it
+        assert_eq!(error.to_string(), json);
+        let parsed: Error<Value> = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.id, error.id);
+        assert_eq!(parsed.error.code, error.error.code);
+        assert_eq!(parsed.error.message, error.error.message);
+        assert_eq!(parsed.error.data, error.error.data);
+        match parse_response_exact::<Value>(json).unwrap() {
+            Response::Err(parsed) => assert_eq!(parsed.to_string(), json),
+            Response::Ok(_) => panic!("expected an error"),
+        }
+    }
+}
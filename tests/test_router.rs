@@ -0,0 +1,531 @@
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use jrpc::router::{Middleware, Router};
+use jrpc::*;
+
+#[derive(Default)]
+struct Ctx {
+    updates: AtomicUsize,
+}
+
+#[derive(Deserialize)]
+struct SubtractParams {
+    minuend: i64,
+    subtrahend: i64,
+}
+
+fn router() -> Router<Ctx> {
+    let mut router = Router::new();
+    router
+        .register("subtract", |_: &Ctx, p: SubtractParams| {
+            Ok(Value::from(p.minuend - p.subtrahend))
+        })
+        .register("update", |ctx: &Ctx, _: Vec<i64>| {
+            ctx.updates.fetch_add(1, Ordering::SeqCst);
+            Ok(Value::Null)
+        })
+        .register_fn("get_data", |(): ()| Ok(("hello", 5)))
+        .register("fail", |_: &Ctx, (): ()| -> Result<(), _> {
+            Err(ErrorObject {
+                code: ErrorCode::ServerError(-32000),
+                message: "failed".into(),
+                data: Some(Value::from(42)),
+            })
+        });
+    router
+}
+
+fn assert_error(reply: Option<String>, code: ErrorCode, id: Id) {
+    let response: Response<Value> = serde_json::from_str(&reply.expect("a reply")).unwrap();
+    match response {
+        Response::Err(error) => {
+            assert_eq!(error.error.code, code);
+            assert_eq!(error.id, id);
+        }
+        Response::Ok(success) => panic!("expected an error, got {:?}", success),
+    }
+}
+
+#[test]
+fn test_success() {
+    let router = router();
+    let ctx = Ctx::default();
+    let json = r#"{
+        "jsonrpc": "2.0",
+        "method": "subtract",
+        "params": {"minuend": 42, "subtrahend": 23},
+        "id": 3
+    }"#;
+    let reply = router.handle(&ctx, json);
+    testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 19, "id": 3}"#,
+    );
+
+    let json = r#"{"jsonrpc": "2.0", "method": "get_data", "id": "9"}"#;
+    let reply = router.handle(&ctx, json);
+    testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "result": ["hello", 5], "id": "9"}"#,
+    );
+}
+
+#[test]
+fn test_notification() {
+    let router = router();
+    let ctx = Ctx::default();
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": [1, 2, 3]}"#;
+    assert_eq!(router.handle(&ctx, json), None);
+    assert_eq!(ctx.updates.load(Ordering::SeqCst), 1);
+
+    // errors of notifications are not replied either
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar"}"#;
+    assert_eq!(router.handle(&ctx, json), None);
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": {"a": 1}}"#;
+    assert_eq!(router.handle(&ctx, json), None);
+    assert_eq!(ctx.updates.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_parse_error() {
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar, "params": "bar", "baz]"#;
+    let reply = router().handle(&Ctx::default(), json);
+    assert_error(reply, ErrorCode::ParseError, Id::Null);
+}
+
+#[test]
+fn test_invalid_request() {
+    let json = r#"{"jsonrpc": "1.0", "method": "subtract", "id": 1}"#;
+    let reply = router().handle(&Ctx::default(), json);
+    assert_error(reply, ErrorCode::InvalidRequest, Id::from(1));
+}
+
+#[test]
+fn test_long_method_not_found() {
+    let json = format!(
+        r#"{{"jsonrpc": "2.0", "method": "{}", "id": 1}}"#,
+        "x".repeat(1 << 20)
+    );
+    let reply = router().handle(&Ctx::default(), &json).expect("a reply");
+    assert!(reply.len() < 512, "{} bytes", reply.len());
+    assert_error(Some(reply), ErrorCode::MethodNotFound, Id::from(1));
+
+    let reply = router().handle_batch(&Ctx::default(), &format!("[{}]", json));
+    assert!(reply.expect("a reply").len() < 512);
+}
+
+#[test]
+fn test_method_not_found() {
+    let router = router();
+    let ctx = Ctx::default();
+
+    let reply = router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": "foobar", "id": "1"}"#);
+    assert_error(reply, ErrorCode::MethodNotFound, Id::from("1"));
+
+    let reply = router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": 1, "id": 2}"#);
+    assert_error(reply, ErrorCode::InvalidRequest, Id::from(2));
+
+    assert!(router.has_method("subtract"));
+    assert!(!router.has_method("foobar"));
+}
+
+#[test]
+fn test_invalid_params() {
+    let router = router();
+    let ctx = Ctx::default();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"minuend": "42"}, "id": 1}"#;
+    let reply = router.handle(&ctx, json);
+    assert_error(reply, ErrorCode::InvalidParams, Id::from(1));
+
+    // missing params
+    let reply = router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": "subtract", "id": 2}"#);
+    assert_error(reply, ErrorCode::InvalidParams, Id::from(2));
+}
+
+#[test]
+fn test_handler_error() {
+    let json = r#"{"jsonrpc": "2.0", "method": "fail", "id": null}"#;
+    let reply = router().handle(&Ctx::default(), json);
+    testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32000, "message": "failed", "data": 42}, "id": null}"#,
+    );
+}
+
+/// Records the hooks it is called with in the ctx.
+struct Record {
+    name: &'static str,
+    reject: Option<&'static str>,
+}
+
+impl Middleware<Mutex<Vec<String>>> for Record {
+    fn on_request(
+        &self,
+        log: &Mutex<Vec<String>>,
+        request: &Request<String, Value>,
+    ) -> Result<(), ErrorObject<Value>> {
+        log.lock()
+            .unwrap()
+            .push(format!("{} request {}", self.name, request.method));
+        if self.reject == Some(request.method.as_str()) {
+            return Err(ErrorObject {
+                code: ErrorCode::ServerError(-32001),
+                message: format!("rejected by {}", self.name).into(),
+                data: None,
+            });
+        }
+        Ok(())
+    }
+
+    fn on_response(
+        &self,
+        log: &Mutex<Vec<String>>,
+        _: &Request<String, Value>,
+        response: &mut Response<Value>,
+    ) {
+        log.lock().unwrap().push(format!("{} response", self.name));
+        if let Response::Ok(ref mut success) = *response {
+            success.result = Value::from(format!("{}({})", self.name, success.result));
+        }
+    }
+}
+
+fn middleware_router() -> Router<Mutex<Vec<String>>> {
+    let mut router = Router::new()
+        .with_middleware(Record {
+            name: "a",
+            reject: None,
+        })
+        .with_middleware(Record {
+            name: "b",
+            reject: Some("secret"),
+        })
+        .with_middleware(Record {
+            name: "c",
+            reject: None,
+        });
+    router
+        .register("echo", |log: &Mutex<Vec<String>>, value: Value| {
+            log.lock().unwrap().push("handler".into());
+            Ok(value)
+        })
+        .register("secret", |_: &Mutex<Vec<String>>, (): ()| -> Result<(), _> {
+            panic!("the handler must not be called")
+        });
+    router
+}
+
+#[test]
+fn test_middleware_order() {
+    let router = middleware_router();
+    let log = Mutex::new(Vec::new());
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "echo", "params": 1, "id": 1}"#);
+    testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "result": "a(\"b(\\\"c(1)\\\")\")", "id": 1}"#,
+    );
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "a request echo",
+            "b request echo",
+            "c request echo",
+            "handler",
+            "c response",
+            "b response",
+            "a response",
+        ]
+    );
+}
+
+#[test]
+fn test_middleware_short_circuit() {
+    let router = middleware_router();
+    let log = Mutex::new(Vec::new());
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "secret", "id": 2}"#);
+    testing::assert_json_eq(
+        &reply.unwrap(),
+        r#"{"jsonrpc": "2.0", "error": {"code": -32001, "message": "rejected by b", "data": null}, "id": 2}"#,
+    );
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["a request secret", "b request secret", "b response", "a response"]
+    );
+}
+
+#[test]
+fn test_middleware_notification() {
+    let router = middleware_router();
+    let log = Mutex::new(Vec::new());
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "echo", "params": 1}"#);
+    assert_eq!(reply, None);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["a request echo", "b request echo", "c request echo", "handler"]
+    );
+
+    // invalid requests never reach the middleware
+    log.lock().unwrap().clear();
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "echo", "params": 1, "id"#);
+    assert!(reply.is_some());
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[derive(Serialize)]
+struct Pet {
+    name: String,
+    tags: Vec<String>,
+}
+
+fn find_pet(name: String) -> Result<Pet, ErrorObject<Value>> {
+    if name == "fido" {
+        Ok(Pet {
+            name,
+            tags: vec!["dog".into()],
+        })
+    } else {
+        Err(ErrorObject {
+            code: ErrorCode::ServerError(-32004),
+            message: format!("no pet named {}", name).into(),
+            data: None,
+        })
+    }
+}
+
+#[test]
+fn test_typed_handlers() {
+    let mut router = Router::new();
+    router
+        .register_fn("find_pet", find_pet)
+        .register_notification("update", |ctx: &Ctx, _: Vec<u32>| {
+            ctx.updates.fetch_add(1, Ordering::SeqCst);
+        });
+    let ctx = Ctx::default();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "find_pet", "params": "fido", "id": 1}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": {"name": "fido", "tags": ["dog"]}, "id": 1}"#,
+    );
+
+    let json = r#"{"jsonrpc": "2.0", "method": "find_pet", "params": "rex", "id": 2}"#;
+    assert_error(router.handle(&ctx, json), ErrorCode::ServerError(-32004), Id::from(2));
+
+    let json = r#"{"jsonrpc": "2.0", "method": "find_pet", "params": 1, "id": 3}"#;
+    assert_error(router.handle(&ctx, json), ErrorCode::InvalidParams, Id::from(3));
+
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": [1]}"#;
+    assert_eq!(router.handle(&ctx, json), None);
+    assert_eq!(ctx.updates.load(Ordering::SeqCst), 1);
+
+    // called with an id anyway
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": [1], "id": 4}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": null, "id": 4}"#,
+    );
+    assert_eq!(ctx.updates.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_catch_panics() {
+    let mut router = Router::new().catch_panics(true);
+    router
+        .register_fn("index", |i: usize| Ok(vec![1, 2, 3][i]))
+        .register_notification("crash", |ctx: &Ctx, (): ()| {
+            ctx.updates.fetch_add(1, Ordering::SeqCst);
+            std::panic::panic_any(String::from("crashed"))
+        });
+    let ctx = Ctx::default();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "index", "params": 1, "id": 1}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 2, "id": 1}"#,
+    );
+
+    let json = r#"{"jsonrpc": "2.0", "method": "index", "params": 7, "id": 2}"#;
+    let response: Response<Value> = serde_json::from_str(&router.handle(&ctx, json).unwrap()).unwrap();
+    match response {
+        Response::Err(error) => {
+            assert_eq!(error.id, Id::from(2));
+            assert_eq!(error.error.code, ErrorCode::InternalError);
+            assert!(
+                error.error.message.starts_with("the handler panicked: index out of bounds"),
+                "{}",
+                error.error.message
+            );
+        }
+        Response::Ok(_) => panic!("expected an error"),
+    }
+
+    let json = r#"{"jsonrpc": "2.0", "method": "crash"}"#;
+    assert_eq!(router.handle(&ctx, json), None);
+    let json = r#"{"jsonrpc": "2.0", "method": "crash", "id": "c"}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc":"2.0","error":{"code":-32603,"message":"the handler panicked: crashed","data":null},"id":"c"}"#,
+    );
+    assert_eq!(ctx.updates.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+#[should_panic(expected = "crashed")]
+fn test_panics_not_caught_by_default() {
+    let mut router = Router::new();
+    router.register_fn("crash", |(): ()| -> Result<(), _> { panic!("crashed") });
+    router.handle(&(), r#"{"jsonrpc": "2.0", "method": "crash", "id": 1}"#);
+}
+
+#[test]
+fn test_case_insensitive() {
+    let json = r#"{"jsonrpc": "2.0", "method": "SUBTRACT", "params": [42, 23], "id": 1}"#;
+
+    // the spec says method names are case-sensitive
+    let router = router();
+    let ctx = Ctx::default();
+    let reply = router.handle(&ctx, json);
+    assert_error(reply, ErrorCode::MethodNotFound, Id::from(1));
+    assert!(!router.has_method("Subtract"));
+
+    let mut router = Router::new().case_insensitive(true);
+    router
+        .register_fn("subtract", |(a, b): (i64, i64)| Ok(a - b))
+        .register_fn("Subtract", |(a, b): (i64, i64)| Ok(b - a));
+    assert!(router.has_method("SubTract"));
+    assert!(!router.has_method("add"));
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": -19, "id": 1}"#,
+    );
+    // an exact match is preferred
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 2}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 19, "id": 2}"#,
+    );
+
+    // the errors show the method as it was sent
+    let reply = router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": "Add", "id": 3}"#);
+    assert!(reply.unwrap().contains("method `Add` not found"));
+}
+
+#[test]
+fn test_aliases() {
+    let aliased = Arc::new(Mutex::new(Vec::new()));
+    let hook_aliased = aliased.clone();
+    let mut router = Router::new()
+        .on_alias(move |alias: &str, canonical: &str| {
+            hook_aliased.lock().unwrap().push(format!("{} -> {}", alias, canonical));
+        })
+        .with_middleware(Record {
+            name: "a",
+            reject: None,
+        });
+    router
+        .register_fn("chain_getBlock", |(n,): (u64,)| Ok(n * 2))
+        .alias("getBlock", "chain_getBlock")
+        .alias("getHeader", "chain_getHeader");
+    let log = Mutex::new(Vec::new());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "params": [4], "id": 1}"#;
+    testing::assert_json_eq(
+        &router.handle(&log, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": "a(8)", "id": 1}"#,
+    );
+    assert_eq!(*aliased.lock().unwrap(), vec!["getBlock -> chain_getBlock"]);
+    // the middleware sees the canonical method
+    assert_eq!(log.lock().unwrap()[0], "a request chain_getBlock");
+
+    // the canonical method still works
+    let json = r#"{"jsonrpc": "2.0", "method": "chain_getBlock", "params": [4], "id": 2}"#;
+    testing::assert_json_eq(
+        &router.handle(&log, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": "a(8)", "id": 2}"#,
+    );
+    assert_eq!(aliased.lock().unwrap().len(), 1);
+
+    // an alias of a method which is not registered
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "getHeader", "id": 3}"#);
+    assert!(reply.unwrap().contains("method `chain_getHeader` not found"));
+    let reply = router.handle(&log, r#"{"jsonrpc": "2.0", "method": "getblock", "id": 4}"#);
+    assert_error(reply, ErrorCode::MethodNotFound, Id::from(4));
+}
+
+#[test]
+fn test_deprecated() {
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let hook_called = called.clone();
+    let mut router = Router::new().on_deprecated(move |method: &str, note: &str| {
+        hook_called.lock().unwrap().push(format!("{}: {}", method, note));
+    });
+    router
+        .register_fn("chain_getBlock", |(): ()| Ok(1))
+        .register_fn("getBlock", |(): ()| Ok(0))
+        .deprecate("getBlock", "use chain_getBlock instead")
+        .deprecate("getHeader", "use chain_getHeader instead")
+        .alias("block", "getBlock");
+    assert_eq!(router.deprecation("getBlock"), Some("use chain_getBlock instead"));
+    assert_eq!(router.deprecation("chain_getBlock"), None);
+    let ctx = Ctx::default();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "chain_getBlock", "id": 1}"#;
+    assert!(router.handle(&ctx, json).is_some());
+    // an unknown method is not called
+    let json = r#"{"jsonrpc": "2.0", "method": "getHeader", "id": 2}"#;
+    assert_error(router.handle(&ctx, json), ErrorCode::MethodNotFound, Id::from(2));
+    assert!(called.lock().unwrap().is_empty());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "getBlock", "id": 3}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 0, "id": 3}"#,
+    );
+    // notifications and aliases call it too
+    assert!(router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": "getBlock"}"#).is_none());
+    assert!(router.handle(&ctx, r#"{"jsonrpc": "2.0", "method": "block", "id": 4}"#).is_some());
+    assert_eq!(*called.lock().unwrap(), vec!["getBlock: use chain_getBlock instead"; 3]);
+}
+
+#[test]
+fn test_case_insensitive_deprecated() {
+    let called = Arc::new(Mutex::new(Vec::new()));
+    let hook_called = called.clone();
+    let aliased = Arc::new(Mutex::new(Vec::new()));
+    let hook_aliased = aliased.clone();
+    let mut router = Router::new()
+        .case_insensitive(true)
+        .on_deprecated(move |method: &str, note: &str| {
+            hook_called.lock().unwrap().push(format!("{}: {}", method, note));
+        })
+        .on_alias(move |alias: &str, canonical: &str| {
+            hook_aliased.lock().unwrap().push(format!("{} -> {}", alias, canonical));
+        });
+    router
+        .register_fn("subtract", |(a, b): (i64, i64)| Ok(a - b))
+        .deprecate("subtract", "use sub instead")
+        .alias("minus", "subtract");
+    let ctx = Ctx::default();
+
+    let json = r#"{"jsonrpc": "2.0", "method": "Subtract", "params": [42, 23], "id": 1}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#,
+    );
+    assert_eq!(*called.lock().unwrap(), vec!["subtract: use sub instead"]);
+
+    let json = r#"{"jsonrpc": "2.0", "method": "MINUS", "params": [42, 23], "id": 2}"#;
+    testing::assert_json_eq(
+        &router.handle(&ctx, json).unwrap(),
+        r#"{"jsonrpc": "2.0", "result": 19, "id": 2}"#,
+    );
+    assert_eq!(*aliased.lock().unwrap(), vec!["minus -> subtract"]);
+    assert_eq!(called.lock().unwrap().len(), 2);
+}
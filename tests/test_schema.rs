@@ -0,0 +1,60 @@
+#![cfg(feature = "schemars")]
+extern crate jrpc;
+extern crate jsonschema;
+extern crate schemars;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_request_schema_snapshot() {
+    let schema = schemars::schema_for!(Request<String, Value>);
+    let result = serde_json::to_value(&schema).unwrap();
+    let expected: Value =
+        serde_json::from_str(include_str!("snapshots/request_schema.json")).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn test_request_schema_validate() {
+    let schema = serde_json::to_value(schemars::schema_for!(Request<String, Value>)).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    let request = Request::with_params(Id::from(4), "CreateFoo".to_string(), vec![1, 2, 3]);
+    assert!(validator.is_valid(&serde_json::to_value(&request).unwrap()));
+
+    let notification = Request::with_params(
+        IdReq::Notification,
+        "NotifyFoo".to_string(),
+        Value::Null,
+    );
+    assert!(validator.is_valid(&serde_json::to_value(&notification).unwrap()));
+
+    let wrong_version: Value = serde_json::from_str(
+        r#"{"jsonrpc": "1.0", "method": "CreateFoo", "id": 4}"#,
+    ).unwrap();
+    assert!(!validator.is_valid(&wrong_version));
+
+    let float_id: Value = serde_json::from_str(
+        r#"{"jsonrpc": "2.0", "method": "CreateFoo", "id": 4.5}"#,
+    ).unwrap();
+    assert!(!validator.is_valid(&float_id));
+}
+
+#[test]
+fn test_response_schema_validate() {
+    let schema = serde_json::to_value(schemars::schema_for!(Response<Value>)).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+
+    let success: Response<Value> = Response::success(Id::from(4), Value::from(19));
+    assert!(validator.is_valid(&serde_json::to_value(&success).unwrap()));
+
+    let error: Response<Value> =
+        Response::error(Id::Null, ErrorCode::ParseError, "Parse error", None);
+    assert!(validator.is_valid(&serde_json::to_value(&error).unwrap()));
+
+    let code_not_int: Value = serde_json::from_str(
+        r#"{"jsonrpc": "2.0", "error": {"code": "oops", "message": "bad"}, "id": 1}"#,
+    ).unwrap();
+    assert!(!validator.is_valid(&code_not_int));
+}
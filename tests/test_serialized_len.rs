@@ -0,0 +1,43 @@
+//! `serialized_len` is the length of `to_string` without allocating it.
+extern crate jrpc;
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_serialized_len_request() {
+    let params = serde_json::json!({
+        "name": "Ünïcödé ✓ 日本語 🦀",
+        "quote": "she said \"hi\"\n\tand left \\ \u{1}",
+    });
+    for request in &[
+        Request::with_params(Id::from(1), "ëcho".to_string(), params.clone()),
+        Request::with_params(IdReq::Notification, "notify".to_string(), params),
+    ] {
+        assert_eq!(request.serialized_len().unwrap(), request.to_string().len());
+    }
+
+    let request = Request::new(Id::from("ключ \"1\""), "get".to_string());
+    assert_eq!(request.serialized_len().unwrap(), request.to_string().len());
+}
+
+#[test]
+fn test_serialized_len_response() {
+    let success = Success::new(Id::from(1), "🦀 \"crab\" \\ ✓".to_string());
+    assert_eq!(success.serialized_len().unwrap(), success.to_string().len());
+
+    let error: Error<Value> = Error::new(
+        Id::Null,
+        ErrorCode::ParseError,
+        "unexpected `é` at line 1\r\n",
+        Some(Value::from("\u{7f}\u{0}日本")),
+    );
+    assert_eq!(error.serialized_len().unwrap(), error.to_string().len());
+
+    for response in &[
+        Response::Ok(Success::new(Id::from(2), Value::from("Ωmega"))),
+        Response::Err(error),
+    ] {
+        assert_eq!(response.serialized_len().unwrap(), response.to_string().len());
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(all(feature = "simd", feature = "test-fixtures"))]
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use jrpc::fixtures::{self, SpecMethod};
+use jrpc::router::{handle_batch, handle_batch_simd};
+use jrpc::*;
+
+/// The parts of a parse result which must not depend on the backend: only the messages of the
+/// `ParseError`s differ.
+fn observable(result: Result<Request<SpecMethod, Value>, Error<Value>>) -> Result<String, Value> {
+    match result {
+        Ok(request) => Ok(request.to_string()),
+        Err(error) => {
+            let mut value = serde_json::to_value(&error).unwrap();
+            if error.error.code == ErrorCode::ParseError {
+                value["error"]["message"] = Value::Null;
+            }
+            Err(value)
+        }
+    }
+}
+
+fn respond(value: Value) -> Option<Response<Value>> {
+    match parse_request_value::<SpecMethod>(value) {
+        Ok(request) => Some(Response::success(request.id.into_id()?, json!(request.method))),
+        Err(error) => Some(Response::Err(error)),
+    }
+}
+
+fn observable_batch(reply: Option<String>) -> Option<Value> {
+    let mut value: Value = serde_json::from_str(&reply?).unwrap();
+    if value["error"]["code"] == json!(-32700) {
+        value["error"]["message"] = Value::Null;
+    }
+    Some(value)
+}
+
+#[test]
+fn test_simd_fixtures() {
+    for fixture in fixtures::ALL {
+        let mut json = fixture.request.as_bytes().to_vec();
+        assert_eq!(
+            observable(parse_request(fixture.request)),
+            observable(parse_request_simd(&mut json)),
+            "{}",
+            fixture.name
+        );
+
+        let mut json = fixture.request.as_bytes().to_vec();
+        assert_eq!(
+            observable_batch(handle_batch(respond, fixture.request)),
+            observable_batch(handle_batch_simd(respond, &mut json)),
+            "{}",
+            fixture.name
+        );
+    }
+}
+
+#[test]
+fn test_simd_bom_and_empty() {
+    let json = "\u{feff}{\"jsonrpc\": \"2.0\", \"method\": \"subtract\", \"id\": 1}";
+    let request = parse_request_simd::<String>(&mut json.as_bytes().to_vec()).unwrap();
+    assert_eq!(request.method, "subtract");
+
+    for input in &["", " \n", "\u{feff}"] {
+        let error = parse_request_simd::<String>(&mut input.as_bytes().to_vec()).unwrap_err();
+        assert_eq!(
+            observable(Err(error)),
+            observable(parse_request(input))
+        );
+    }
+}
+
+#[test]
+fn test_simd_invalid_utf8() {
+    let error = parse_request_simd::<String>(&mut b"\"\xff\"".to_vec()).unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+    assert_eq!(error.id, Id::Null);
+}
+
+#[test]
+fn test_simd_max_depth() {
+    let nested = |depth: usize| format!("{}1{}", "[".repeat(depth), "]".repeat(depth));
+    let inputs = vec![
+        format!(r#"{{"jsonrpc": "2.0", "method": "sum", "params": {}, "id": 1}}"#, nested(5000)),
+        format!(r#"{{"jsonrpc": "2.0", "method": "sum", "params": {}, "id": 1}}"#, nested(500_000)),
+        format!("[{}", nested(2000)),
+    ];
+    for input in &inputs {
+        let error = parse_request::<SpecMethod>(input).unwrap_err();
+        assert_eq!(error.error.data.as_ref().unwrap()["kind"], "depth");
+        let simd = parse_request_simd::<SpecMethod>(&mut input.as_bytes().to_vec()).unwrap_err();
+        assert_eq!(simd.to_string(), error.to_string());
+
+        let mut json = input.as_bytes().to_vec();
+        assert_eq!(
+            observable_batch(handle_batch(respond, input)),
+            observable_batch(handle_batch_simd(respond, &mut json)),
+        );
+    }
+}
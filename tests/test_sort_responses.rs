@@ -0,0 +1,82 @@
+extern crate jrpc;
+
+use jrpc::*;
+
+fn ids(responses: &[Response<i64>]) -> Vec<Id> {
+    responses
+        .iter()
+        .map(|response| response.id().clone())
+        .collect()
+}
+
+fn results(responses: &[Response<i64>]) -> Vec<Option<i64>> {
+    responses
+        .iter()
+        .map(|response| match *response {
+            Response::Ok(ref success) => Some(success.result),
+            Response::Err(_) => None,
+        })
+        .collect()
+}
+
+/// Fisher-Yates with a fixed LCG, so the test is reproducible.
+fn shuffle<T>(values: &mut [T], mut seed: u64) {
+    for i in (1..values.len()).rev() {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        values.swap(i, (seed >> 33) as usize % (i + 1));
+    }
+}
+
+fn canonical() -> Vec<Response<i64>> {
+    vec![
+        Response::error(Id::Null, ErrorCode::ParseError, "Parse error", None),
+        Response::success(Id::from(-3), 0),
+        Response::success(Id::from(1), 1),
+        Response::success(Id::from(20), 2),
+        Response::success(Id::from("10"), 3),
+        Response::success(Id::from("9"), 4),
+    ]
+}
+
+#[test]
+fn test_sort_responses_shuffled() {
+    for seed in 0..50 {
+        let mut responses = canonical();
+        shuffle(&mut responses, seed);
+        sort_responses(&mut responses);
+        assert_eq!(ids(&responses), ids(&canonical()), "seed {}", seed);
+        assert_eq!(results(&responses), results(&canonical()));
+    }
+}
+
+#[test]
+fn test_sort_responses_stable() {
+    let mut responses = vec![
+        Response::success(Id::from(2), 0),
+        Response::error(Id::Null, ErrorCode::InvalidRequest, "first", None),
+        Response::success(Id::from(1), 1),
+        Response::success(Id::from(2), 2),
+        Response::error(Id::Null, ErrorCode::ParseError, "second", None),
+        Response::success(Id::from(1), 3),
+    ];
+    sort_responses(&mut responses);
+    assert_eq!(
+        results(&responses),
+        vec![None, None, Some(1), Some(3), Some(0), Some(2)]
+    );
+    match responses[0] {
+        Response::Err(ref error) => assert_eq!(error.error.message, "first"),
+        ref response => panic!("unexpected {:?}", response),
+    }
+}
+
+#[test]
+fn test_id_ord() {
+    assert!(Id::Null < Id::from(i64::MIN));
+    assert!(Id::from(i64::MAX) < Id::from(""));
+    assert!(Id::from(2) < Id::from(10));
+    assert!(Id::from("10") < Id::from("2"));
+    assert_eq!(Id::from(1).cmp(&Id::from(1)), std::cmp::Ordering::Equal);
+}
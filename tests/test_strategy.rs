@@ -0,0 +1,92 @@
+#![cfg(feature = "proptest")]
+extern crate jrpc;
+extern crate proptest;
+extern crate serde_json;
+
+use jrpc::strategy::{self, StrategyConfig};
+use jrpc::*;
+use proptest::prelude::*;
+
+fn request_roundtrip(request: &Request<String, Value>) -> Result<(), TestCaseError> {
+    let json = request.to_string();
+    let parsed = parse_request::<String>(&json)
+        .map_err(|err| TestCaseError::fail(format!("{:?}", err)))?;
+    prop_assert_eq!(&parsed.method, &request.method);
+    prop_assert_eq!(&parsed.id, &request.id);
+    prop_assert_eq!(json, parsed.to_string());
+    Ok(())
+}
+
+fn response_roundtrip(response: &Response<Value>) -> Result<(), TestCaseError> {
+    let json = response.to_string();
+    let parsed: Response<Value> = serde_json::from_str(&json).unwrap();
+    prop_assert_eq!(response.id(), parsed.id());
+    prop_assert_eq!(json, parsed.to_string());
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_id_roundtrip(id: Id) {
+        prop_assert!(id != Id::Null);
+        let json = serde_json::to_string(&id).unwrap();
+        prop_assert_eq!(id, serde_json::from_str::<Id>(&json).unwrap());
+    }
+
+    #[test]
+    fn test_id_req_roundtrip(id in strategy::id_req(StrategyConfig::borderline())) {
+        let request = Request::new(id.clone(), "foo".to_string());
+        let parsed = parse_request::<String>(&request.to_string()).unwrap();
+        prop_assert_eq!(id, parsed.id);
+    }
+
+    #[test]
+    fn test_error_code_roundtrip(code: ErrorCode) {
+        prop_assert!(code.is_valid());
+        let json = serde_json::to_string(&code).unwrap();
+        prop_assert_eq!(code, serde_json::from_str::<ErrorCode>(&json).unwrap());
+    }
+
+    #[test]
+    fn test_error_code_borderline_roundtrip(
+        code in strategy::error_code(StrategyConfig::borderline())
+    ) {
+        let json = serde_json::to_string(&code).unwrap();
+        prop_assert_eq!(code, serde_json::from_str::<ErrorCode>(&json).unwrap());
+    }
+
+    #[test]
+    fn test_error_object_roundtrip(error: ErrorObject<Value>) {
+        let json = serde_json::to_string(&error).unwrap();
+        let parsed: ErrorObject<Value> = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(error.code, parsed.code);
+        prop_assert_eq!(json, serde_json::to_string(&parsed).unwrap());
+    }
+
+    #[test]
+    fn test_request_roundtrip(request: Request<String, Value>) {
+        if let Some(ref params) = request.params {
+            prop_assert!(params.is_array() || params.is_object());
+        }
+        request_roundtrip(&request)?;
+    }
+
+    #[test]
+    fn test_request_borderline_roundtrip(
+        request in strategy::request(StrategyConfig { borderline: true, max_depth: 8 })
+    ) {
+        request_roundtrip(&request)?;
+    }
+
+    #[test]
+    fn test_response_roundtrip(response: Response<Value>) {
+        response_roundtrip(&response)?;
+    }
+
+    #[test]
+    fn test_response_borderline_roundtrip(
+        response in strategy::response(StrategyConfig { borderline: true, max_depth: 8 })
+    ) {
+        response_roundtrip(&response)?;
+    }
+}
@@ -0,0 +1,127 @@
+//! Subscription Notifications pushed by the Server.
+extern crate jrpc;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use jrpc::subscription::{self, SubscriptionId, SubscriptionParams};
+use jrpc::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Log {
+    address: String,
+    topics: Vec<String>,
+    block: Block,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Block {
+    number: u64,
+    hash: String,
+}
+
+fn log() -> Log {
+    Log {
+        address: "0x8320fe7702b96808f7bbc0d4a888ed1468216cfd".into(),
+        topics: vec!["0xd78a0cb8bb633d06981248b816e7bd33c2a35a6089241d099fa519e361cab902".into()],
+        block: Block {
+            number: 436,
+            hash: "0x61cdb2a09ab99abf791d474f20c2ea89bf8de2923a2d42bb49944c8c993cbf04".into(),
+        },
+    }
+}
+
+#[test]
+fn test_subscription_round_trip() {
+    let notification =
+        subscription::notification("eth_subscription", SubscriptionId::from("0xcd0c"), log());
+    let json = serde_json::to_string(&notification).unwrap();
+    jrpc::testing::assert_json_eq(
+        &json,
+        r#"{
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {
+                "subscription": "0xcd0c",
+                "result": {
+                    "address": "0x8320fe7702b96808f7bbc0d4a888ed1468216cfd",
+                    "topics": ["0xd78a0cb8bb633d06981248b816e7bd33c2a35a6089241d099fa519e361cab902"],
+                    "block": {
+                        "number": 436,
+                        "hash": "0x61cdb2a09ab99abf791d474f20c2ea89bf8de2923a2d42bb49944c8c993cbf04"
+                    }
+                }
+            }
+        }"#,
+    );
+
+    let request = parse_request::<String>(&json).unwrap();
+    assert_eq!(request.method, "eth_subscription");
+    let params: SubscriptionParams<Log> = subscription::decode(&request, "eth_subscription")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        params,
+        SubscriptionParams {
+            subscription: SubscriptionId::from("0xcd0c"),
+            result: log(),
+        }
+    );
+}
+
+#[test]
+fn test_subscription_int_id() {
+    let json = r#"{"jsonrpc": "2.0", "method": "update", "params": {"subscription": 7, "result": [1, 2]}}"#;
+    let request = parse_request::<String>(json).unwrap();
+    let params = subscription::decode::<Vec<u32>>(&request, "update")
+        .unwrap()
+        .unwrap();
+    assert_eq!(params.subscription, SubscriptionId::Int(7));
+    assert_eq!(params.result, vec![1, 2]);
+
+    assert!(
+        subscription::decode::<String>(&request, "update")
+            .unwrap()
+            .is_err(),
+        "the result is not a String"
+    );
+}
+
+#[test]
+fn test_subscription_not_recognized() {
+    for json in &[
+        r#"{"jsonrpc": "2.0", "method": "update", "params": {"subscription": 7, "result": 1}, "id": 1}"#,
+        r#"{"jsonrpc": "2.0", "method": "other", "params": {"subscription": 7, "result": 1}}"#,
+    ] {
+        let request = parse_request::<String>(json).unwrap();
+        assert!(
+            subscription::decode::<Value>(&request, "update").is_none(),
+            "{}",
+            json
+        );
+    }
+
+    for json in &[
+        r#"{"jsonrpc": "2.0", "method": "update", "params": {"subscription": 7}}"#,
+        r#"{"jsonrpc": "2.0", "method": "update", "params": [7]}"#,
+        r#"{"jsonrpc": "2.0", "method": "update"}"#,
+        r#"{"jsonrpc": "2.0", "method": "update", "params": {"subscription": 1.5, "result": 1}}"#,
+    ] {
+        let request = parse_request::<String>(json).unwrap();
+        assert!(
+            subscription::decode::<Value>(&request, "update")
+                .unwrap()
+                .is_err(),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn test_subscription_id_is_progress_token() {
+    let id = SubscriptionId::from(7);
+    let token: jrpc::progress::ProgressToken = id.clone();
+    assert_eq!(token, StringOrInt::Int(7));
+    assert_eq!(serde_json::to_string(&id).unwrap(), "7");
+}
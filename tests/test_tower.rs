@@ -0,0 +1,97 @@
+//! The routers as `tower::Service`s, driven with `block_on` instead of a runtime.
+#![cfg(feature = "tower")]
+
+extern crate futures;
+extern crate jrpc;
+extern crate serde_json;
+extern crate tower;
+
+use futures::executor::block_on;
+use jrpc::router::Router;
+use jrpc::*;
+use tower::ServiceExt;
+
+fn router() -> Router<()> {
+    let mut router = Router::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+    router
+}
+
+fn request(json: &str) -> Request<String, Value> {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_service_request() {
+    let service = router().into_service(());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let response = block_on(service.clone().oneshot(request(json))).unwrap().unwrap();
+    assert_eq!(response.to_string(), r#"{"jsonrpc":"2.0","result":19,"id":1}"#);
+
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar", "id": "1"}"#;
+    match block_on(service.clone().oneshot(request(json))).unwrap() {
+        Some(Response::Err(error)) => {
+            assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+            assert_eq!(error.id, Id::from("1"));
+        }
+        other => panic!("expected a MethodNotFound error, got {:?}", other.map(|r| r.to_string())),
+    }
+
+    // notifications have no response, even when they fail
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23]}"#;
+    assert!(block_on(service.clone().oneshot(request(json))).unwrap().is_none());
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar"}"#;
+    assert!(block_on(service.oneshot(request(json))).unwrap().is_none());
+}
+
+#[test]
+fn test_service_json() {
+    let service = router().into_service(());
+    let call = |json: &str| block_on(service.clone().oneshot(json.to_string())).unwrap();
+
+    let reply = call(r#"{"jsonrpc": "2.0", "method": "foobar", "id": "1"}"#).unwrap();
+    testing::assert_json_eq(
+        &reply,
+        r#"{"jsonrpc": "2.0", "error": {"code": -32601, "message": "method `foobar` not found",
+            "data": null}, "id": "1"}"#,
+    );
+    assert_eq!(call(r#"{"jsonrpc": "2.0", "method": "subtract", "params": [1, 2]}"#), None);
+
+    // a payload which can't be parsed is still answered
+    let reply = call(r#"{"jsonrpc": "2.0", "method": "#).unwrap();
+    let error: Error<Value> = serde_json::from_str(&reply).unwrap();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+
+    let reply = call(
+        r#"[
+            {"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1},
+            {"jsonrpc": "2.0", "method": "subtract", "params": [1, 2]}
+        ]"#,
+    );
+    testing::assert_json_eq(&reply.unwrap(), r#"[{"jsonrpc": "2.0", "result": 19, "id": 1}]"#);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn test_async_service() {
+    use futures::future;
+    use jrpc::router::AsyncRouter;
+
+    let mut router = AsyncRouter::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| future::ready(Ok(a - b)));
+    let service = router.into_service(());
+
+    let json = r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#;
+    let response = block_on(service.clone().oneshot(request(json))).unwrap().unwrap();
+    assert_eq!(response.to_string(), r#"{"jsonrpc":"2.0","result":19,"id":1}"#);
+
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar", "id": 2}"#;
+    let reply = block_on(service.clone().oneshot(json.to_string())).unwrap().unwrap();
+    let error: Error<Value> = serde_json::from_str(&reply).unwrap();
+    assert_eq!(error.error.code, ErrorCode::MethodNotFound);
+
+    let json = r#"{"jsonrpc": "2.0", "method": "foobar"}"#;
+    assert!(block_on(service.clone().oneshot(request(json))).unwrap().is_none());
+    assert!(block_on(service.oneshot(json.to_string())).unwrap().is_none());
+}
@@ -0,0 +1,98 @@
+//! The `tracing` events emitted when parsing and dispatching Requests.
+#![cfg(feature = "tracing")]
+
+extern crate jrpc;
+extern crate tracing;
+extern crate tracing_subscriber;
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use jrpc::router::Router;
+use jrpc::*;
+
+/// A writer collecting the formatted events.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run `f`, returning the lines of the events it emitted.
+fn capture<F: FnOnce()>(f: F) -> Vec<String> {
+    let capture = Capture::default();
+    let writer = capture.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(move || writer.clone())
+        .without_time()
+        .finish();
+    tracing::subscriber::with_default(subscriber, f);
+    let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+    output.lines().map(String::from).collect()
+}
+
+#[test]
+fn test_tracing_method_not_found() {
+    let mut router = Router::new();
+    router.register("subtract", |_: &(), (a, b): (i64, i64)| Ok(a - b));
+
+    let lines = capture(|| {
+        let json = r#"{"jsonrpc": "2.0", "method": "foo", "params": [1, 2], "id": 4}"#;
+        router.handle(&(), json).unwrap();
+    });
+    assert_eq!(lines.len(), 2, "{:#?}", lines);
+    assert!(lines[0].contains("DEBUG"), "{}", lines[0]);
+    assert!(
+        lines[0].contains(r#"parsed the request method="foo" id="int" params_len=5"#),
+        "{}",
+        lines[0]
+    );
+    assert!(lines[1].contains("WARN"), "{}", lines[1]);
+    assert!(
+        lines[1].contains(r#"handler{method=foo id="int"}"#),
+        "{}",
+        lines[1]
+    );
+    assert!(
+        lines[1].contains("method `foo` not found code=-32601"),
+        "{}",
+        lines[1]
+    );
+}
+
+#[test]
+fn test_tracing_error_classes() {
+    for &(json, code) in &[
+        ("{", "code=-32700"),
+        (r#"{"jsonrpc": "2.0", "id": 1}"#, "code=-32600"),
+        (r#"{"jsonrpc": "2.0", "method": 1, "id": 1}"#, "code=-32600"),
+        (r#"{"jsonrpc": "2.0", "method": "rpc.foo", "id": 1}"#, "code=-32601"),
+        ("[]", "code=-32600"),
+    ] {
+        let lines = capture(|| {
+            router::handle_batch(
+                |value| {
+                    parse_request_value_with::<String>(value, &ParseOptions::strict())
+                        .err()
+                        .map(Response::Err)
+                },
+                json,
+            );
+        });
+        assert!(
+            lines.iter().any(|l| l.contains("WARN") && l.contains(code)),
+            "{}: {:#?}",
+            json,
+            lines
+        );
+    }
+}
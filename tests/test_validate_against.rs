@@ -0,0 +1,80 @@
+extern crate jrpc;
+
+use jrpc::*;
+
+fn error(id: Id, code: ErrorCode) -> Response<Value> {
+    Response::error(id, code, "failed", None)
+}
+
+#[test]
+fn test_validate_against_matching_id() {
+    let request = Request::new(Id::from("a"), "subtract".to_string());
+    assert_eq!(
+        Response::success(Id::from("a"), 1).validate_against(&request),
+        Ok(())
+    );
+    let response = error(Id::from("a"), ErrorCode::InvalidParams);
+    assert_eq!(response.validate_against(&request), Ok(()));
+
+    let request = Request::new(Id::Null, "subtract".to_string());
+    assert_eq!(
+        Response::success(Id::Null, 1).validate_against(&request),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_validate_against_wrong_id() {
+    let request = Request::new(Id::from(4), "subtract".to_string());
+    let mismatch = Response::success(Id::from("4"), 1)
+        .validate_against(&request)
+        .unwrap_err();
+    assert_eq!(
+        mismatch,
+        IdMismatch {
+            request: IdReq::Int(4),
+            response: Id::from("4"),
+        }
+    );
+    assert_eq!(
+        mismatch.to_string(),
+        r#"the Response id "4" doesn't match the Request id 4"#
+    );
+
+    let response = error(Id::from(5), ErrorCode::ParseError);
+    assert!(response.validate_against(&request).is_err());
+}
+
+#[test]
+fn test_validate_against_notification() {
+    let request = Request::with_params(IdReq::Notification, "update".to_string(), [1]);
+    let mismatch = Response::success(Id::from(1), 1)
+        .validate_against(&request)
+        .unwrap_err();
+    assert_eq!(mismatch.request, IdReq::Notification);
+    assert_eq!(
+        mismatch.to_string(),
+        "the Response with the id 1 was received for a Notification"
+    );
+
+    // even an error about an undetected id
+    let response = error(Id::Null, ErrorCode::ParseError);
+    assert!(response.validate_against(&request).is_err());
+}
+
+#[test]
+fn test_validate_against_null_id() {
+    let request = Request::new(Id::from(4), "subtract".to_string());
+    for code in &[ErrorCode::ParseError, ErrorCode::InvalidRequest] {
+        assert_eq!(error(Id::Null, *code).validate_against(&request), Ok(()));
+    }
+    for code in &[ErrorCode::MethodNotFound, ErrorCode::ServerError(-32000)] {
+        let mismatch = error(Id::Null, *code)
+            .validate_against(&request)
+            .unwrap_err();
+        assert_eq!(mismatch.response, Id::Null);
+    }
+    assert!(Response::success(Id::Null, 1)
+        .validate_against(&request)
+        .is_err());
+}
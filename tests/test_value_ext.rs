@@ -0,0 +1,63 @@
+//! Converting already parsed json `Value`s with `JrpcValueExt`.
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use jrpc::*;
+
+#[test]
+fn test_value_into_request_error_codes() {
+    let cases = vec![
+        (json!(1), ErrorCode::InvalidRequest),
+        (json!({"jsonrpc": "1.0", "method": "foo", "id": 1}), ErrorCode::InvalidRequest),
+        (json!({"jsonrpc": "2.0", "method": 1, "id": 1}), ErrorCode::InvalidRequest),
+    ];
+    for (value, code) in cases {
+        let json = value.to_string();
+        let expected = parse_request::<String>(&json).unwrap_err();
+        let error = value.into_request::<String>().unwrap_err();
+        assert_eq!(error.error.code, code, "{}", json);
+        assert_eq!(error.to_string(), expected.to_string(), "same as parse_request");
+    }
+
+    let value = json!({"jsonrpc": "2.0", "method": "update", "params": [1, 2]});
+    let request = value.into_request::<String>().unwrap();
+    assert_eq!(request.id, IdReq::Notification);
+    assert_eq!(request.params, Some(json!([1, 2])));
+}
+
+#[test]
+fn test_value_into_response() {
+    let response = json!({"jsonrpc": "2.0", "result": ["hello", 5], "id": "9"})
+        .into_response::<(String, u32)>()
+        .unwrap();
+    match response {
+        Response::Ok(success) => assert_eq!(success.result, ("hello".to_string(), 5)),
+        Response::Err(error) => panic!("unexpected {:?}", error),
+    }
+
+    let error = json!({"jsonrpc": "2.0", "result": "19", "id": 3})
+        .into_response::<i64>()
+        .unwrap_err();
+    assert_eq!(error.id, Id::from(3));
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+
+    let error = json!({"jsonrpc": "2.0", "id": 3}).into_response::<Value>().unwrap_err();
+    assert_eq!(error.error.code, ErrorCode::ParseError);
+}
+
+#[test]
+fn test_value_kind() {
+    let cases = vec![
+        (json!({"jsonrpc": "2.0", "method": "a", "id": null}), MessageKind::Request),
+        (json!({"jsonrpc": "2.0", "method": "a"}), MessageKind::Notification),
+        (json!({"jsonrpc": "2.0", "result": null, "id": 1}), MessageKind::Success),
+        (json!({"jsonrpc": "2.0", "error": {}, "id": 1}), MessageKind::Error),
+        (json!([]), MessageKind::Batch),
+        (json!({"jsonrpc": "2.0", "id": 1}), MessageKind::Invalid),
+        (json!("2.0"), MessageKind::Invalid),
+    ];
+    for (value, kind) in cases {
+        assert_eq!(value.kind(), kind, "{}", value);
+    }
+}
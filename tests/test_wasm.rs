@@ -0,0 +1,96 @@
+//! Run with `wasm-pack test --node -- --features wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+extern crate jrpc;
+extern crate js_sys;
+extern crate serde_json;
+extern crate wasm_bindgen;
+extern crate wasm_bindgen_test;
+
+use jrpc::*;
+use js_sys::{Reflect, JSON};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+/// Build a `JsValue` the way a browser would.
+fn js(json: &str) -> JsValue {
+    JSON::parse(json).unwrap()
+}
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().into()
+}
+
+fn has(value: &JsValue, key: &str) -> bool {
+    Reflect::has(value, &JsValue::from(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_request_roundtrip() {
+    let json = r#"{"jsonrpc":"2.0","method":"subtract","params":{"minuend":42,"subtrahend":23},"id":1}"#;
+    let request = Request::<String, Value>::from_js(&js(json)).unwrap();
+    assert_eq!(request.method, "subtract");
+    assert_eq!(request.id, IdReq::Int(1));
+
+    let value = request.to_js().unwrap();
+    assert!(value.is_object());
+    testing::assert_json_eq(&stringify(&value), json);
+}
+
+#[wasm_bindgen_test]
+fn test_notification() {
+    let request = Request::<String, Value>::from_js(&js(r#"{"jsonrpc":"2.0","method":"update"}"#))
+        .unwrap();
+    assert_eq!(request.id, IdReq::Notification);
+    let value = request.to_js().unwrap();
+    assert!(!has(&value, "id"), "{}", stringify(&value));
+
+    // a null id is not a notification
+    let request =
+        Request::<String, Value>::from_js(&js(r#"{"jsonrpc":"2.0","method":"update","id":null}"#))
+            .unwrap();
+    assert_eq!(request.id, IdReq::Null);
+    let value = request.to_js().unwrap();
+    assert!(has(&value, "id"));
+    assert!(Reflect::get(&value, &JsValue::from("id")).unwrap().is_null());
+}
+
+#[wasm_bindgen_test]
+fn test_response_nested_result() {
+    let json = r#"{
+        "jsonrpc": "2.0",
+        "result": {"pet": {"name": "fido", "tags": ["dog", {"age": 3}]}, "ok": true},
+        "id": "9"
+    }"#;
+    let response = Response::<Value>::from_js(&js(json)).unwrap();
+    match response {
+        Response::Ok(ref success) => {
+            assert_eq!(success.id, Id::from("9"));
+            assert_eq!(success.result["pet"]["tags"][1]["age"], 3);
+        }
+        Response::Err(_) => panic!("expected success"),
+    }
+
+    // nested objects are plain Objects, not Maps
+    let value = response.to_js().unwrap();
+    let result = Reflect::get(&value, &JsValue::from("result")).unwrap();
+    assert!(has(&Reflect::get(&result, &JsValue::from("pet")).unwrap(), "name"));
+    testing::assert_json_eq(&stringify(&value), json);
+}
+
+#[wasm_bindgen_test]
+fn test_response_error() {
+    let json = r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found","data":null},"id":"5"}"#;
+    let response = Response::<Value>::from_js(&js(json)).unwrap();
+    match response {
+        Response::Err(ref error) => assert_eq!(error.error.code, ErrorCode::MethodNotFound),
+        Response::Ok(_) => panic!("expected error"),
+    }
+    testing::assert_json_eq(&stringify(&response.to_js().unwrap()), json);
+}
+
+#[wasm_bindgen_test]
+fn test_invalid() {
+    let json = r#"{"jsonrpc":"1.0","method":"update"}"#;
+    assert!(Request::<String, Value>::from_js(&js(json)).is_err());
+    assert!(Response::<Value>::from_js(&js(r#"{"id":1}"#)).is_err());
+}
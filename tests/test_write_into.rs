@@ -0,0 +1,75 @@
+//! Merging the members of the messages into the envelope Object of a transport.
+extern crate jrpc;
+#[macro_use]
+extern crate serde_json;
+
+use jrpc::*;
+
+fn envelope() -> serde_json::Map<String, Value> {
+    let mut envelope = serde_json::Map::new();
+    envelope.insert("channel".into(), Value::from("rpc"));
+    envelope.insert("seq".into(), Value::from(9));
+    // overwritten
+    envelope.insert("id".into(), Value::from("stale"));
+    envelope
+}
+
+/// Take the members of the message back out of the envelope.
+fn extract(mut envelope: serde_json::Map<String, Value>) -> Value {
+    assert_eq!(envelope.remove("channel"), Some(Value::from("rpc")));
+    assert_eq!(envelope.remove("seq"), Some(Value::from(9)));
+    Value::Object(envelope)
+}
+
+#[test]
+fn test_write_request_into() {
+    let requests = vec![
+        Request::with_params(Id::from(1), "sum".to_string(), json!([1, 2])),
+        Request {
+            jsonrpc: V2_0,
+            method: "ping".to_string(),
+            params: None,
+            id: IdReq::from(Id::from("a")),
+        },
+    ];
+    for request in requests {
+        let mut map = envelope();
+        request.write_into(&mut map).unwrap();
+        let parsed: Request<String, Value> = serde_json::from_value(extract(map)).unwrap();
+        assert_eq!(parsed.method, request.method);
+        assert_eq!(parsed.params, request.params);
+        assert_eq!(parsed.id, request.id);
+    }
+}
+
+#[test]
+fn test_write_notification_into() {
+    let notification = Request::new(IdReq::Notification, "ping".to_string());
+    let mut map = serde_json::Map::new();
+    notification.write_into(&mut map).unwrap();
+    assert_eq!(Value::Object(map), json!({"jsonrpc": "2.0", "method": "ping"}));
+
+    // the `id` of the envelope is kept, since a Notification has none
+    let mut map = envelope();
+    notification.write_into(&mut map).unwrap();
+    assert_eq!(map["id"], "stale");
+}
+
+#[test]
+fn test_write_response_into() {
+    let success = Success {
+        jsonrpc: V2_0,
+        result: 19,
+        id: Id::from(1),
+    };
+    let mut map = envelope();
+    success.write_into(&mut map).unwrap();
+    let parsed: Success<i64> = serde_json::from_value(extract(map)).unwrap();
+    assert_eq!(parsed.to_string(), success.to_string());
+
+    let error: Error<Value> = Error::new(Id::Null, ErrorCode::ParseError, "oops", None);
+    let mut map = envelope();
+    error.write_into(&mut map).unwrap();
+    let parsed: Error<Value> = serde_json::from_value(extract(map)).unwrap();
+    assert_eq!(parsed.to_string(), error.to_string());
+}